@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE people
+DROP COLUMN IF EXISTS version;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE people
+ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
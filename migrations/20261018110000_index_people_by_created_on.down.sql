@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS people_created_on_id_idx;
+
+ALTER TABLE people
+ALTER COLUMN created_on TYPE TIMESTAMP USING created_on AT TIME ZONE 'UTC';
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE people
+ALTER COLUMN created_on TYPE TIMESTAMPTZ USING created_on AT TIME ZONE 'UTC';
+
+CREATE INDEX IF NOT EXISTS people_created_on_id_idx ON people (created_on, id);
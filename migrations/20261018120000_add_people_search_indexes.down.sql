@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS people_full_name_tsv_idx;
+DROP INDEX IF EXISTS people_full_name_trgm_idx;
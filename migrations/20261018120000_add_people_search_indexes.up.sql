@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS people_full_name_trgm_idx
+ON people USING GIN ((first_name || ' ' || last_name) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS people_full_name_tsv_idx
+ON people USING GIN (to_tsvector('simple', first_name || ' ' || last_name));
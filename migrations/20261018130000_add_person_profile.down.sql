@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE people
+DROP COLUMN IF EXISTS email,
+DROP COLUMN IF EXISTS phone,
+DROP COLUMN IF EXISTS birth_date,
+DROP COLUMN IF EXISTS addresses;
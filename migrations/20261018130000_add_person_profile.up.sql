@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE people
+ADD COLUMN email VARCHAR(254) NULL,
+ADD COLUMN phone VARCHAR(16) NULL,
+ADD COLUMN birth_date DATE NULL,
+ADD COLUMN addresses TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,9 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS relationships (
+    PERSON_ID VARCHAR(36) NOT NULL REFERENCES people,
+    RELATED_ID VARCHAR(36) NOT NULL REFERENCES people,
+    KIND VARCHAR(16) NOT NULL,
+    CREATED_ON TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (PERSON_ID, RELATED_ID),
+    CHECK (PERSON_ID <> RELATED_ID)
+);
@@ -0,0 +1,6 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS merges (
+    SOURCE_ID VARCHAR(36) PRIMARY KEY,
+    TARGET_ID VARCHAR(36) NOT NULL,
+    MERGED_AT TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS audit_log (
+    ID VARCHAR(36) PRIMARY KEY,
+    ENTITY VARCHAR(16) NOT NULL,
+    ENTITY_ID VARCHAR(36) NOT NULL,
+    ACTION VARCHAR(16) NOT NULL,
+    ACTOR VARCHAR(36),
+    REQUEST_ID VARCHAR(255) NOT NULL,
+    AT TIMESTAMPTZ NOT NULL,
+    BEFORE TEXT,
+    AFTER TEXT
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_entity_id ON audit_log (ENTITY_ID);
+CREATE INDEX IF NOT EXISTS audit_log_at ON audit_log (AT);
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS person_history (
+    REVISION BIGSERIAL PRIMARY KEY,
+    ID VARCHAR(36) NOT NULL REFERENCES people,
+    FIRST_NAME VARCHAR(40) NOT NULL,
+    LAST_NAME VARCHAR(40) NOT NULL,
+    CREATED_ON TIMESTAMPTZ NOT NULL,
+    VERSION INTEGER NOT NULL,
+    DELETED_AT TIMESTAMPTZ NULL,
+    EMAIL VARCHAR(254) NULL,
+    PHONE VARCHAR(16) NULL,
+    BIRTH_DATE DATE NULL,
+    ADDRESSES TEXT NOT NULL DEFAULT '[]',
+    SAVED_AT TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS person_history_id_saved_at_idx ON person_history (ID, SAVED_AT);
+
+-- the people saved before there was a history start it as they are now.
+INSERT INTO person_history (ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, SAVED_AT)
+SELECT ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, CREATED_ON FROM people;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS outbox (
+    ID BIGSERIAL PRIMARY KEY,
+    KIND VARCHAR(32) NOT NULL,
+    AGGREGATE_ID VARCHAR(36) NOT NULL,
+    PAYLOAD TEXT NOT NULL,
+    OCCURRED_AT TIMESTAMPTZ NOT NULL,
+    SENT_AT TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS outbox_pending_idx ON outbox (ID) WHERE SENT_AT IS NULL;
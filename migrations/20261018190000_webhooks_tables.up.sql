@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS webhooks (
+    ID VARCHAR(36) PRIMARY KEY,
+    URL TEXT NOT NULL,
+    SECRET TEXT NOT NULL,
+    EVENTS TEXT NOT NULL,
+    CREATED_AT TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    ID VARCHAR(36) PRIMARY KEY,
+    WEBHOOK_ID VARCHAR(36) NOT NULL REFERENCES webhooks ON DELETE CASCADE,
+    EVENT_ID BIGINT NOT NULL,
+    KIND VARCHAR(32) NOT NULL,
+    PAYLOAD TEXT NOT NULL,
+    STATUS VARCHAR(16) NOT NULL,
+    RESPONSE_STATUS INTEGER,
+    ERROR TEXT,
+    DELIVERED_AT TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_id ON webhook_deliveries (WEBHOOK_ID, DELIVERED_AT);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS people_name_key_idx;
+ALTER TABLE people DROP COLUMN NAME_KEY;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- the soundex of the first and last names once their accents are removed,
+-- possible duplicates share it. the people saved before it are given theirs
+-- when the store starts.
+ALTER TABLE people ADD COLUMN NAME_KEY TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS people_name_key_idx ON people (NAME_KEY);
@@ -0,0 +1,7 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS pets (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(40) NOT NULL,
+    person_id VARCHAR(36) REFERENCES people,
+    created_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
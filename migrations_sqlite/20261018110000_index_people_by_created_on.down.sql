@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS people_created_on_id_idx;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- rows created with the CURRENT_TIMESTAMP default use another text format,
+-- cursors compare timestamps as RFC 3339 text.
+UPDATE people
+SET created_on = strftime('%Y-%m-%dT%H:%M:%S+00:00', created_on)
+WHERE created_on NOT LIKE '%T%';
+
+CREATE INDEX IF NOT EXISTS people_created_on_id_idx ON people (created_on, id);
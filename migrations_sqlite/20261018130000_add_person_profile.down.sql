@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE people DROP COLUMN addresses;
+ALTER TABLE people DROP COLUMN birth_date;
+ALTER TABLE people DROP COLUMN phone;
+ALTER TABLE people DROP COLUMN email;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE people ADD COLUMN email VARCHAR(254) NULL;
+ALTER TABLE people ADD COLUMN phone VARCHAR(16) NULL;
+ALTER TABLE people ADD COLUMN birth_date DATE NULL;
+ALTER TABLE people ADD COLUMN addresses TEXT NOT NULL DEFAULT '[]';
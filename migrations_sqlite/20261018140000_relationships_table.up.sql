@@ -0,0 +1,9 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS relationships (
+    person_id VARCHAR(36) NOT NULL REFERENCES people,
+    related_id VARCHAR(36) NOT NULL REFERENCES people,
+    kind VARCHAR(16) NOT NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (person_id, related_id),
+    CHECK (person_id <> related_id)
+);
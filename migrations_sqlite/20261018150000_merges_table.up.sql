@@ -0,0 +1,6 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS merges (
+    source_id VARCHAR(36) PRIMARY KEY,
+    target_id VARCHAR(36) NOT NULL,
+    merged_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS audit_log (
+    id VARCHAR(36) PRIMARY KEY,
+    entity VARCHAR(16) NOT NULL,
+    entity_id VARCHAR(36) NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    actor VARCHAR(36),
+    request_id VARCHAR(255) NOT NULL,
+    at TIMESTAMP NOT NULL,
+    before TEXT,
+    after TEXT
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_entity_id ON audit_log (entity_id);
+CREATE INDEX IF NOT EXISTS audit_log_at ON audit_log (at);
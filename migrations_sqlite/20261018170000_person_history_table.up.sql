@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS person_history (
+    revision INTEGER PRIMARY KEY AUTOINCREMENT,
+    id VARCHAR(36) NOT NULL REFERENCES people,
+    first_name VARCHAR(40) NOT NULL,
+    last_name VARCHAR(40) NOT NULL,
+    created_on TIMESTAMP NOT NULL,
+    version INTEGER NOT NULL,
+    deleted_at TIMESTAMP NULL,
+    email VARCHAR(254) NULL,
+    phone VARCHAR(16) NULL,
+    birth_date DATE NULL,
+    addresses TEXT NOT NULL DEFAULT '[]',
+    saved_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS person_history_id_saved_at_idx ON person_history (id, saved_at);
+
+-- the people saved before there was a history start it as they are now.
+INSERT INTO person_history (id, first_name, last_name, created_on, version, deleted_at, email, phone, birth_date, addresses, saved_at)
+SELECT id, first_name, last_name, created_on, version, deleted_at, email, phone, birth_date, addresses, created_on FROM people;
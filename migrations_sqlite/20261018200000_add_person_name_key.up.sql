@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- the soundex of the first and last names, possible duplicates share it.
+-- the people saved before it are given theirs when the store starts.
+ALTER TABLE people ADD COLUMN name_key TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS people_name_key_idx ON people (name_key);
@@ -1,3 +1,7 @@
+use crate::people::handler::MAX_RETENTION_DAYS;
+use crate::types::people::{PersonID, PersonMatch};
+use crate::types::pets::Pet;
+use crate::types::query::MAX_GRAPH_DEPTH;
 use std::{fmt, fmt::Display, fmt::Formatter, num::ParseIntError};
 use warp::reject::Reject;
 use warp::{
@@ -10,15 +14,68 @@ use warp::{
 pub enum Error {
     ParseError(ParseIntError),
     MissingParameters,
+    InvalidCursor,
+    OffsetNotSupported,
+    InvalidSort,
+    InvalidExpand,
+    InvalidDeletePolicy,
+    InvalidMatchAlgorithm,
+    InvalidBatch,
+    BatchTooLarge,
+    InvalidCsv(String),
+    InvalidProfile(String),
+    InvalidRelationship(String),
+    InvalidGraphDepth,
+    InvalidRetentionDays,
+    InvalidMerge(String),
+    InvalidAuditQuery(String),
+    InvalidAsOf(String),
+    UnknownEventKind(String),
+    InvalidWebhook(String),
+    InvalidLastEventId(String),
+    InvalidToken,
     PersonNotFound,
+    PetNotFound,
+    RelationshipNotFound,
+    WebhookNotFound,
+    WebhookDeliveryNotFound,
+    RelationshipExists,
+    PersonVersionConflict,
+    PersonNotDeleted,
+    PersonMerged(PersonID),
+    PersonHasPets(Vec<Pet>),
+    PossibleDuplicates(Vec<PersonMatch>),
+    PreconditionFailed,
+    PreconditionRequired,
+    DatabaseConnectionError,
     DatabaseQueryError,
     DatabaseUniqueError,
     GetPeopleError,
     GetPersonError,
+    SearchPeopleError,
     CreatePersonError,
     UpdatePersonError,
     DeletePersonError,
+    RestorePersonError,
+    PurgePeopleError,
     AddPetError,
+    GetPetsError,
+    GetPetError,
+    UpdatePetError,
+    DeletePetError,
+    AddRelationshipError,
+    GetRelationshipsError,
+    DeleteRelationshipError,
+    MergePeopleError,
+    GetAuditError,
+    GetPersonHistoryError,
+    PublishEventError(String),
+    AddWebhookError,
+    GetWebhooksError,
+    DeleteWebhookError,
+    GetWebhookDeliveriesError,
+    RedeliverWebhookError,
+    GetEventsError,
     CreateAccountError,
     GetAccountError,
     AccountNotFound,
@@ -37,11 +94,78 @@ impl Display for Error {
         match *self {
             Error::ParseError(ref err) => write!(f, "Cannot parse parameter: {err}"),
             Error::MissingParameters => write!(f, "Missing parameter"),
+            Error::InvalidCursor => write!(f, "Invalid pagination cursor"),
+            Error::OffsetNotSupported => {
+                write!(
+                    f,
+                    "Offset is not supported, use the cursor of the previous page"
+                )
+            }
+            Error::InvalidSort => write!(f, "Invalid sort field or order"),
+            Error::InvalidExpand => write!(f, "Invalid expand, only pets can be expanded"),
+            Error::InvalidDeletePolicy => {
+                write!(f, "Invalid delete policy, use refuse, cascade or orphan")
+            }
+            Error::InvalidMatchAlgorithm => write!(
+                f,
+                "Invalid match algorithm, use exact, jaro_winkler or phonetic"
+            ),
+            Error::InvalidBatch => write!(f, "Invalid batch of people"),
+            Error::BatchTooLarge => write!(f, "Too many people in the batch"),
+            Error::InvalidCsv(ref err) => write!(f, "Invalid CSV: {err}"),
+            Error::InvalidProfile(ref err) => write!(f, "Invalid person: {err}"),
+            Error::InvalidRelationship(ref err) => write!(f, "Invalid relationship: {err}"),
+            Error::InvalidGraphDepth => write!(
+                f,
+                "Invalid depth, it must be between 0 and {MAX_GRAPH_DEPTH}"
+            ),
+            Error::InvalidRetentionDays => write!(
+                f,
+                "Invalid retention_days, it must be between 0 and {MAX_RETENTION_DAYS}"
+            ),
+            Error::InvalidMerge(ref err) => write!(f, "Invalid merge: {err}"),
+            Error::InvalidAuditQuery(ref err) => write!(f, "Invalid audit query: {err}"),
+            Error::InvalidAsOf(ref as_of) => {
+                write!(f, "Invalid as_of: {as_of} is not an RFC 3339 time")
+            }
+            Error::UnknownEventKind(ref kind) => write!(f, "Unknown event kind: {kind}"),
+            Error::InvalidWebhook(ref err) => write!(f, "Invalid webhook: {err}"),
+            Error::InvalidLastEventId(ref id) => write!(f, "Invalid Last-Event-ID {id}"),
+            Error::InvalidToken => write!(f, "Invalid or expired token"),
             Error::PersonNotFound => write!(f, "Person not found"),
+            Error::PetNotFound => write!(f, "Pet not found"),
+            Error::RelationshipNotFound => write!(f, "Relationship not found"),
+            Error::WebhookNotFound => write!(f, "Webhook not found"),
+            Error::WebhookDeliveryNotFound => write!(f, "Webhook delivery not found"),
+            Error::RelationshipExists => write!(f, "People are already related"),
+            Error::PersonVersionConflict => write!(f, "Person was changed by someone else"),
+            Error::PersonNotDeleted => write!(f, "Person is not deleted"),
+            Error::PersonMerged(ref target_id) => write!(f, "Person was merged into {target_id}"),
+            Error::PersonHasPets(ref pets) => write!(
+                f,
+                "Person has pets: {}",
+                pets.iter()
+                    .map(|pet| pet.id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Error::PossibleDuplicates(ref candidates) => write!(
+                f,
+                "Person may already exist: {}",
+                candidates
+                    .iter()
+                    .map(|candidate| candidate.person.id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Error::PreconditionFailed => write!(f, "Person does not match the given version"),
+            Error::PreconditionRequired => write!(f, "Person version is required"),
+            Error::DatabaseConnectionError => write!(f, "Cannot connect to the database"),
             Error::DatabaseQueryError => write!(f, "Cannot update, invalid data"),
             Error::DatabaseUniqueError => write!(f, "Cannot create, duplicate data"),
             Error::GetPeopleError => write!(f, "Unable to get people"),
             Error::GetPersonError => write!(f, "Unable to get person"),
+            Error::SearchPeopleError => write!(f, "Unable to search people"),
             Error::CreatePersonError => write!(f, "Unable to create person"),
             Error::CreateAccountError => write!(f, "Unable to create account"),
             Error::GetAccountError => write!(f, "Unable to get account"),
@@ -51,7 +175,26 @@ impl Display for Error {
             Error::DuplicateAccountError => write!(f, "Account already exists"),
             Error::UpdatePersonError => write!(f, "Unable to update person"),
             Error::DeletePersonError => write!(f, "Unable to delete person"),
+            Error::RestorePersonError => write!(f, "Unable to restore person"),
+            Error::PurgePeopleError => write!(f, "Unable to purge people"),
             Error::AddPetError => write!(f, "Unable to add pet"),
+            Error::GetPetsError => write!(f, "Unable to get pets"),
+            Error::GetPetError => write!(f, "Unable to get pet"),
+            Error::UpdatePetError => write!(f, "Unable to update pet"),
+            Error::DeletePetError => write!(f, "Unable to delete pet"),
+            Error::AddRelationshipError => write!(f, "Unable to add relationship"),
+            Error::GetRelationshipsError => write!(f, "Unable to get relationships"),
+            Error::DeleteRelationshipError => write!(f, "Unable to delete relationship"),
+            Error::MergePeopleError => write!(f, "Unable to merge people"),
+            Error::GetAuditError => write!(f, "Unable to get audit log"),
+            Error::GetPersonHistoryError => write!(f, "Unable to get person history"),
+            Error::PublishEventError(ref err) => write!(f, "Unable to publish event: {err}"),
+            Error::AddWebhookError => write!(f, "Unable to add webhook"),
+            Error::GetWebhooksError => write!(f, "Unable to get webhooks"),
+            Error::DeleteWebhookError => write!(f, "Unable to delete webhook"),
+            Error::GetWebhookDeliveriesError => write!(f, "Unable to get webhook deliveries"),
+            Error::RedeliverWebhookError => write!(f, "Unable to redeliver webhook"),
+            Error::GetEventsError => write!(f, "Unable to get events"),
             Error::ValidateBadWordsError => write!(f, "cannot validate bad words"),
             Error::ClientError(ref err) => write!(f, "External Server error: {}", err),
             Error::ServerError(ref err) => write!(f, "External Server error: {}", err),
@@ -92,6 +235,171 @@ pub async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
             "Cannot get person".to_string(),
             StatusCode::UNPROCESSABLE_ENTITY,
         ))
+    } else if let Some(Error::SearchPeopleError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot search people".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::MissingParameters) = r.find() {
+        Ok(warp::reply::with_status(
+            "Missing parameter".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::InvalidCursor) = r.find() {
+        Ok(warp::reply::with_status(
+            "Invalid pagination cursor".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::OffsetNotSupported) = r.find() {
+        Ok(warp::reply::with_status(
+            "Offset is not supported, use the cursor of the previous page".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::InvalidSort) = r.find() {
+        Ok(warp::reply::with_status(
+            "Invalid sort field or order".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::InvalidExpand) = r.find() {
+        Ok(warp::reply::with_status(
+            "Invalid expand, only pets can be expanded".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::InvalidBatch) = r.find() {
+        Ok(warp::reply::with_status(
+            "Invalid batch of people".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::InvalidProfile(err)) = r.find() {
+        Ok(warp::reply::with_status(
+            format!("Invalid person: {err}"),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidRelationship(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidGraphDepth) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidRetentionDays) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidMerge(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidAuditQuery(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidAsOf(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidWebhook(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidLastEventId(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(err @ Error::InvalidToken) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else if let Some(Error::InvalidCsv(err)) = r.find() {
+        Ok(warp::reply::with_status(
+            format!("Invalid CSV: {err}"),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::BatchTooLarge) = r.find() {
+        Ok(warp::reply::with_status(
+            "Too many people in the batch".to_string(),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ))
+    } else if let Some(Error::PersonNotFound) = r.find() {
+        Ok(warp::reply::with_status(
+            "Person not found".to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(Error::PetNotFound) = r.find() {
+        Ok(warp::reply::with_status(
+            "Pet not found".to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(Error::RelationshipNotFound) = r.find() {
+        Ok(warp::reply::with_status(
+            "Relationship not found".to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(err @ Error::WebhookNotFound) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(err @ Error::WebhookDeliveryNotFound) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(Error::RelationshipExists) = r.find() {
+        Ok(warp::reply::with_status(
+            "People are already related".to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(Error::PersonVersionConflict) = r.find() {
+        Ok(warp::reply::with_status(
+            "Person was changed by someone else".to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(err @ Error::PersonNotDeleted) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(err @ Error::PersonMerged(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(err @ Error::PersonHasPets(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(err @ Error::PossibleDuplicates(_)) = r.find() {
+        Ok(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(Error::InvalidDeletePolicy) = r.find() {
+        Ok(warp::reply::with_status(
+            "Invalid delete policy, use refuse, cascade or orphan".to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(Error::PreconditionFailed) = r.find() {
+        Ok(warp::reply::with_status(
+            "Person does not match If-Match".to_string(),
+            StatusCode::PRECONDITION_FAILED,
+        ))
+    } else if let Some(Error::PreconditionRequired) = r.find() {
+        Ok(warp::reply::with_status(
+            "If-Match header is required".to_string(),
+            StatusCode::PRECONDITION_REQUIRED,
+        ))
     } else if let Some(Error::CreatePersonError) = r.find() {
         Ok(warp::reply::with_status(
             "Cannot create person".to_string(),
@@ -127,11 +435,101 @@ pub async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
             "Cannot delete person".to_string(),
             StatusCode::UNPROCESSABLE_ENTITY,
         ))
+    } else if let Some(Error::RestorePersonError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot restore person".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::PurgePeopleError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot purge people".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
     } else if let Some(Error::AddPetError) = r.find() {
         Ok(warp::reply::with_status(
             "Cannot add pet".to_string(),
             StatusCode::UNPROCESSABLE_ENTITY,
         ))
+    } else if let Some(Error::GetPetsError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get pets".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetPetError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get pet".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::UpdatePetError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot update pet".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::DeletePetError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot delete pet".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::AddRelationshipError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot add relationship".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetRelationshipsError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get relationships".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::DeleteRelationshipError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot delete relationship".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::MergePeopleError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot merge people".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetAuditError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get audit log".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetPersonHistoryError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get person history".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::AddWebhookError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot add webhook".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetWebhooksError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get webhooks".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::DeleteWebhookError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot delete webhook".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetWebhookDeliveriesError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get webhook deliveries".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::RedeliverWebhookError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot redeliver webhook".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
+    } else if let Some(Error::GetEventsError) = r.find() {
+        Ok(warp::reply::with_status(
+            "Cannot get events".to_string(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ))
     } else if let Some(error) = r.find::<Error>() {
         Ok(warp::reply::with_status(
             error.to_string(),
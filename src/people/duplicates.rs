@@ -0,0 +1,414 @@
+use crate::errors::error;
+use crate::storage::search::full_name;
+use crate::types::people::{Person, PersonMatch};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// The score two names need to be duplicates when no threshold is configured
+pub const DEFAULT_THRESHOLD: f32 = 0.92;
+/// The most possible duplicates returned for a new person
+pub const MAX_CANDIDATES: usize = 10;
+/// How many saved people the stores read at once when looking for duplicates
+pub const CANDIDATES_PAGE: i32 = 1000;
+/// The longest common prefix Jaro-Winkler gives a bonus to
+const WINKLER_PREFIX: usize = 4;
+/// How much every letter of the common prefix raises the Jaro-Winkler score
+const WINKLER_SCALING: f32 = 0.1;
+
+/// MatchAlgorithm is how the name of a new person is compared to the names of
+/// the people already saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchAlgorithm {
+    /// The first and last names are the same once normalized
+    Exact,
+    /// The names are spelled alike, typos and transposed letters included
+    #[default]
+    JaroWinkler,
+    /// The names sound alike in English, like Smith and Smyth
+    Phonetic,
+}
+
+/// DuplicateDetection tells when a new person is likely someone already saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateDetection {
+    pub algorithm: MatchAlgorithm,
+    /// The lowest score, from 0 to 1, for two names to be duplicates
+    pub threshold: f32,
+}
+
+impl Default for DuplicateDetection {
+    fn default() -> Self {
+        DuplicateDetection {
+            algorithm: MatchAlgorithm::default(),
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+impl DuplicateDetection {
+    /// score tells how much the full names of both people look alike, from 0
+    /// to 1.
+    pub fn score(&self, person: &Person, other: &Person) -> f32 {
+        let name = normalize(&full_name(person));
+        let other_name = normalize(&full_name(other));
+
+        match self.algorithm {
+            // the names are compared one by one, so exact duplicates always
+            // share their name key.
+            MatchAlgorithm::Exact => {
+                let same = normalize(&person.first_name) == normalize(&other.first_name)
+                    && normalize(&person.last_name) == normalize(&other.last_name);
+                match same {
+                    true => 1.0,
+                    false => 0.0,
+                }
+            }
+            MatchAlgorithm::JaroWinkler => jaro_winkler(&name, &other_name),
+            MatchAlgorithm::Phonetic => phonetic_similarity(&name, &other_name),
+        }
+    }
+
+    /// candidate returns the other person as a possible duplicate of the
+    /// person when their score reaches the threshold.
+    pub fn candidate(&self, person: &Person, other: &Person) -> Option<PersonMatch> {
+        if person.id == other.id {
+            return None;
+        }
+
+        let score = self.score(person, other);
+        (score >= self.threshold).then(|| PersonMatch {
+            person: other.clone(),
+            score,
+        })
+    }
+
+    /// matches returns the candidates who are possible duplicates of the
+    /// person, the best matches first.
+    pub fn matches(
+        &self,
+        person: &Person,
+        candidates: impl IntoIterator<Item = Person>,
+    ) -> Vec<PersonMatch> {
+        let mut matches: Vec<PersonMatch> = candidates
+            .into_iter()
+            .filter_map(|other| self.candidate(person, &other))
+            .collect();
+
+        keep_best(&mut matches);
+        matches
+    }
+}
+
+/// DuplicateCheck looks for the possible duplicates of new people among the
+/// saved people, which the stores read page after page, and among each other.
+#[derive(Debug)]
+pub struct DuplicateCheck {
+    detection: DuplicateDetection,
+    new_people: Vec<Person>,
+    /// The best matches found so far for every new person
+    matches: Vec<Vec<PersonMatch>>,
+}
+
+impl DuplicateCheck {
+    pub fn new(detection: &DuplicateDetection, new_people: Vec<Person>) -> Self {
+        DuplicateCheck {
+            detection: *detection,
+            matches: vec![vec![]; new_people.len()],
+            new_people,
+        }
+    }
+
+    /// shortlist returns which saved people are worth scoring against the new
+    /// people.
+    pub fn shortlist(&self) -> Shortlist {
+        if self.detection.threshold <= 0.0 {
+            return Shortlist::Everybody;
+        }
+
+        let name_keys = sorted(self.new_people.iter().map(name_key));
+        match self.detection.algorithm {
+            MatchAlgorithm::Exact => Shortlist::NameKeys(name_keys),
+            _ => Shortlist::Similar {
+                name_keys,
+                names: sorted(self.new_people.iter().flat_map(|person| {
+                    let name = full_name(person);
+                    [normalize(&name), name]
+                })),
+            },
+        }
+    }
+
+    /// lock_keys returns the keys a saved person has to share with one of the
+    /// new people to be their duplicate, so the checks of new people without
+    /// a key in common can run at the same time. Exact duplicates share their
+    /// name key, the others their first or their last name key, as a typo
+    /// rarely changes the sound of both names.
+    pub fn lock_keys(&self) -> Vec<String> {
+        match self.detection.algorithm {
+            MatchAlgorithm::Exact => sorted(self.new_people.iter().map(name_key)),
+            _ => sorted(self.new_people.iter().flat_map(|person| {
+                [
+                    format!("first {}", word_key(&person.first_name)),
+                    format!("last {}", word_key(&person.last_name)),
+                ]
+            })),
+        }
+    }
+
+    /// score keeps the saved people who are possible duplicates of the new
+    /// people, only the best matches of every new person are kept.
+    pub fn score<'a>(&mut self, saved: impl IntoIterator<Item = &'a Person>) {
+        for other in saved {
+            for (person, matches) in self.new_people.iter().zip(self.matches.iter_mut()) {
+                matches.extend(self.detection.candidate(person, other));
+            }
+        }
+        for matches in self.matches.iter_mut() {
+            keep_best(matches);
+        }
+    }
+
+    /// finish returns, in the given order, the new people to save and the
+    /// possible duplicates of the others. The new people to save are
+    /// candidates for the ones after them, so the same person added twice at
+    /// once is saved once.
+    pub fn finish(self) -> Vec<Result<Person, error::Error>> {
+        let mut to_save: Vec<Person> = vec![];
+        let mut results = Vec::with_capacity(self.new_people.len());
+        for (person, mut matches) in self.new_people.into_iter().zip(self.matches) {
+            matches.extend(
+                to_save
+                    .iter()
+                    .filter_map(|other| self.detection.candidate(&person, other)),
+            );
+            keep_best(&mut matches);
+
+            match matches.is_empty() {
+                true => {
+                    to_save.push(person.clone());
+                    results.push(Ok(person));
+                }
+                false => results.push(Err(error::Error::PossibleDuplicates(matches))),
+            }
+        }
+
+        results
+    }
+}
+
+/// Shortlist is which saved people can be possible duplicates of the new
+/// people, the stores which cannot tell similar names apart score everybody.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shortlist {
+    /// Every saved person who is not deleted, as every name matches
+    Everybody,
+    /// The people with one of the name keys
+    NameKeys(Vec<String>),
+    /// The people with one of the name keys or a full name sharing enough
+    /// trigrams with one of the names
+    Similar {
+        name_keys: Vec<String>,
+        names: Vec<String>,
+    },
+}
+
+fn sorted(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut values: Vec<String> = values.collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// keep_best sorts the matches, the best first and the id breaking ties, and
+/// drops the ones after the first MAX_CANDIDATES.
+fn keep_best(matches: &mut Vec<PersonMatch>) {
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.person.id.cmp(&b.person.id))
+    });
+    matches.truncate(MAX_CANDIDATES);
+}
+
+impl FromStr for MatchAlgorithm {
+    type Err = error::Error;
+
+    fn from_str(algorithm: &str) -> Result<Self, Self::Err> {
+        match algorithm {
+            "exact" => Ok(MatchAlgorithm::Exact),
+            "jaro_winkler" => Ok(MatchAlgorithm::JaroWinkler),
+            "phonetic" => Ok(MatchAlgorithm::Phonetic),
+            _ => Err(error::Error::InvalidMatchAlgorithm),
+        }
+    }
+}
+
+/// normalize lowercases the name, removes its accents and punctuation and
+/// leaves a single space between words, so José  Pérez-Gil is jose perez gil.
+pub fn normalize(name: &str) -> String {
+    let folded: String = name
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| match c.is_alphanumeric() {
+            true => c,
+            false => ' ',
+        })
+        .collect::<String>()
+        .to_lowercase();
+
+    folded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// name_key is the soundex of the first name and of the last name. People
+/// with the same names have the same key, so the stores only score the people
+/// with the key of a new person when looking for exact duplicates.
+pub fn name_key(person: &Person) -> String {
+    format!(
+        "{} {}",
+        word_key(&person.first_name),
+        word_key(&person.last_name)
+    )
+}
+
+/// word_key is the soundex of the name as a single word, or the name itself
+/// when it has no letters soundex knows.
+fn word_key(name: &str) -> String {
+    let name = normalize(name).replace(' ', "");
+    match soundex(&name) {
+        code if code.is_empty() => name,
+        code => code,
+    }
+}
+
+/// jaro_winkler is the Jaro similarity of both texts, raised when they start
+/// the same way.
+pub fn jaro_winkler(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let jaro = jaro(&a, &b);
+    let prefix = a
+        .iter()
+        .zip(b.iter())
+        .take(WINKLER_PREFIX)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    jaro + prefix as f32 * WINKLER_SCALING * (1.0 - jaro)
+}
+
+/// jaro counts the letters both texts have close to the same position and how
+/// many of them are out of order.
+fn jaro(a: &[char], b: &[char]) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0;
+    for (i, letter) in a.iter().enumerate() {
+        let end = (i + window + 1).min(b.len());
+        for j in i.saturating_sub(window)..end {
+            if !b_matched[j] && b[j] == *letter {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let matched = |text: &[char], flags: &[bool]| -> Vec<char> {
+        text.iter()
+            .zip(flags)
+            .filter(|(_, matched)| **matched)
+            .map(|(letter, _)| *letter)
+            .collect()
+    };
+    let out_of_order = matched(a, &a_matched)
+        .iter()
+        .zip(matched(b, &b_matched).iter())
+        .filter(|(a, b)| a != b)
+        .count();
+
+    let matches = matches as f32;
+    let transpositions = out_of_order as f32 / 2.0;
+    (matches / a.len() as f32 + matches / b.len() as f32 + (matches - transpositions) / matches)
+        / 3.0
+}
+
+/// phonetic_similarity is the share of words which sound the same in both
+/// names, word by word.
+fn phonetic_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<String> = a.split(' ').map(soundex).collect();
+    let b: Vec<String> = b.split(' ').map(soundex).collect();
+
+    let words = a.len().max(b.len());
+    if words == 0 {
+        return 0.0;
+    }
+    let alike = a
+        .iter()
+        .zip(b.iter())
+        .filter(|(a, b)| !a.is_empty() && a == b)
+        .count();
+
+    alike as f32 / words as f32
+}
+
+/// soundex is the American Soundex code of the word, the first letter and
+/// three digits for the consonants which follow it, like R163 for Robert.
+pub fn soundex(word: &str) -> String {
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let first = match letters.next() {
+        Some(first) => first,
+        None => return String::new(),
+    };
+
+    let mut code = String::from(first);
+    let mut last = soundex_digit(first);
+    for letter in letters {
+        match soundex_digit(letter) {
+            // H and W do not separate consonants with the same digit.
+            None => continue,
+            // vowels do, so the same digit can be written twice.
+            Some('0') => last = Some('0'),
+            digit if digit != last => {
+                code.extend(digit);
+                last = digit;
+            }
+            _ => {}
+        }
+        if code.len() == 4 {
+            break;
+        }
+    }
+
+    format!("{code:0<4}")
+}
+
+fn soundex_digit(letter: char) -> Option<char> {
+    match letter {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        'H' | 'W' => None,
+        _ => Some('0'),
+    }
+}
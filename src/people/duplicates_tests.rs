@@ -0,0 +1,201 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod duplicates_tests {
+    use crate::errors::error;
+    use crate::people::duplicates::{
+        jaro_winkler, name_key, normalize, soundex, DuplicateCheck, DuplicateDetection,
+        MatchAlgorithm, Shortlist,
+    };
+    use crate::types::people::{Person, PersonID};
+
+    fn person(id: &str, first_name: &str, last_name: &str) -> Person {
+        Person::new(
+            PersonID(id.to_string()),
+            first_name.to_string(),
+            last_name.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  José  Pérez-Gil "), "jose perez gil");
+        assert_eq!(normalize("MÜLLER, Zoë"), "muller zoe");
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        for (a, b, want) in [
+            ("martha", "marhta", 0.961),
+            ("dwayne", "duane", 0.84),
+            ("dixon", "dicksonx", 0.813),
+            ("luis", "luis", 1.0),
+            ("luis", "", 0.0),
+        ] {
+            // When
+            let got = jaro_winkler(a, b);
+            // Then
+            assert!((got - want).abs() < 0.001, "{a} and {b} scored {got}");
+        }
+    }
+
+    #[test]
+    fn test_soundex() {
+        for (word, want) in [
+            ("Robert", "R163"),
+            ("Rupert", "R163"),
+            ("Rubin", "R150"),
+            ("Ashcraft", "A261"),
+            ("Tymczak", "T522"),
+            ("Pfister", "P236"),
+            ("Lee", "L000"),
+            ("", ""),
+        ] {
+            assert_eq!(soundex(word), want, "soundex of {word}");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_candidates() {
+        // Given
+        let new_person = person("new", "Jon", "Smith");
+        let existing = [
+            person("1", "John", "Smyth"),
+            person("2", "jon", "SMITH"),
+            person("3", "Esme", "Weatherwax"),
+        ];
+        let detection = |algorithm, threshold| DuplicateDetection {
+            algorithm,
+            threshold,
+        };
+        let ids = |detection: DuplicateDetection| -> Vec<String> {
+            existing
+                .iter()
+                .filter_map(|other| detection.candidate(&new_person, other))
+                .map(|found| found.person.id.to_string())
+                .collect()
+        };
+        // When
+        let got_exact = ids(detection(MatchAlgorithm::Exact, 1.0));
+        let got_jaro_winkler = ids(detection(MatchAlgorithm::JaroWinkler, 0.9));
+        let got_phonetic = ids(detection(MatchAlgorithm::Phonetic, 1.0));
+        let got_itself = DuplicateDetection::default().candidate(&new_person, &new_person);
+        // Then
+        assert_eq!(got_exact, vec!["2"]);
+        assert_eq!(got_jaro_winkler, vec!["1", "2"]);
+        assert_eq!(got_phonetic, vec!["1", "2"]);
+        assert_eq!(got_itself, None);
+    }
+
+    #[test]
+    fn test_duplicate_matches() {
+        // Given
+        let new_person = person("new", "Jon", "Smith");
+        let candidates = vec![
+            person("3", "Esme", "Weatherwax"),
+            person("2", "John", "Smith"),
+            person("1", "Jon", "Smith"),
+        ];
+        // When
+        let got: Vec<String> = DuplicateDetection::default()
+            .matches(&new_person, candidates)
+            .into_iter()
+            .map(|found| found.person.id.to_string())
+            .collect();
+        // Then
+        assert_eq!(got, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_duplicate_check() {
+        // Given
+        let new_people = vec![
+            person("new-1", "Catherine", "Smith"),
+            person("new-2", "Gytha", "Ogg"),
+            person("new-3", "Gytha", "Ogg"),
+        ];
+        let first_page = vec![person("1", "Esme", "Weatherwax")];
+        let second_page = vec![person("2", "Katherine", "Smith")];
+        let exact = DuplicateDetection {
+            algorithm: MatchAlgorithm::Exact,
+            threshold: 1.0,
+        };
+        let mut check = DuplicateCheck::new(&DuplicateDetection::default(), new_people.clone());
+        // When
+        let exact_check = DuplicateCheck::new(&exact, new_people.clone());
+        let got_exact_shortlist = exact_check.shortlist();
+        let got_exact_lock_keys = exact_check.lock_keys();
+        let got_everybody = DuplicateCheck::new(
+            &DuplicateDetection {
+                threshold: 0.0,
+                ..Default::default()
+            },
+            new_people.clone(),
+        )
+        .shortlist();
+        let got_shortlist = check.shortlist();
+        let got_lock_keys = check.lock_keys();
+        check.score(&first_page);
+        check.score(&second_page);
+        let got = check.finish();
+        // Then
+        let name_keys = vec!["C365 S530".to_string(), "G300 O200".to_string()];
+        assert_eq!(got_exact_shortlist, Shortlist::NameKeys(name_keys.clone()));
+        assert_eq!(got_exact_lock_keys, name_keys);
+        assert_eq!(got_everybody, Shortlist::Everybody);
+        assert_eq!(
+            got_shortlist,
+            Shortlist::Similar {
+                name_keys,
+                names: vec![
+                    "Catherine Smith".to_string(),
+                    "Gytha Ogg".to_string(),
+                    "catherine smith".to_string(),
+                    "gytha ogg".to_string(),
+                ],
+            }
+        );
+        assert_eq!(
+            got_lock_keys,
+            vec!["first C365", "first G300", "last O200", "last S530"]
+        );
+        match &got[0] {
+            Err(error::Error::PossibleDuplicates(matches)) => {
+                assert_eq!(matches.len(), 1);
+                assert_eq!(matches[0].person, second_page[0]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(got[1], Ok(new_people[1].clone()));
+        match &got[2] {
+            Err(error::Error::PossibleDuplicates(matches)) => {
+                assert_eq!(matches[0].person, new_people[1]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_name_key() {
+        assert_eq!(name_key(&person("1", "Jon", "Smith")), "J500 S530");
+        assert_eq!(
+            name_key(&person("1", "John", "Smyth")),
+            name_key(&person("2", "Jon", "Smith"))
+        );
+        assert_eq!(
+            name_key(&person("1", "Esmé", "Weather-wax")),
+            name_key(&person("2", "Esme", "Weatherwax"))
+        );
+        assert_eq!(name_key(&person("1", "李", "小龍")), "李 小龍");
+    }
+
+    #[test]
+    fn test_parse_match_algorithm() {
+        assert_eq!("exact".parse(), Ok(MatchAlgorithm::Exact));
+        assert_eq!("jaro_winkler".parse(), Ok(MatchAlgorithm::JaroWinkler));
+        assert_eq!("phonetic".parse(), Ok(MatchAlgorithm::Phonetic));
+        assert_eq!(
+            "levenshtein".parse::<MatchAlgorithm>(),
+            Err(error::Error::InvalidMatchAlgorithm)
+        );
+    }
+}
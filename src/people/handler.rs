@@ -1,80 +1,275 @@
+use crate::errors::error::Error;
 use crate::people::{censor, service, storage};
 use crate::types::{
+    audit,
+    batch::{self, BatchResult},
+    csv::{self, ImportResult, ImportedPerson, RowError},
+    history,
+    merges::MergeRequest,
     pagination,
-    people::{NewPerson, Person, PersonID, SavePersonSuccess},
-    pets::NewPet,
+    people::{
+        DuplicateCandidates, NewPerson, Person, PersonID, PersonWithPets, PurgePeopleSuccess,
+        SavePersonSuccess,
+    },
+    pets::{NewPet, PetID, PetName, PetTransfer, PetsBlockingDelete},
+    query,
+    relationships::NewRelationship,
+    webhooks::{NewWebhook, WebhookID, WebhookPayload},
 };
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use hyper::{body::Bytes, Body};
 use std::collections::HashMap;
+use std::convert::Infallible;
 use tracing::{debug, error};
-use warp::{http::StatusCode, reject::Reject, Rejection, Reply};
+use warp::{
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, LINK, LOCATION},
+        HeaderValue, StatusCode,
+    },
+    reply::Response,
+    sse::{self, Event},
+    Rejection, Reply,
+};
 
-#[derive(Debug)]
-struct InvalidID;
-impl Reject for InvalidID {}
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+/// The longest retention_days of a purge, about a hundred years
+pub const MAX_RETENTION_DAYS: u32 = 36_500;
 
+/// get_people returns a page of people, the Link header points to the next
+/// page when there is one.
 pub async fn get_people(
     params: HashMap<String, String>,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl Reply, Rejection> {
     debug!("start querying people");
 
-    let mut pagination = pagination::Pagination::default();
-    if !params.is_empty() {
-        debug!(pagination = false);
-        pagination = pagination::extract_pagination(params)?;
-    }
+    let query = query::extract_people_query(&params)?;
+    let pagination = pagination::extract_pagination(&params)?;
+    let expand_pets = query::extract_expand_pets(&params)?;
 
-    debug!(pagination = true);
-    let mut res: Vec<Person> = match service
-        .get_people(pagination.limit, pagination.offset)
-        .await
-    {
+    let res = match service.get_people(query, pagination).await {
         Ok(res) => res,
         Err(e) => return Err(warp::reject::custom(e)),
     };
 
-    res.sort();
+    let mut response = match expand_pets {
+        true => match service.expand_pets(res.people).await {
+            Ok(people) => warp::reply::json(&people).into_response(),
+            Err(e) => return Err(warp::reject::custom(e)),
+        },
+        false => warp::reply::json(&res.people).into_response(),
+    };
+    if let Some(next) = res.next {
+        // the next page keeps the same filters, sort and limit.
+        let mut next_params: Vec<(&String, &String)> = params
+            .iter()
+            .filter(|(name, _)| name.as_str() != "cursor")
+            .collect();
+        next_params.sort();
+        let token = next.encode();
+        let cursor = String::from("cursor");
+        next_params.push((&cursor, &token));
 
-    Ok(warp::reply::json(&res))
+        let link = format!(
+            "</people?{}>; rel=\"next\"",
+            serde_urlencoded::to_string(next_params).unwrap_or_default()
+        );
+        if let Ok(link) = HeaderValue::from_str(&link) {
+            response.headers_mut().insert(LINK, link);
+        }
+    }
+
+    Ok(response)
+}
+
+/// search_people returns the people whose names best match the q parameter,
+/// best matches first.
+pub async fn search_people(
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl Reply, Rejection> {
+    let terms = match params.get("q") {
+        Some(terms) => terms.clone(),
+        None => return Err(warp::reject::custom(Error::MissingParameters)),
+    };
+    let limit = pagination::extract_pagination(&params)?.limit;
+
+    match service.search_people(terms, limit).await {
+        Ok(matches) => Ok(warp::reply::json(&matches)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
 }
 
 pub async fn get_person(
     id: String,
+    params: HashMap<String, String>,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let res = match service.get_person(PersonID(id)).await {
+    let expand_pets = query::extract_expand_pets(&params)?;
+    if let Some(at) = history::extract_as_of(&params)? {
+        return get_person_as_of(PersonID(id), at, expand_pets, &params, service).await;
+    }
+    let res = match service
+        .get_person(PersonID(id.clone()), include_deleted(&params))
+        .await
+    {
         Ok(res) => res,
+        Err(Error::PersonNotFound) => {
+            return match service.find_survivor(PersonID(id)).await {
+                Ok(Some(survivor)) => Ok(redirect_to_person(survivor, &params)),
+                Ok(None) => Err(warp::reject::custom(Error::PersonNotFound)),
+                Err(e) => Err(warp::reject::custom(e)),
+            }
+        }
         Err(e) => return Err(warp::reject::custom(e)),
     };
 
-    Ok(warp::reply::json(&res))
+    let etag = res.etag();
+    let person = match expand_pets {
+        true => match service.expand_pets(vec![res]).await {
+            Ok(mut people) => people.remove(0),
+            Err(e) => return Err(warp::reject::custom(e)),
+        },
+        false => PersonWithPets {
+            person: res,
+            pets: None,
+        },
+    };
+
+    Ok(warp::reply::with_header(warp::reply::json(&person), "ETag", etag).into_response())
 }
 
+/// get_person_as_of answers with the person as they were at the moment, an
+/// old version has no ETag as it cannot be updated.
+async fn get_person_as_of(
+    person_id: PersonID,
+    at: DateTime<Utc>,
+    expand_pets: bool,
+    params: &HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<Response, Rejection> {
+    let res = match service
+        .get_person_as_of(person_id, at, include_deleted(params))
+        .await
+    {
+        Ok(res) => res,
+        Err(e) => return Err(warp::reject::custom(e)),
+    };
+
+    let person = match expand_pets {
+        true => match service.expand_pets(vec![res]).await {
+            Ok(mut people) => people.remove(0),
+            Err(e) => return Err(warp::reject::custom(e)),
+        },
+        false => PersonWithPets {
+            person: res,
+            pets: None,
+        },
+    };
+
+    Ok(warp::reply::json(&person).into_response())
+}
+
+/// get_person_history returns every version of the person, oldest first,
+/// with the fields changed from the version before.
+pub async fn get_person_history(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.get_person_history(PersonID(id)).await {
+        Ok(revisions) => Ok(warp::reply::json(&revisions)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// redirect_to_person answers with the location of the person a merged
+/// person survives in, keeping the query parameters.
+fn redirect_to_person(person_id: PersonID, params: &HashMap<String, String>) -> Response {
+    let mut params: Vec<(&String, &String)> = params.iter().collect();
+    params.sort();
+    let location = match params.is_empty() {
+        true => format!("/people/{}", person_id),
+        false => format!(
+            "/people/{}?{}",
+            person_id,
+            serde_urlencoded::to_string(params).unwrap_or_default()
+        ),
+    };
+
+    warp::reply::with_header(
+        warp::reply::with_status(
+            format!("Person was merged into {}", person_id),
+            StatusCode::MOVED_PERMANENTLY,
+        ),
+        LOCATION,
+        location,
+    )
+    .into_response()
+}
+
+/// update_person only overwrites the person if it still has the version the
+/// client read, which must be given in the If-Match header.
 pub async fn update_person(
+    if_match: Option<String>,
     person: Person,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let mut person = person;
+
+    match if_match.as_deref().map(str::trim) {
+        Some("*") => {
+            person.version = match service.get_person(person.id.clone(), false).await {
+                Ok(current) => current.version,
+                Err(e) => return Err(warp::reject::custom(e)),
+            }
+        }
+        Some(etag) => person.version = parse_etag(etag)?,
+        None => return Err(warp::reject::custom(Error::PreconditionRequired)),
+    }
+
     let res = match service.update_person(person).await {
         Ok(res) => res,
+        Err(Error::PersonVersionConflict) => {
+            return Err(warp::reject::custom(Error::PreconditionFailed))
+        }
         Err(e) => return Err(warp::reject::custom(e)),
     };
 
-    Ok(warp::reply::json(&res))
+    Ok(warp::reply::with_header(
+        warp::reply::json(&res),
+        "ETag",
+        res.etag(),
+    ))
 }
 
+/// add_person saves a new person, or answers 409 with the people they may be
+/// unless force=true is given.
 pub async fn add_person(
+    params: HashMap<String, String>,
     new_person: NewPerson,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     debug!("start adding people {:?}", new_person);
 
-    match service.add_person(new_person.clone()).await {
+    let force = params.get("force").is_some_and(|value| value == "true");
+    match service.add_person(new_person.clone(), force).await {
         Ok(person) => {
             debug!("new person was saved {:?}", person);
 
             let result = SavePersonSuccess::new(person.id);
 
-            Ok(warp::reply::json(&result))
+            Ok(warp::reply::json(&result).into_response())
+        }
+        Err(Error::PossibleDuplicates(candidates)) => {
+            let body = DuplicateCandidates {
+                error: "Person may already exist, use force=true to add them anyway".to_string(),
+                candidates,
+            };
+            Ok(
+                warp::reply::with_status(warp::reply::json(&body), StatusCode::CONFLICT)
+                    .into_response(),
+            )
         }
         Err(e) => {
             error!("adding person {:?}", new_person);
@@ -83,26 +278,530 @@ pub async fn add_person(
     }
 }
 
+/// add_people adds every person of a JSON array or NDJSON body, the response
+/// tells which ones were created and why the others were not.
+/// People who may already exist are not added unless force=true is given.
+pub async fn add_people(
+    params: HashMap<String, String>,
+    body: Bytes,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let items = batch::parse_new_people(&body).map_err(warp::reject::custom)?;
+    debug!("start adding a batch of {} people", items.len());
+
+    let new_people: Vec<NewPerson> = items
+        .iter()
+        .filter_map(|item| item.as_ref().ok().cloned())
+        .collect();
+    let force = params.get("force").is_some_and(|value| value == "true");
+    let mut saved = service.add_people(new_people, force).await.into_iter();
+
+    let results: Vec<Result<Person, String>> = items
+        .into_iter()
+        .map(|item| match item {
+            Ok(_) => match saved.next() {
+                Some(Ok(person)) => Ok(person),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err(Error::CreatePersonError.to_string()),
+            },
+            Err(e) => Err(e),
+        })
+        .collect();
+
+    let result = BatchResult::new(results);
+    debug!(
+        "{} people were created and {} failed",
+        result.created, result.failed
+    );
+    Ok(warp::reply::json(&result))
+}
+
+/// export_people returns the people matching the listing filters as CSV, with
+/// their pets when include_pets is true.
+pub async fn export_people(
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let query = query::extract_people_query(&params)?;
+    let include_pets = params
+        .get("include_pets")
+        .is_some_and(|value| value == "true");
+
+    match service.export_people(query, include_pets).await {
+        Ok((people, pets)) => {
+            debug!("exporting {} people", people.len());
+            let body = csv::export_people(&people, pets.as_deref());
+            let reply = warp::reply::with_header(body, CONTENT_TYPE, "text/csv; charset=utf-8");
+            Ok(warp::reply::with_header(
+                reply,
+                CONTENT_DISPOSITION,
+                "attachment; filename=\"people.csv\"",
+            ))
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// stream_people writes every person as a line of JSON while they are read,
+/// with their pets when include_pets is true.
+pub async fn stream_people(
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let include_pets = params
+        .get("include_pets")
+        .is_some_and(|value| value == "true");
+
+    let people = service
+        .stream_people(include_pets)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    let lines = people.map(|person| {
+        let person = person.map_err(|e| {
+            // the status was already sent, so the client sees a broken body.
+            error!("streaming people: {:?}", e);
+            std::io::Error::other(e.to_string())
+        })?;
+        let mut line = serde_json::to_vec(&person).expect("person is always serializable");
+        line.push(b'\n');
+        Ok::<Vec<u8>, std::io::Error>(line)
+    });
+
+    let mut response = warp::reply::Response::new(Body::wrap_stream(lines));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    Ok(response)
+}
+
+/// stream_events sends the changes of people and pets as server-sent events
+/// while the client stays connected. A client which reconnects with the
+/// Last-Event-ID header gets the events it missed first.
+pub async fn stream_events(
+    last_event_id: Option<String>,
+    service: service::Service<
+        impl storage::Storer + Clone + Send + Sync + 'static,
+        impl censor::Censorious,
+    >,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let last_event_id = last_event_id
+        .map(|id| {
+            id.trim()
+                .parse::<i64>()
+                .map_err(|_| warp::reject::custom(Error::InvalidLastEventId(id)))
+        })
+        .transpose()?;
+
+    let events = service
+        .live_events(last_event_id)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    let events = events.map(|event| {
+        let payload = WebhookPayload::from(&event);
+        let event = Event::default()
+            .id(event.id.to_string())
+            .event(event.kind.as_str())
+            .json_data(payload)
+            .expect("event payload is always serializable");
+        Ok::<Event, Infallible>(event)
+    });
+
+    Ok(sse::reply(sse::keep_alive().stream(events)))
+}
+
+/// import_people adds the people of a CSV file, the response tells the line of
+/// every row which could not be imported.
+/// People who may already exist are not imported unless force=true is given.
+pub async fn import_people(
+    params: HashMap<String, String>,
+    body: Bytes,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let text = std::str::from_utf8(&body)
+        .map_err(|_| warp::reject::custom(Error::InvalidCsv("it is not UTF-8".to_string())))?;
+    let rows = csv::parse_import(text).map_err(warp::reject::custom)?;
+    debug!("start importing {} rows of people", rows.len());
+
+    let mut errors: Vec<RowError> = Vec::new();
+    let mut valid_rows = Vec::new();
+    for row in rows {
+        match row {
+            Ok(row) => valid_rows.push(row),
+            Err(row_error) => errors.push(row_error),
+        }
+    }
+
+    let lines: Vec<usize> = valid_rows.iter().map(|row| row.line).collect();
+    let force = params.get("force").is_some_and(|value| value == "true");
+    let mut people: Vec<ImportedPerson> = Vec::new();
+    for (line, imported) in lines
+        .into_iter()
+        .zip(service.import_people(valid_rows, force).await)
+    {
+        let (person, pets) = match imported {
+            Ok(imported) => imported,
+            Err(e) => {
+                errors.push(RowError {
+                    line,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let mut saved_pets = Vec::new();
+        for pet in pets {
+            match pet {
+                Ok(pet) => saved_pets.push(pet),
+                Err(e) => errors.push(RowError {
+                    line,
+                    error: format!("person was imported but not a pet: {e}"),
+                }),
+            }
+        }
+        people.push(ImportedPerson {
+            line,
+            person,
+            pets: saved_pets,
+        });
+    }
+
+    let result = ImportResult::new(people, errors);
+    debug!(
+        "{} people were imported and {} rows failed",
+        result.imported, result.failed
+    );
+    Ok(warp::reply::json(&result))
+}
+
+/// delete_person deletes the person with the delete_policy parameter or else
+/// the one of the service, a refused delete lists the pets blocking it.
 pub async fn delete_person(
     id: String,
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let policy = query::extract_delete_policy(&params)?;
+
+    match service.delete_person(PersonID(id.clone()), policy).await {
+        Ok(_) => Ok(
+            warp::reply::with_status(format!("Person {} deleted", id), StatusCode::OK)
+                .into_response(),
+        ),
+        Err(Error::PersonHasPets(pets)) => {
+            let body = PetsBlockingDelete {
+                error: format!(
+                    "Person {} has pets, delete them first or use the cascade or orphan delete policy",
+                    id
+                ),
+                pets,
+            };
+            Ok(
+                warp::reply::with_status(warp::reply::json(&body), StatusCode::CONFLICT)
+                    .into_response(),
+            )
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// merge_people merges the source person of the body into the person of the
+/// path, who must still have the If-Match version when it is given.
+pub async fn merge_people(
+    id: String,
+    if_match: Option<String>,
+    request: MergeRequest,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let version = match if_match.as_deref().map(str::trim) {
+        Some("*") | None => None,
+        Some(etag) => Some(parse_etag(etag)?),
+    };
+
+    let res = match service.merge_people(PersonID(id), request, version).await {
+        Ok(res) => res,
+        Err(Error::PersonVersionConflict) if version.is_some() => {
+            return Err(warp::reject::custom(Error::PreconditionFailed))
+        }
+        Err(e) => return Err(warp::reject::custom(e)),
+    };
+
+    Ok(warp::reply::with_header(
+        warp::reply::json(&res),
+        "ETag",
+        res.etag(),
+    ))
+}
+
+pub async fn restore_person(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let res = match service.restore_person(PersonID(id)).await {
+        Ok(res) => res,
+        Err(e) => return Err(warp::reject::custom(e)),
+    };
+
+    Ok(warp::reply::with_header(
+        warp::reply::json(&res),
+        "ETag",
+        res.etag(),
+    ))
+}
+
+/// purge_people removes for good the people deleted more than retention_days
+/// ago, 30 days when it is not given. retention_days must be between 0 and
+/// MAX_RETENTION_DAYS.
+pub async fn purge_people(
+    params: HashMap<String, String>,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    if let Err(e) = service.delete_person(PersonID(id.clone())).await {
+    let retention_days = match params.get("retention_days") {
+        Some(days) => match days.parse::<u32>() {
+            Ok(days) if days <= MAX_RETENTION_DAYS => days,
+            _ => return Err(warp::reject::custom(Error::InvalidRetentionDays)),
+        },
+        None => DEFAULT_RETENTION_DAYS,
+    };
+
+    match service
+        .purge_people(Duration::days(retention_days.into()))
+        .await
+    {
+        Ok(purged) => Ok(warp::reply::json(&PurgePeopleSuccess { purged })),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn add_pet(
+    id: String,
+    pet_name: PetName,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let new_pet = NewPet::new(pet_name.name, PersonID(id));
+    match service.add_pet(new_pet).await {
+        Ok(pet) => Ok(warp::reply::with_status(
+            warp::reply::json(&pet),
+            StatusCode::CREATED,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_person_pets(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.get_person_pets(PersonID(id)).await {
+        Ok(pets) => Ok(warp::reply::json(&pets)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_pet(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.get_pet(PetID(id)).await {
+        Ok(pet) => Ok(warp::reply::json(&pet)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn update_pet(
+    id: String,
+    pet_name: PetName,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.rename_pet(PetID(id), pet_name.name).await {
+        Ok(pet) => Ok(warp::reply::json(&pet)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// transfer_pet gives the pet to the person in the body.
+pub async fn transfer_pet(
+    id: String,
+    transfer: PetTransfer,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.transfer_pet(PetID(id), transfer.person_id).await {
+        Ok(pet) => Ok(warp::reply::json(&pet)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn delete_pet(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Err(e) = service.delete_pet(PetID(id.clone())).await {
         return Err(warp::reject::custom(e));
     }
 
     Ok(warp::reply::with_status(
-        format!("Person {} deleted", id),
+        format!("Pet {} deleted", id),
         StatusCode::OK,
     ))
 }
 
-pub async fn add_pet(
-    new_pet: NewPet,
+/// add_relationship relates the person to the one in the body, the reciprocal
+/// relationship is added too.
+pub async fn add_relationship(
+    id: String,
+    new_relationship: NewRelationship,
     service: service::Service<impl storage::Storer, impl censor::Censorious>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    match service.add_pet(new_pet.clone()).await {
-        Ok(pet) => Ok(warp::reply::with_status(pet.id.to_string(), StatusCode::OK)),
+    match service
+        .add_relationship(PersonID(id), new_relationship)
+        .await
+    {
+        Ok(relationship) => Ok(warp::reply::with_status(
+            warp::reply::json(&relationship),
+            StatusCode::CREATED,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_person_relationships(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.get_person_relationships(PersonID(id)).await {
+        Ok(relationships) => Ok(warp::reply::json(&relationships)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// get_relationship_graph returns the people reached from the person following
+/// their relationships, one level deep unless depth is given.
+pub async fn get_relationship_graph(
+    id: String,
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let depth = query::extract_graph_depth(&params)?;
+
+    match service.get_relationship_graph(PersonID(id), depth).await {
+        Ok(graph) => Ok(warp::reply::json(&graph)),
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
+
+pub async fn delete_relationship(
+    id: String,
+    related_id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Err(e) = service
+        .delete_relationship(PersonID(id.clone()), PersonID(related_id.clone()))
+        .await
+    {
+        return Err(warp::reject::custom(e));
+    }
+
+    Ok(warp::reply::with_status(
+        format!("Relationship between {} and {} deleted", id, related_id),
+        StatusCode::OK,
+    ))
+}
+
+/// get_audit returns the newest audit entries, filtered by entity id, actor
+/// and time range.
+pub async fn get_audit(
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let query = audit::extract_audit_query(&params)?;
+
+    match service.get_audit_entries(query).await {
+        Ok(entries) => Ok(warp::reply::json(&entries)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// add_webhook subscribes the URL in the body to the domain events, every
+/// kind of event unless some are given.
+pub async fn add_webhook(
+    new_webhook: NewWebhook,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.add_webhook(new_webhook).await {
+        Ok(webhook) => Ok(warp::reply::with_status(
+            warp::reply::json(&webhook),
+            StatusCode::CREATED,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_webhooks(
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.get_webhooks().await {
+        Ok(webhooks) => Ok(warp::reply::json(&webhooks)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn delete_webhook(
+    id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Err(e) = service.delete_webhook(WebhookID(id.clone())).await {
+        return Err(warp::reject::custom(e));
+    }
+
+    Ok(warp::reply::with_status(
+        format!("Webhook {} deleted", id),
+        StatusCode::OK,
+    ))
+}
+
+/// get_webhook_deliveries returns the latest deliveries of the webhook, as
+/// many as the limit.
+pub async fn get_webhook_deliveries(
+    id: String,
+    params: HashMap<String, String>,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let pagination = pagination::extract_pagination(&params)?;
+
+    match service
+        .get_webhook_deliveries(WebhookID(id), pagination.limit)
+        .await
+    {
+        Ok(deliveries) => Ok(warp::reply::json(&deliveries)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// redeliver_webhook posts the payload of the delivery again, the new
+/// delivery is returned.
+pub async fn redeliver_webhook(
+    id: String,
+    delivery_id: String,
+    service: service::Service<impl storage::Storer, impl censor::Censorious>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match service.redeliver_webhook(WebhookID(id), delivery_id).await {
+        Ok(delivery) => Ok(warp::reply::json(&delivery)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+fn include_deleted(params: &HashMap<String, String>) -> bool {
+    params
+        .get("include_deleted")
+        .is_some_and(|value| value == "true")
+}
+
+fn parse_etag(etag: &str) -> Result<i32, Rejection> {
+    etag.trim_start_matches("W/")
+        .trim_matches('"')
+        .parse::<i32>()
+        .map_err(|_| warp::reject::custom(Error::PreconditionFailed))
+}
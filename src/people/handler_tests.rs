@@ -1,11 +1,33 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod handler_tests {
     use crate::errors::error;
+    use crate::people::duplicates::DuplicateDetection;
+    use crate::people::live::LiveEvents;
     use crate::people::{censor, handler, service, storage};
-    use crate::types::people::{NewPerson, Person, PersonID, SavePersonSuccess};
-    use crate::types::pets::Pet;
+    use crate::types::audit::{AuditAction, AuditContext, AuditEntity, AuditEntry, AuditQuery};
+    use crate::types::batch::BatchResult;
+    use crate::types::csv::{ImportResult, RowError};
+    use crate::types::events::{DomainEvent, EventKind};
+    use crate::types::history::{PersonRevision, PersonVersion};
+    use crate::types::merges::{Merge, MergeRequest};
+    use crate::types::pagination::Cursor;
+    use crate::types::people::{
+        DuplicateCandidates, NewPerson, Person, PersonID, PersonMatch, PersonWithPets,
+        PurgePeopleSuccess, SavePersonSuccess,
+    };
+    use crate::types::pets::{DeletePolicy, Pet, PetID, PetName, PetTransfer, PetsBlockingDelete};
+    use crate::types::profile::Profile;
+    use crate::types::query::{PeopleQuery, Sort};
+    use crate::types::relationships::{
+        NewRelationship, Relationship, RelationshipGraph, RelationshipKind,
+    };
+    use crate::types::webhooks::{NewWebhook, Webhook, WebhookDelivery, WebhookID};
     use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use futures::StreamExt;
     use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
     use tokio::runtime::Runtime;
     use warp::{http::StatusCode, Rejection, Reply};
 
@@ -17,11 +39,19 @@ mod handler_tests {
                 id: PersonID("1".to_string()),
                 first_name: "Luis".to_string(),
                 last_name: "Luis".to_string(),
+                created_on: DateTime::default(),
+                version: 1,
+                deleted_at: None,
+                profile: Profile::default(),
             },
             Person {
                 id: PersonID("2".to_string()),
                 first_name: "Fernando".to_string(),
                 last_name: "Fernando".to_string(),
+                created_on: DateTime::default(),
+                version: 1,
+                deleted_at: None,
+                profile: Profile::default(),
             },
         ];
 
@@ -30,7 +60,6 @@ mod handler_tests {
         let person_service = service::Service::new(a_store, a_censor);
 
         let mut params: HashMap<String, String> = HashMap::new();
-        params.insert(String::from("offset"), String::from("0"));
         params.insert(String::from("limit"), String::from("2"));
 
         let expected_people: Vec<Person> = vec![
@@ -38,11 +67,19 @@ mod handler_tests {
                 id: PersonID("1".to_string()),
                 first_name: "Luis".to_string(),
                 last_name: "Luis".to_string(),
+                created_on: DateTime::default(),
+                version: 1,
+                deleted_at: None,
+                profile: Profile::default(),
             },
             Person {
                 id: PersonID("2".to_string()),
                 first_name: "Fernando".to_string(),
                 last_name: "Fernando".to_string(),
+                created_on: DateTime::default(),
+                version: 1,
+                deleted_at: None,
+                profile: Profile::default(),
             },
         ];
 
@@ -71,6 +108,90 @@ mod handler_tests {
         assert_eq!(got_body_string, want_body_string);
     }
 
+    #[test]
+    fn test_get_people_returns_next_link() {
+        // Given
+        let people_store = vec![
+            Person::new(
+                PersonID("1".to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            ),
+            Person::new(
+                PersonID("2".to_string()),
+                "Fernando".to_string(),
+                "Fernando".to_string(),
+            ),
+        ];
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_get_people(people_store.clone(), false);
+        let person_service = service::Service::new(a_store, a_censor);
+
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("limit"), String::from("1"));
+        let want = format!(
+            "</people?limit=1&cursor={}>; rel=\"next\"",
+            Cursor::new(Sort::default(), &people_store[0]).encode()
+        );
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        // When
+        let got = runtime.block_on(handler::get_people(params, person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.headers().get("Link").unwrap(), want.as_str());
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: Vec<Person> = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got, vec![people_store[0].clone()]);
+    }
+
+    #[test]
+    fn test_search_people() {
+        // Given
+        let a_person = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        let a_store = DummyStore::new_with_get_people(vec![a_person.clone()], false);
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("q"), String::from("lui"));
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        // When
+        let got = runtime.block_on(handler::search_people(
+            params,
+            service::Service::new(a_store, a_censor.clone()),
+        ));
+        let got_without_q = runtime.block_on(handler::search_people(
+            HashMap::new(),
+            service::Service::new(DummyStore::new_with_get_people(vec![], false), a_censor),
+        ));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: Vec<PersonMatch> = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(
+            got,
+            vec![PersonMatch {
+                person: a_person,
+                score: 1.0
+            }]
+        );
+        let got_error = match got_without_q {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::MissingParameters)
+        );
+    }
+
     #[test]
     fn test_get_person() {
         // Given
@@ -78,6 +199,10 @@ mod handler_tests {
             id: PersonID("1".to_string()),
             first_name: "Luis".to_string(),
             last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
 
         let a_censor = DummyCensor::new("".to_string(), false);
@@ -89,10 +214,18 @@ mod handler_tests {
             id: PersonID("1".to_string()),
             first_name: "Luis".to_string(),
             last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
         let runtime = Runtime::new().expect("unable to create runtime to test get person");
         // When
-        let response = runtime.block_on(handler::get_person(person_id, person_service));
+        let response = runtime.block_on(handler::get_person(
+            person_id,
+            HashMap::new(),
+            person_service,
+        ));
         // Then
         let got_body_bytes = runtime
             .block_on(hyper::body::to_bytes(
@@ -113,9 +246,13 @@ mod handler_tests {
         let person_id = "2000".to_string();
         let runtime = Runtime::new().expect("unable to create runtime to test get person");
         // When
-        let got = runtime.block_on(handler::get_person(person_id, person_service));
+        let got = runtime.block_on(handler::get_person(
+            person_id,
+            HashMap::new(),
+            person_service,
+        ));
         // Then
-        assert_eq!(true, got.is_err());
+        assert!(got.is_err());
 
         let got_error = match got {
             Ok(value) => panic!("unexpected result {:?}", value.into_response()),
@@ -124,10 +261,274 @@ mod handler_tests {
 
         if let Some(e) = got_error.find::<error::Error>() {
             assert_eq!(*e, error::Error::GetPersonError);
-            return;
         }
     }
 
+    #[test]
+    fn test_add_people() {
+        // Given
+        let body = hyper::body::Bytes::from(
+            "{\"first_name\":\"esme\",\"last_name\":\"esme\"}\n{\"first_name\":\"luis\"}\n",
+        );
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_add_person(None, false);
+        let person_service = service::Service::new(a_store, a_censor);
+        let runtime = Runtime::new().expect("unable to create runtime to test add people");
+        // When
+        let got = runtime.block_on(handler::add_people(HashMap::new(), body, person_service));
+        // Then
+        let reply_response = got.unwrap().into_response();
+        assert_eq!(StatusCode::OK, reply_response.status());
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(reply_response.into_body()))
+            .unwrap();
+        let got: BatchResult = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.created, 1);
+        assert_eq!(got.failed, 1);
+        assert_eq!(
+            got.results[0]
+                .person
+                .as_ref()
+                .map(|person| person.first_name.as_str()),
+            Some("esme")
+        );
+        assert_eq!(got.results[1].index, 1);
+        assert!(got.results[1].error.is_some());
+    }
+
+    #[test]
+    fn test_add_people_with_possible_duplicates() {
+        // Given
+        let body =
+            hyper::body::Bytes::from("[{\"first_name\":\"Esme\",\"last_name\":\"Wetherwax\"}]");
+        let existing = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let a_store = DummyStore {
+            get_people_values: Some(vec![existing]),
+            add_person_error: Some(false),
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let mut force: HashMap<String, String> = HashMap::new();
+        force.insert(String::from("force"), String::from("true"));
+        let runtime = Runtime::new().expect("unable to create runtime to test add people");
+        // When
+        let got = runtime.block_on(handler::add_people(
+            HashMap::new(),
+            body.clone(),
+            person_service.clone(),
+        ));
+        let got_forced = runtime.block_on(handler::add_people(force, body, person_service));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: BatchResult = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.created, 0);
+        assert_eq!(
+            got.results[0].error.as_deref(),
+            Some("Person may already exist: 1")
+        );
+        let got_forced_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got_forced.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got_forced: BatchResult = serde_json::from_slice(&got_forced_bytes).unwrap();
+        assert_eq!(got_forced.created, 1);
+    }
+
+    #[test]
+    fn test_import_people() {
+        // Given
+        let body =
+            hyper::body::Bytes::from("first_name,last_name,pet_name\nLuis,Luis,Firulais\n,Esme,\n");
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        let a_store = DummyStore {
+            add_pet_value: Some(pet.clone()),
+            add_pet_error: Some(false),
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let runtime = Runtime::new().expect("unable to create runtime to test import people");
+        // When
+        let got = runtime.block_on(handler::import_people(HashMap::new(), body, person_service));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: ImportResult = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.imported, 1);
+        assert_eq!(got.people[0].line, 2);
+        assert_eq!(got.people[0].person.first_name, "Luis");
+        assert_eq!(got.people[0].pets, vec![pet]);
+        assert_eq!(
+            got.errors,
+            vec![RowError {
+                line: 3,
+                error: "first_name and last_name cannot be empty".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_export_people() {
+        // Given
+        let people = vec![Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        )];
+        let a_store = DummyStore::new_with_get_people(people, false);
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let runtime = Runtime::new().expect("unable to create runtime to test export people");
+        // When
+        let got = runtime.block_on(handler::export_people(HashMap::new(), person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "text/csv; charset=utf-8"
+        );
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got = std::str::from_utf8(&got_body_bytes).unwrap();
+        assert!(got.starts_with("id,first_name,last_name,created_on,version\r\n1,Luis,Luis,"));
+    }
+
+    #[test]
+    fn test_stream_people() {
+        // Given
+        let people = vec![
+            Person::new(
+                PersonID("1".to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            ),
+            Person::new(
+                PersonID("2".to_string()),
+                "Esme".to_string(),
+                "Esme".to_string(),
+            ),
+        ];
+        let a_store = DummyStore::new_with_get_people(people.clone(), false);
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("include_pets"), String::from("true"));
+        let runtime = Runtime::new().expect("unable to create runtime to test stream people");
+        // When
+        let got = runtime.block_on(handler::stream_people(params, person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/x-ndjson"
+        );
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: Vec<PersonWithPets> = std::str::from_utf8(&got_body_bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let want: Vec<PersonWithPets> = people
+            .into_iter()
+            .map(|person| PersonWithPets {
+                person,
+                pets: Some(vec![]),
+            })
+            .collect();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn test_stream_events() {
+        // Given
+        let person_service = service::Service::new(
+            DummyStore::default(),
+            DummyCensor::new("".to_string(), false),
+        );
+        let runtime = Runtime::new().expect("unable to create runtime to test stream events");
+        // When
+        let got = runtime.block_on(handler::stream_events(
+            Some("12".to_string()),
+            person_service.clone(),
+        ));
+        let got_bad_id = runtime.block_on(handler::stream_events(
+            Some("yesterday".to_string()),
+            person_service,
+        ));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "text/event-stream"
+        );
+        let err = got_bad_id.err().unwrap();
+        assert_eq!(
+            err.find::<error::Error>(),
+            Some(&error::Error::InvalidLastEventId("yesterday".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_create_person_with_possible_duplicates() {
+        // Given
+        let existing = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let a_store = DummyStore {
+            get_people_values: Some(vec![existing.clone()]),
+            add_person_value: Some(existing.clone()),
+            add_person_error: Some(false),
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let new_person = NewPerson::new("Esme".to_string(), "Wetherwax".to_string());
+        let mut force: HashMap<String, String> = HashMap::new();
+        force.insert(String::from("force"), String::from("true"));
+        let runtime = Runtime::new().expect("unable to create runtime to test create person");
+        // When
+        let got = runtime.block_on(handler::add_person(
+            HashMap::new(),
+            new_person.clone(),
+            person_service.clone(),
+        ));
+        let got_forced = runtime.block_on(handler::add_person(force, new_person, person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: DuplicateCandidates = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.candidates.len(), 1);
+        assert_eq!(got.candidates[0].person, existing);
+        assert!(got.candidates[0].score > 0.9);
+
+        assert_eq!(got_forced.unwrap().into_response().status(), StatusCode::OK);
+    }
+
     #[test]
     fn test_create_person() {
         // Given
@@ -135,6 +536,10 @@ mod handler_tests {
             id: PersonID("3".to_string()),
             first_name: "esme".to_string(),
             last_name: "esme".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
         let new_person = NewPerson::new("esme".to_string(), "esme".to_string());
         let mut expected_result = SavePersonSuccess { id: "".to_string() };
@@ -143,9 +548,13 @@ mod handler_tests {
         let person_service = service::Service::new(a_store, a_censor);
         let runtime = Runtime::new().expect("unable to create runtime to test create person");
         // When
-        let got = runtime.block_on(handler::add_person(new_person, person_service));
+        let got = runtime.block_on(handler::add_person(
+            HashMap::new(),
+            new_person,
+            person_service,
+        ));
         // Then
-        assert_eq!(false, got.is_err());
+        assert!(got.is_ok());
 
         let got_result = match got {
             Ok(reply) => {
@@ -176,9 +585,13 @@ mod handler_tests {
         let expected_result = "Person 2 deleted";
         let runtime = Runtime::new().expect("unable to create runtime to test delete person");
         // When
-        let got = runtime.block_on(handler::delete_person(person_id, person_service));
+        let got = runtime.block_on(handler::delete_person(
+            person_id,
+            HashMap::new(),
+            person_service,
+        ));
         // Then
-        assert_eq!(false, got.is_err());
+        assert!(got.is_ok());
 
         let got_result = match got {
             Ok(reply) => {
@@ -205,9 +618,13 @@ mod handler_tests {
         let person_id = "2000".to_string();
         let runtime = Runtime::new().expect("unable to create runtime to test delete person");
         // When
-        let got = runtime.block_on(handler::delete_person(person_id, person_service));
+        let got = runtime.block_on(handler::delete_person(
+            person_id,
+            HashMap::new(),
+            person_service,
+        ));
         // Then
-        assert_eq!(true, got.is_err());
+        assert!(got.is_err());
 
         let got_error = match got {
             Ok(value) => panic!("unexpected result {:?}", value.into_response()),
@@ -216,10 +633,59 @@ mod handler_tests {
 
         if let Some(e) = got_error.find::<error::Error>() {
             assert_eq!(*e, error::Error::DeletePersonError);
-            return;
         }
     }
 
+    #[test]
+    fn test_delete_person_with_pets() {
+        // Given
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        let a_store = DummyStore {
+            add_pet_value: Some(pet.clone()),
+            ..DummyStore::new_with_delete_person(false)
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("delete_policy"), String::from("cascade"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("delete_policy"), String::from("later"));
+        let runtime = Runtime::new().expect("unable to create runtime to test delete person");
+        // When
+        let got = runtime.block_on(handler::delete_person(
+            "1".to_string(),
+            HashMap::new(),
+            person_service.clone(),
+        ));
+        let got_cascade = runtime.block_on(handler::delete_person(
+            "1".to_string(),
+            params,
+            person_service.clone(),
+        ));
+        let got_bad_params = runtime.block_on(handler::delete_person(
+            "1".to_string(),
+            bad_params,
+            person_service,
+        ));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: PetsBlockingDelete = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.pets, vec![pet]);
+        assert_eq!(
+            got_cascade.unwrap().into_response().status(),
+            StatusCode::OK
+        );
+        assert!(got_bad_params.is_err());
+    }
+
     #[test]
     fn test_update_person() {
         // Given
@@ -227,11 +693,19 @@ mod handler_tests {
             id: PersonID("1".to_string()),
             first_name: "Luisfer".to_string(),
             last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
         let person_to_return = Some(Person {
             id: PersonID("1".to_string()),
             first_name: "Luisfer".to_string(),
             last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         });
         let a_censor = DummyCensor::new("".to_string(), false);
         let a_store = DummyStore::new_with_update_person(person_to_return, false);
@@ -240,12 +714,20 @@ mod handler_tests {
             id: PersonID("1".to_string()),
             first_name: "Luisfer".to_string(),
             last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
         let runtime = Runtime::new().expect("unable to create runtime to test update person");
         // When
-        let got = runtime.block_on(handler::update_person(a_person, person_service));
+        let got = runtime.block_on(handler::update_person(
+            Some("\"1\"".to_string()),
+            a_person,
+            person_service,
+        ));
         // Then
-        assert_eq!(false, got.is_err());
+        assert!(got.is_ok());
 
         let got_result = match got {
             Ok(reply) => {
@@ -272,15 +754,23 @@ mod handler_tests {
             id: PersonID("1".to_string()),
             first_name: "Luisfer".to_string(),
             last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         };
         let a_censor = DummyCensor::new("".to_string(), false);
         let a_store = DummyStore::new_with_update_person(None, true);
         let person_service = service::Service::new(a_store, a_censor);
         let runtime = Runtime::new().expect("unable to create runtime to test update person");
         // When
-        let got = runtime.block_on(handler::update_person(a_person, person_service));
+        let got = runtime.block_on(handler::update_person(
+            Some("\"1\"".to_string()),
+            a_person,
+            person_service,
+        ));
         // Then
-        assert_eq!(true, got.is_err());
+        assert!(got.is_err());
 
         let got_error = match got {
             Ok(value) => panic!("unexpected result {:?}", value.into_response()),
@@ -289,22 +779,761 @@ mod handler_tests {
 
         if let Some(e) = got_error.find::<error::Error>() {
             assert_eq!(*e, error::Error::UpdatePersonError);
-            return;
         }
     }
 
+    #[test]
+    fn test_get_people_rejects_offset() {
+        // Given
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_get_people(vec![], false);
+        let person_service = service::Service::new(a_store, a_censor);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("offset"), String::from("20"));
+        let runtime = Runtime::new().expect("unable to create runtime to test get people");
+        // When
+        let got = runtime.block_on(handler::get_people(params, person_service));
+        // Then
+        let got_error = match got {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::OffsetNotSupported)
+        );
+
+        let response = runtime
+            .block_on(error::return_error(got_error))
+            .unwrap()
+            .into_response();
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+
+    #[test]
+    fn test_get_person_returns_not_found_status() {
+        // Given
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_person_not_found();
+        let person_service = service::Service::new(a_store, a_censor);
+        let person_id = "2000".to_string();
+        let runtime = Runtime::new().expect("unable to create runtime to test get person");
+        // When
+        let got = runtime.block_on(handler::get_person(
+            person_id,
+            HashMap::new(),
+            person_service,
+        ));
+        // Then
+        let got_error = match got {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::PersonNotFound)
+        );
+
+        let response = runtime
+            .block_on(error::return_error(got_error))
+            .unwrap()
+            .into_response();
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[test]
+    fn test_restore_person() {
+        // Given
+        let person_store = Person {
+            id: PersonID("1".to_string()),
+            first_name: "Luis".to_string(),
+            last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 3,
+            deleted_at: None,
+            profile: Profile::default(),
+        };
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_get_person(Some(person_store.clone()), false);
+        let person_service = service::Service::new(a_store, a_censor);
+        let runtime = Runtime::new().expect("unable to create runtime to test restore person");
+        // When
+        let got = runtime.block_on(handler::restore_person("1".to_string(), person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.headers().get("ETag").unwrap(), "\"3\"");
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: Person = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got, person_store);
+    }
+
+    #[test]
+    fn test_purge_people() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("retention_days"), String::from("7"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("retention_days"), String::from("a week"));
+        let runtime = Runtime::new().expect("unable to create runtime to test purge people");
+        // When
+        let got = runtime.block_on(handler::purge_people(
+            params,
+            service::Service::new(
+                DummyStore::new_with_purge_people(2, false),
+                DummyCensor::new("".to_string(), false),
+            ),
+        ));
+        let got_bad_params = runtime.block_on(handler::purge_people(
+            bad_params,
+            service::Service::new(
+                DummyStore::new_with_purge_people(2, false),
+                DummyCensor::new("".to_string(), false),
+            ),
+        ));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: PurgePeopleSuccess = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got, PurgePeopleSuccess { purged: 2 });
+        assert!(got_bad_params.is_err());
+    }
+
+    #[test]
+    fn test_purge_people_rejects_retention_days_out_of_range() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test purge people");
+        for retention_days in ["-1", "36501", "9223372036854775807"] {
+            let mut params: HashMap<String, String> = HashMap::new();
+            params.insert(String::from("retention_days"), retention_days.to_string());
+            // When
+            let got = runtime.block_on(handler::purge_people(
+                params,
+                service::Service::new(
+                    DummyStore::new_with_purge_people(2, false),
+                    DummyCensor::new("".to_string(), false),
+                ),
+            ));
+            // Then
+            let got_error = match got {
+                Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+                Err(err) => err,
+            };
+            assert_eq!(
+                got_error.find::<error::Error>(),
+                Some(&error::Error::InvalidRetentionDays)
+            );
+            let response = runtime
+                .block_on(error::return_error(got_error))
+                .unwrap()
+                .into_response();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        }
+    }
+
+    #[test]
+    fn test_get_audit() {
+        // Given
+        let person_service = service::Service::new(
+            DummyStore::new_with_purge_people(2, false),
+            DummyCensor::new("".to_string(), false),
+        );
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("entity_id"), String::from("1"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("since"), String::from("yesterday"));
+        let runtime = Runtime::new().expect("unable to create runtime to test get audit");
+        runtime
+            .block_on(handler::purge_people(
+                HashMap::new(),
+                person_service.clone(),
+            ))
+            .unwrap();
+        // When
+        let got = runtime.block_on(handler::get_audit(params, person_service.clone()));
+        let got_bad_params = runtime.block_on(handler::get_audit(bad_params, person_service));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: Vec<AuditEntry> = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].entity, AuditEntity::Person);
+        assert_eq!(got[0].entity_id, "1");
+        assert_eq!(got[0].action, AuditAction::Purge);
+        assert_eq!(got[0].after, None);
+        let err = got_bad_params.err().unwrap();
+        assert_eq!(
+            err.find::<error::Error>(),
+            Some(&error::Error::InvalidAuditQuery(
+                "since yesterday is not an RFC 3339 time".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_pets() {
+        // Given
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        let a_store = DummyStore {
+            add_pet_value: Some(pet.clone()),
+            add_pet_error: Some(false),
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let runtime = Runtime::new().expect("unable to create runtime to test pets");
+        // When
+        let got_added = runtime.block_on(handler::add_pet(
+            "1".to_string(),
+            PetName {
+                name: "Firulais".to_string(),
+            },
+            person_service.clone(),
+        ));
+        let got_transferred = runtime.block_on(handler::transfer_pet(
+            "10".to_string(),
+            PetTransfer {
+                person_id: PersonID("2".to_string()),
+            },
+            person_service.clone(),
+        ));
+        let got_not_found = runtime.block_on(handler::get_pet("2000".to_string(), person_service));
+        // Then
+        let got_added = got_added.unwrap().into_response();
+        assert_eq!(got_added.status(), StatusCode::CREATED);
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(got_added.into_body()))
+            .unwrap();
+        let got: Pet = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got, pet);
+
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got_transferred.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: Pet = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.person_id, Some(PersonID("2".to_string())));
+
+        let got_error = match got_not_found {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::PetNotFound)
+        );
+    }
+
+    #[test]
+    fn test_add_pet_returns_not_found_status() {
+        // Given
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_person_not_found();
+        let person_service = service::Service::new(a_store, a_censor);
+        let runtime = Runtime::new().expect("unable to create runtime to test add pet");
+        // When
+        let got = runtime.block_on(handler::add_pet(
+            "2000".to_string(),
+            PetName {
+                name: "Firulais".to_string(),
+            },
+            person_service,
+        ));
+        // Then
+        let got_error = match got {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::PersonNotFound)
+        );
+
+        let response = runtime
+            .block_on(error::return_error(got_error))
+            .unwrap()
+            .into_response();
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[test]
+    fn test_merge_people() {
+        // Given
+        let target = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let source = Person::new(
+            PersonID("2".to_string()),
+            "Esmé".to_string(),
+            "Wetherwax".to_string(),
+        );
+        let a_store = DummyStore {
+            relatives: vec![target.clone(), source.clone()],
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let request = MergeRequest {
+            source_id: source.id.clone(),
+            from_source: vec![],
+        };
+        let runtime = Runtime::new().expect("unable to create runtime to test merge people");
+        // When
+        let got = runtime.block_on(handler::merge_people(
+            "1".to_string(),
+            Some("\"1\"".to_string()),
+            request.clone(),
+            person_service.clone(),
+        ));
+        let got_old_version = runtime.block_on(handler::merge_people(
+            "1".to_string(),
+            Some("\"7\"".to_string()),
+            request,
+            person_service,
+        ));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["ETag"], "\"2\"");
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(response.into_body()))
+            .unwrap();
+        let got: Person = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.id, target.id);
+        assert_eq!(got.last_name, "Weatherwax");
+
+        let got_old_version = match got_old_version {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_old_version.find::<error::Error>(),
+            Some(&error::Error::PreconditionFailed)
+        );
+    }
+
+    #[test]
+    fn test_get_merged_person() {
+        // Given
+        let survivor = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let a_store = DummyStore {
+            relatives: vec![survivor],
+            merges: vec![Merge::new(
+                PersonID("2".to_string()),
+                PersonID("1".to_string()),
+            )],
+            ..Default::default()
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let params = HashMap::from([(String::from("expand"), String::from("pets"))]);
+        let runtime = Runtime::new().expect("unable to create runtime to test get person");
+        // When
+        let got = runtime.block_on(handler::get_person("2".to_string(), params, person_service));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.headers()["Location"], "/people/1?expand=pets");
+    }
+
+    #[test]
+    fn test_relationships() {
+        // Given
+        let people: Vec<Person> = ["1", "2"]
+            .into_iter()
+            .map(|id| Person::new(PersonID(id.to_string()), id.to_string(), id.to_string()))
+            .collect();
+        let relationship = Relationship {
+            person_id: PersonID("1".to_string()),
+            related_id: PersonID("2".to_string()),
+            kind: RelationshipKind::Guardian,
+        };
+        let a_store = DummyStore::new_with_relationships(
+            people,
+            vec![relationship.clone(), relationship.reciprocal()],
+        );
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("depth"), String::from("ten"));
+        let runtime = Runtime::new().expect("unable to create runtime to test relationships");
+        // When
+        let got_added = runtime.block_on(handler::add_relationship(
+            "2".to_string(),
+            NewRelationship {
+                related_id: PersonID("1".to_string()),
+                kind: RelationshipKind::Spouse,
+            },
+            person_service.clone(),
+        ));
+        let got_graph = runtime.block_on(handler::get_relationship_graph(
+            "1".to_string(),
+            HashMap::new(),
+            person_service.clone(),
+        ));
+        let got_bad_depth = runtime.block_on(handler::get_relationship_graph(
+            "1".to_string(),
+            bad_params,
+            person_service.clone(),
+        ));
+        let got_deleted = runtime.block_on(handler::delete_relationship(
+            "2".to_string(),
+            "1".to_string(),
+            person_service,
+        ));
+        // Then
+        let got_error = match got_added {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::RelationshipExists)
+        );
+
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got_graph.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: RelationshipGraph = serde_json::from_slice(&got_body_bytes).unwrap();
+        let depths: Vec<(String, u32)> = got
+            .people
+            .iter()
+            .map(|reached| (reached.person.id.to_string(), reached.depth))
+            .collect();
+        assert_eq!(depths, vec![("1".to_string(), 0), ("2".to_string(), 1)]);
+        assert_eq!(got.relationships, vec![relationship]);
+
+        let got_error = match got_bad_depth {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::InvalidGraphDepth)
+        );
+
+        assert_eq!(
+            got_deleted.unwrap().into_response().status(),
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn test_get_person_returns_etag() {
+        // Given
+        let person_store = Person {
+            id: PersonID("1".to_string()),
+            first_name: "Luis".to_string(),
+            last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 7,
+            deleted_at: None,
+            profile: Profile::default(),
+        };
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_get_person(Some(person_store), false);
+        let person_service = service::Service::new(a_store, a_censor);
+        let runtime = Runtime::new().expect("unable to create runtime to test get person");
+        // When
+        let got = runtime.block_on(handler::get_person(
+            "1".to_string(),
+            HashMap::new(),
+            person_service,
+        ));
+        // Then
+        let response = got.unwrap().into_response();
+        assert_eq!(response.headers().get("ETag").unwrap(), "\"7\"");
+    }
+
+    #[test]
+    fn test_get_person_with_pets() {
+        // Given
+        let person_store = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        let a_store = DummyStore {
+            add_pet_value: Some(pet.clone()),
+            ..DummyStore::new_with_get_person(Some(person_store.clone()), false)
+        };
+        let person_service =
+            service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("expand"), String::from("pets"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("expand"), String::from("friends"));
+        let runtime = Runtime::new().expect("unable to create runtime to test get person");
+        // When
+        let got = runtime.block_on(handler::get_person(
+            "1".to_string(),
+            params,
+            person_service.clone(),
+        ));
+        let got_bad_params = runtime.block_on(handler::get_person(
+            "1".to_string(),
+            bad_params,
+            person_service,
+        ));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: PersonWithPets = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(
+            got,
+            PersonWithPets {
+                person: person_store,
+                pets: Some(vec![pet]),
+            }
+        );
+        assert!(got_bad_params.is_err());
+    }
+
+    #[test]
+    fn test_update_person_without_if_match() {
+        // Given
+        let a_person = Person {
+            id: PersonID("1".to_string()),
+            first_name: "Luisfer".to_string(),
+            last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
+        };
+        let a_censor = DummyCensor::new("".to_string(), false);
+        let a_store = DummyStore::new_with_update_person(None, true);
+        let person_service = service::Service::new(a_store, a_censor);
+        let runtime = Runtime::new().expect("unable to create runtime to test update person");
+        // When
+        let got = runtime.block_on(handler::update_person(None, a_person, person_service));
+        // Then
+        let got_error = match got {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::PreconditionRequired)
+        );
+    }
+
+    #[test]
+    fn test_update_person_with_stale_version() {
+        // Given
+        let a_person = Person {
+            id: PersonID("1".to_string()),
+            first_name: "Luisfer".to_string(),
+            last_name: "Luisfer".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
+        };
+        let runtime = Runtime::new().expect("unable to create runtime to test update person");
+        // When
+        let got = runtime.block_on(handler::update_person(
+            Some("W/\"1\"".to_string()),
+            a_person,
+            service::Service::new(
+                DummyStore::new_with_version_conflict(),
+                DummyCensor::new("".to_string(), false),
+            ),
+        ));
+        // Then
+        let got_error = match got {
+            Ok(value) => panic!("unexpected result {:?}", value.into_response()),
+            Err(err) => err,
+        };
+        assert_eq!(
+            got_error.find::<error::Error>(),
+            Some(&error::Error::PreconditionFailed)
+        );
+    }
+
+    #[test]
+    fn test_get_person_history() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Ogg".to_string(),
+        );
+        let renamed = Person {
+            last_name: "Weatherwax".to_string(),
+            version: 2,
+            ..person.clone()
+        };
+        let person_service = service::Service::new(
+            DummyStore {
+                history: vec![
+                    PersonVersion {
+                        saved_at: "2026-10-01T00:00:00Z".parse().unwrap(),
+                        person,
+                    },
+                    PersonVersion {
+                        saved_at: "2026-10-05T00:00:00Z".parse().unwrap(),
+                        person: renamed,
+                    },
+                ],
+                ..Default::default()
+            },
+            DummyCensor::new("".to_string(), false),
+        );
+        let mut as_of: HashMap<String, String> = HashMap::new();
+        as_of.insert(String::from("as_of"), String::from("2026-10-02T00:00:00Z"));
+        let mut bad_as_of: HashMap<String, String> = HashMap::new();
+        bad_as_of.insert(String::from("as_of"), String::from("yesterday"));
+        let runtime = Runtime::new().expect("unable to create runtime to test person history");
+        // When
+        let got = runtime.block_on(handler::get_person_history(
+            "1".to_string(),
+            person_service.clone(),
+        ));
+        let got_unknown = runtime.block_on(handler::get_person_history(
+            "2".to_string(),
+            person_service.clone(),
+        ));
+        let got_as_of = runtime.block_on(handler::get_person(
+            "1".to_string(),
+            as_of,
+            person_service.clone(),
+        ));
+        let got_bad_as_of = runtime.block_on(handler::get_person(
+            "1".to_string(),
+            bad_as_of,
+            person_service,
+        ));
+        // Then
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got: Vec<PersonRevision> = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0].changes, vec![]);
+        assert_eq!(got[1].changes[0].field, "last_name");
+        assert_eq!(
+            got_unknown.err().unwrap().find::<error::Error>(),
+            Some(&error::Error::PersonNotFound)
+        );
+        let got_as_of = got_as_of.unwrap().into_response();
+        assert_eq!(got_as_of.headers().get("ETag"), None);
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(got_as_of.into_body()))
+            .unwrap();
+        let got_as_of: Person = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got_as_of.last_name, "Ogg");
+        assert_eq!(
+            got_bad_as_of.err().unwrap().find::<error::Error>(),
+            Some(&error::Error::InvalidAsOf("yesterday".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_add_and_get_webhooks() {
+        // Given
+        let new_webhook = NewWebhook {
+            url: "https://partner.example.com/hooks".to_string(),
+            secret: "a-long-enough-secret".to_string(),
+            events: vec![EventKind::PetAdded],
+        };
+        let person_service = service::Service::new(
+            DummyStore {
+                webhooks: vec![new_webhook.to_webhook()],
+                ..Default::default()
+            },
+            DummyCensor::new("".to_string(), false),
+        );
+        let runtime = Runtime::new().expect("unable to create runtime to test webhooks");
+        // When
+        let got_added = runtime.block_on(handler::add_webhook(
+            new_webhook.clone(),
+            person_service.clone(),
+        ));
+        let got_short_secret = runtime.block_on(handler::add_webhook(
+            NewWebhook {
+                secret: "short".to_string(),
+                ..new_webhook
+            },
+            person_service.clone(),
+        ));
+        let got_webhooks = runtime.block_on(handler::get_webhooks(person_service.clone()));
+        let got_unknown_deliveries = runtime.block_on(handler::get_webhook_deliveries(
+            "unknown".to_string(),
+            HashMap::new(),
+            person_service,
+        ));
+        // Then
+        let got_added = got_added.unwrap().into_response();
+        assert_eq!(got_added.status(), StatusCode::CREATED);
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(got_added.into_body()))
+            .unwrap();
+        let got_added: serde_json::Value = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got_added["events"], serde_json::json!(["PetAdded"]));
+        assert_eq!(got_added.get("secret"), None);
+        assert_eq!(
+            got_short_secret.err().unwrap().find::<error::Error>(),
+            Some(&error::Error::InvalidWebhook(
+                "secret must have at least 16 characters".to_string()
+            ))
+        );
+        let got_body_bytes = runtime
+            .block_on(hyper::body::to_bytes(
+                got_webhooks.unwrap().into_response().into_body(),
+            ))
+            .unwrap();
+        let got_webhooks: Vec<Webhook> = serde_json::from_slice(&got_body_bytes).unwrap();
+        assert_eq!(got_webhooks.len(), 1);
+        assert_eq!(got_webhooks[0].secret, "");
+        assert_eq!(
+            got_unknown_deliveries.err().unwrap().find::<error::Error>(),
+            Some(&error::Error::WebhookNotFound)
+        );
+    }
+
     fn new_people_result(
         people: Vec<Person>,
         err: Option<error::Error>,
     ) -> Result<impl Reply, Rejection> {
-        if err.is_some() {
-            return Err(warp::reject::custom(err.unwrap()));
+        if let Some(err) = err {
+            return Err(warp::reject::custom(err));
         }
 
         Ok(warp::reply::json(&people))
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default)]
     struct DummyStore {
         get_people_values: Option<Vec<Person>>,
         get_people_error: Option<bool>,
@@ -318,108 +1547,528 @@ mod handler_tests {
         delete_person_error: Option<bool>,
         add_pet_value: Option<Pet>,
         add_pet_error: Option<bool>,
+        person_not_found: bool,
+        purged_people: u64,
+        purge_people_error: bool,
+        relatives: Vec<Person>,
+        relationships: Vec<Relationship>,
+        merges: Vec<Merge>,
+        version_conflict: bool,
+        audit: Arc<Mutex<Vec<AuditEntry>>>,
+        history: Vec<PersonVersion>,
+        webhooks: Vec<Webhook>,
+        webhook_deliveries: Arc<Mutex<Vec<WebhookDelivery>>>,
+        live_events: LiveEvents,
     }
 
     impl DummyStore {
         fn new_with_get_people(people: Vec<Person>, is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.get_people_values = Some(people);
-            dummy_store.get_people_error = Some(is_error);
-
-            dummy_store
+            DummyStore {
+                get_people_values: Some(people),
+                get_people_error: Some(is_error),
+                ..Default::default()
+            }
         }
         fn new_with_get_person(person: Option<Person>, is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.get_person_value = person;
-            dummy_store.get_person_error = Some(is_error);
-
-            dummy_store
+            DummyStore {
+                get_person_value: person,
+                get_person_error: Some(is_error),
+                ..Default::default()
+            }
         }
         fn new_with_add_person(person: Option<Person>, is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.add_person_value = person;
-            dummy_store.add_person_error = Some(is_error);
-
-            dummy_store
+            DummyStore {
+                add_person_value: person,
+                add_person_error: Some(is_error),
+                ..Default::default()
+            }
         }
         fn new_with_delete_person(is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.delete_person_error = Some(is_error);
-            dummy_store.delete_person_value = Some(is_error);
-
-            dummy_store
+            DummyStore {
+                delete_person_error: Some(is_error),
+                delete_person_value: Some(is_error),
+                ..Default::default()
+            }
         }
-        fn new_with_update_person(person: Option<Person>, is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.update_person_error = Some(is_error);
-            dummy_store.update_person_value = person;
-
-            dummy_store
+        fn new_with_purge_people(purged: u64, is_error: bool) -> Self {
+            DummyStore {
+                purged_people: purged,
+                purge_people_error: is_error,
+                ..Default::default()
+            }
         }
-    }
-
-    impl Default for DummyStore {
-        fn default() -> DummyStore {
+        fn new_with_relationships(
+            relatives: Vec<Person>,
+            relationships: Vec<Relationship>,
+        ) -> Self {
+            DummyStore {
+                relatives,
+                relationships,
+                ..Default::default()
+            }
+        }
+        fn new_with_person_not_found() -> Self {
+            DummyStore {
+                person_not_found: true,
+                ..Default::default()
+            }
+        }
+        fn new_with_version_conflict() -> Self {
+            DummyStore {
+                version_conflict: true,
+                ..Default::default()
+            }
+        }
+        fn new_with_update_person(person: Option<Person>, is_error: bool) -> Self {
             DummyStore {
-                get_people_values: Default::default(),
-                get_people_error: Default::default(),
-                get_person_value: Default::default(),
-                get_person_error: Default::default(),
-                add_person_value: Default::default(),
-                add_person_error: Default::default(),
-                update_person_value: Default::default(),
-                update_person_error: Default::default(),
-                delete_person_value: Default::default(),
-                delete_person_error: Default::default(),
-                add_pet_value: Default::default(),
-                add_pet_error: Default::default(),
+                update_person_error: Some(is_error),
+                update_person_value: person,
+                ..Default::default()
             }
         }
     }
 
     #[async_trait]
     impl storage::Storer for DummyStore {
-        async fn get_people(&self, _: Option<i32>, _: i32) -> Result<Vec<Person>, error::Error> {
+        async fn get_people(
+            &self,
+            _: &PeopleQuery,
+            _: i32,
+            _: Option<Cursor>,
+        ) -> Result<Vec<Person>, error::Error> {
             match self.get_people_error.unwrap() {
                 false => Ok(self.get_people_values.clone().unwrap()),
                 true => Err(error::Error::GetPeopleError),
             }
         }
 
-        async fn get_person(&self, _: PersonID) -> Result<Person, error::Error> {
-            match &self.get_person_error.unwrap() {
-                false => Ok(self.get_person_value.clone().unwrap()),
-                true => Err(error::Error::GetPersonError),
+        async fn get_person(&self, person_id: PersonID, _: bool) -> Result<Person, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+            if let Some(person) = self.relatives.iter().find(|person| person.id == person_id) {
+                return Ok(person.clone());
+            }
+            // merged people are deleted.
+            if self.merges.iter().any(|merge| merge.source_id == person_id) {
+                return Err(error::Error::PersonNotFound);
+            }
+
+            match self.get_person_error {
+                Some(true) => Err(error::Error::GetPersonError),
+                _ => self
+                    .get_person_value
+                    .clone()
+                    .ok_or(error::Error::PersonNotFound),
             }
         }
 
-        async fn add_person(&self, _: Person) -> Result<Person, error::Error> {
+        async fn add_person(&self, _: Person, _: &AuditContext) -> Result<Person, error::Error> {
             match &self.add_person_error.unwrap() {
                 false => Ok(self.add_person_value.clone().unwrap()),
                 true => Err(error::Error::CreatePersonError),
             }
         }
 
-        async fn update_person(&self, _: Person) -> Result<Person, error::Error> {
+        async fn add_people(
+            &self,
+            people: Vec<Person>,
+            _: &AuditContext,
+        ) -> Result<Vec<Person>, error::Error> {
+            match self.add_person_error.unwrap_or(false) {
+                false => Ok(people),
+                true => Err(error::Error::CreatePersonError),
+            }
+        }
+
+        async fn stream_people(
+            &self,
+            include_pets: bool,
+        ) -> Result<storage::PeopleStream, error::Error> {
+            let people: Vec<Result<PersonWithPets, error::Error>> = self
+                .get_people_values
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|person| {
+                    Ok(PersonWithPets {
+                        person,
+                        pets: include_pets.then(Vec::new),
+                    })
+                })
+                .collect();
+            Ok(futures::stream::iter(people).boxed())
+        }
+
+        async fn search_people(&self, _: &str, _: i32) -> Result<Vec<PersonMatch>, error::Error> {
+            match self.get_people_error.unwrap() {
+                false => Ok(self
+                    .get_people_values
+                    .clone()
+                    .unwrap()
+                    .into_iter()
+                    .map(|person| PersonMatch { person, score: 1.0 })
+                    .collect()),
+                true => Err(error::Error::GetPeopleError),
+            }
+        }
+
+        async fn update_person(
+            &self,
+            _: Person,
+            audit: &AuditContext,
+        ) -> Result<Person, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+            if self.version_conflict {
+                return Err(error::Error::PersonVersionConflict);
+            }
+
             match &self.update_person_error.unwrap() {
-                false => Ok(self.update_person_value.clone().unwrap()),
+                false => {
+                    let updated = self.update_person_value.clone().unwrap();
+                    self.audit.lock().unwrap().push(audit.person_entry(
+                        AuditAction::Update,
+                        self.get_person_value.as_ref(),
+                        Some(&updated),
+                    ));
+                    Ok(updated)
+                }
                 true => Err(error::Error::UpdatePersonError),
             }
         }
 
-        async fn delete_person(&self, _: PersonID) -> Result<bool, error::Error> {
+        async fn delete_person(
+            &self,
+            _: PersonID,
+            policy: DeletePolicy,
+            audit: &AuditContext,
+        ) -> Result<bool, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+            if let (DeletePolicy::Refuse, Some(pet)) = (policy, &self.add_pet_value) {
+                return Err(error::Error::PersonHasPets(vec![pet.clone()]));
+            }
+
             match &self.delete_person_error.unwrap() {
-                false => Ok(self.delete_person_value.unwrap()),
+                false => {
+                    let mut entries = vec![audit.person_entry(
+                        AuditAction::Delete,
+                        self.get_person_value.as_ref(),
+                        None,
+                    )];
+                    if let (DeletePolicy::Orphan, Some(pet)) = (policy, &self.add_pet_value) {
+                        let orphan = Pet {
+                            person_id: None,
+                            ..pet.clone()
+                        };
+                        entries.push(audit.pet_entry(
+                            AuditAction::Update,
+                            Some(pet),
+                            Some(&orphan),
+                        ));
+                    }
+                    self.audit.lock().unwrap().extend(entries);
+                    Ok(self.delete_person_value.unwrap())
+                }
                 true => Err(error::Error::DeletePersonError),
             }
         }
 
-        async fn add_pet(&self, _: Pet) -> Result<Pet, error::Error> {
+        async fn restore_person(
+            &self,
+            _: PersonID,
+            _: &AuditContext,
+        ) -> Result<Person, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+
+            match &self.get_person_error.unwrap() {
+                false => Ok(self.get_person_value.clone().unwrap()),
+                true => Err(error::Error::RestorePersonError),
+            }
+        }
+
+        async fn merge_people(
+            &self,
+            person: Person,
+            _: PersonID,
+            _: &AuditContext,
+        ) -> Result<Person, error::Error> {
+            if self.version_conflict {
+                return Err(error::Error::PersonVersionConflict);
+            }
+
+            Ok(Person {
+                version: person.version + 1,
+                ..person
+            })
+        }
+
+        async fn get_merge(&self, source_id: PersonID) -> Result<Option<Merge>, error::Error> {
+            Ok(self
+                .merges
+                .iter()
+                .find(|merge| merge.source_id == source_id)
+                .cloned())
+        }
+
+        async fn purge_people(
+            &self,
+            _: DateTime<Utc>,
+            audit: &AuditContext,
+        ) -> Result<Vec<Person>, error::Error> {
+            if self.purge_people_error {
+                return Err(error::Error::PurgePeopleError);
+            }
+
+            let purged: Vec<Person> = (0..self.purged_people)
+                .map(|id| Person::new(PersonID(id.to_string()), id.to_string(), id.to_string()))
+                .collect();
+            self.audit.lock().unwrap().extend(
+                purged
+                    .iter()
+                    .map(|person| audit.person_entry(AuditAction::Purge, Some(person), None)),
+            );
+            Ok(purged)
+        }
+
+        async fn add_pet(&self, _: Pet, _: &AuditContext) -> Result<Pet, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+
             match &self.add_pet_error.unwrap() {
                 false => Ok(self.add_pet_value.clone().unwrap()),
                 true => Err(error::Error::AddPetError),
             }
         }
+
+        async fn get_pets(&self, owners: &[PersonID]) -> Result<Vec<Pet>, error::Error> {
+            Ok(self
+                .add_pet_value
+                .clone()
+                .into_iter()
+                .filter(|pet| {
+                    pet.person_id
+                        .as_ref()
+                        .is_some_and(|owner| owners.contains(owner))
+                })
+                .collect())
+        }
+
+        async fn get_pet(&self, pet_id: PetID) -> Result<Pet, error::Error> {
+            self.add_pet_value
+                .clone()
+                .filter(|pet| pet.id == pet_id)
+                .ok_or(error::Error::PetNotFound)
+        }
+
+        async fn update_pet(&self, pet: Pet, _: &AuditContext) -> Result<Pet, error::Error> {
+            self.get_pet(pet.id.clone()).await?;
+            match self.add_pet_error.unwrap_or(false) {
+                false => Ok(pet),
+                true => Err(error::Error::UpdatePetError),
+            }
+        }
+
+        async fn delete_pet(&self, pet_id: PetID, _: &AuditContext) -> Result<bool, error::Error> {
+            self.get_pet(pet_id).await?;
+            match self.add_pet_error.unwrap_or(false) {
+                false => Ok(true),
+                true => Err(error::Error::DeletePetError),
+            }
+        }
+
+        async fn add_relationship(
+            &self,
+            relationship: Relationship,
+        ) -> Result<Relationship, error::Error> {
+            if self.person_not_found {
+                return Err(error::Error::PersonNotFound);
+            }
+
+            let already_related = self.relationships.iter().any(|saved| {
+                saved.person_id == relationship.person_id
+                    && saved.related_id == relationship.related_id
+            });
+            match already_related {
+                false => Ok(relationship),
+                true => Err(error::Error::RelationshipExists),
+            }
+        }
+
+        async fn get_relationships(
+            &self,
+            people: &[PersonID],
+        ) -> Result<Vec<Relationship>, error::Error> {
+            Ok(self
+                .relationships
+                .iter()
+                .filter(|relationship| people.contains(&relationship.person_id))
+                .cloned()
+                .collect())
+        }
+
+        async fn delete_relationship(
+            &self,
+            person_id: PersonID,
+            related_id: PersonID,
+        ) -> Result<bool, error::Error> {
+            let related = self
+                .relationships
+                .iter()
+                .any(|saved| saved.person_id == person_id && saved.related_id == related_id);
+            match related {
+                true => Ok(true),
+                false => Err(error::Error::RelationshipNotFound),
+            }
+        }
+
+        async fn add_audit_entries(&self, entries: Vec<AuditEntry>) -> Result<(), error::Error> {
+            self.audit.lock().unwrap().extend(entries);
+            Ok(())
+        }
+
+        async fn get_person_history(
+            &self,
+            person_id: PersonID,
+        ) -> Result<Vec<PersonVersion>, error::Error> {
+            Ok(self
+                .history
+                .iter()
+                .filter(|version| version.person.id == person_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn get_people_as_of(&self, at: DateTime<Utc>) -> Result<Vec<Person>, error::Error> {
+            let mut people: Vec<Person> = Vec::new();
+            for version in self.history.iter().filter(|version| version.saved_at <= at) {
+                people.retain(|person| person.id != version.person.id);
+                people.push(version.person.clone());
+            }
+            Ok(people)
+        }
+
+        async fn get_pending_events(&self, _: i32) -> Result<Vec<DomainEvent>, error::Error> {
+            Ok(vec![])
+        }
+
+        async fn mark_events_sent(&self, _: &[i64]) -> Result<(), error::Error> {
+            Ok(())
+        }
+
+        async fn get_events_after(&self, _: i64, _: i32) -> Result<Vec<DomainEvent>, error::Error> {
+            Ok(vec![])
+        }
+
+        async fn get_last_event_id(&self) -> Result<i64, error::Error> {
+            Ok(0)
+        }
+
+        fn live_events(&self) -> &LiveEvents {
+            &self.live_events
+        }
+
+        async fn add_webhook(&self, webhook: Webhook) -> Result<Webhook, error::Error> {
+            Ok(webhook)
+        }
+
+        async fn get_webhooks(&self) -> Result<Vec<Webhook>, error::Error> {
+            Ok(self.webhooks.clone())
+        }
+
+        async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, error::Error> {
+            self.webhooks
+                .iter()
+                .find(|webhook| webhook.id == webhook_id)
+                .cloned()
+                .ok_or(error::Error::WebhookNotFound)
+        }
+
+        async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, error::Error> {
+            self.get_webhook(webhook_id).await.map(|_| true)
+        }
+
+        async fn add_webhook_delivery(
+            &self,
+            delivery: WebhookDelivery,
+        ) -> Result<(), error::Error> {
+            self.webhook_deliveries.lock().unwrap().push(delivery);
+            Ok(())
+        }
+
+        async fn get_webhook_deliveries(
+            &self,
+            webhook_id: WebhookID,
+            limit: i32,
+        ) -> Result<Vec<WebhookDelivery>, error::Error> {
+            Ok(self
+                .webhook_deliveries
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|delivery| delivery.webhook_id == webhook_id)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn get_webhook_delivery(
+            &self,
+            delivery_id: String,
+        ) -> Result<WebhookDelivery, error::Error> {
+            self.webhook_deliveries
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|delivery| delivery.id == delivery_id)
+                .cloned()
+                .ok_or(error::Error::WebhookDeliveryNotFound)
+        }
+
+        async fn add_people_unless_duplicates(
+            &self,
+            people: Vec<Person>,
+            detection: &DuplicateDetection,
+            _: &AuditContext,
+        ) -> Result<Vec<Result<Person, error::Error>>, error::Error> {
+            if self.add_person_error.unwrap_or(false) {
+                return Err(error::Error::CreatePersonError);
+            }
+
+            let existing = self.get_people_values.clone().unwrap_or_default();
+            Ok(people
+                .into_iter()
+                .map(|person| {
+                    let matches = detection.matches(&person, existing.clone());
+                    match matches.is_empty() {
+                        true => Ok(self.add_person_value.clone().unwrap_or(person)),
+                        false => Err(error::Error::PossibleDuplicates(matches)),
+                    }
+                })
+                .collect())
+        }
+
+        async fn get_audit_entries(
+            &self,
+            query: &AuditQuery,
+        ) -> Result<Vec<AuditEntry>, error::Error> {
+            Ok(self
+                .audit
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .filter(|entry| query.matches(entry))
+                .cloned()
+                .collect())
+        }
     }
 
     #[derive(Debug, Clone)]
@@ -430,10 +2079,7 @@ mod handler_tests {
 
     impl DummyCensor {
         fn new(response: String, is_error: bool) -> Self {
-            DummyCensor {
-                response: response,
-                is_error: is_error,
-            }
+            DummyCensor { response, is_error }
         }
     }
 
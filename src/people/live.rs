@@ -0,0 +1,103 @@
+use crate::people::storage;
+use crate::types::events::DomainEvent;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::error;
+
+/// How often a stream looks for events saved by other instances of the
+/// service, which cannot wake it up
+const POLL_PERIOD: Duration = Duration::from_secs(5);
+/// The most events read from the outbox at the same time
+const BATCH_SIZE: i32 = 100;
+
+/// EventStream yields the events of the outbox one at a time, as the changes
+/// are committed.
+pub type EventStream = BoxStream<'static, DomainEvent>;
+
+/// LiveEvents wakes up the streams of live events when a change is committed.
+/// The events themselves are read from the outbox, so a stream resumes after
+/// any event it was given and never skips one.
+#[derive(Debug, Clone)]
+pub struct LiveEvents {
+    changes: Arc<watch::Sender<()>>,
+}
+
+impl LiveEvents {
+    pub fn new() -> Self {
+        let (changes, _) = watch::channel(());
+        LiveEvents {
+            changes: Arc::new(changes),
+        }
+    }
+
+    /// notify tells the streams a change was committed, several changes made
+    /// before a stream reads the outbox wake it up once.
+    pub fn notify(&self) {
+        self.changes.send_replace(());
+    }
+
+    /// stream returns the events saved after the given one, then waits for
+    /// the next changes. It ends when the outbox cannot be read, a client
+    /// reconnects with the last event it got.
+    pub fn stream<T>(&self, store: T, after: i64) -> EventStream
+    where
+        T: storage::Storer + Send + Sync + 'static,
+    {
+        // subscribe before the first read, so no change can be missed.
+        let state = Cursor {
+            store,
+            changes: self.changes.subscribe(),
+            after,
+            pending: VecDeque::new(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    state.after = event.id;
+                    return Some((event, state));
+                }
+
+                match state.store.get_events_after(state.after, BATCH_SIZE).await {
+                    Ok(events) if !events.is_empty() => {
+                        state.pending.extend(events);
+                        continue;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("reading live events after {}: {:?}", state.after, e);
+                        return None;
+                    }
+                }
+
+                tokio::select! {
+                    changed = state.changes.changed() => {
+                        // without a sender only the polling is left.
+                        if changed.is_err() {
+                            tokio::time::sleep(POLL_PERIOD).await;
+                        }
+                    }
+                    _ = tokio::time::sleep(POLL_PERIOD) => {}
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+impl Default for LiveEvents {
+    fn default() -> Self {
+        LiveEvents::new()
+    }
+}
+
+/// Cursor is where a stream of live events is in the outbox.
+struct Cursor<T> {
+    store: T,
+    changes: watch::Receiver<()>,
+    after: i64,
+    pending: VecDeque<DomainEvent>,
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod live_tests {
+    use crate::errors::error;
+    use crate::people::censor::Censorious;
+    use crate::people::service::Service;
+    use crate::people::storage::Storer;
+    use crate::storage::memory::Store;
+    use crate::types::audit::AuditContext;
+    use crate::types::events::EventKind;
+    use crate::types::people::{Person, PersonID};
+    use crate::types::pets::NewPet;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use std::time::Duration;
+    use tokio::runtime::Runtime;
+
+    /// Shorter than the polling of the streams, so an event within it was
+    /// woken up by the change
+    const WAKE_UP_TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_live_events_resume_after_last_event() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test live events");
+        let store = Store::new();
+        runtime.block_on(add_people(&store, &["7", "8", "9"]));
+        let service = Service::new(store, DummyCensor);
+        // When
+        let got: Vec<(i64, String)> = runtime.block_on(async {
+            service
+                .live_events(Some(1))
+                .await
+                .unwrap()
+                .take(2)
+                .map(|event| (event.id, event.aggregate_id))
+                .collect()
+                .await
+        });
+        // Then
+        assert_eq!(got, vec![(2, "8".to_string()), (3, "9".to_string())]);
+    }
+
+    #[test]
+    fn test_live_events_wake_up_on_change() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test live events");
+        let store = Store::new();
+        runtime.block_on(add_people(&store, &["7"]));
+        let service = Service::new(store, DummyCensor);
+        let mut events = runtime.block_on(service.live_events(None)).unwrap();
+        // When
+        let got = runtime.block_on(async {
+            service
+                .add_pet(NewPet::new("Greebo".to_string(), PersonID("7".to_string())))
+                .await
+                .unwrap();
+            tokio::time::timeout(WAKE_UP_TIMEOUT, events.next()).await
+        });
+        // Then
+        let event = got.expect("the change did not wake up the stream").unwrap();
+        assert_eq!(event.id, 2);
+        assert_eq!(event.kind, EventKind::PetAdded);
+        assert_eq!(event.payload["name"], "Greebo");
+    }
+
+    #[test]
+    fn test_live_events_wake_up_on_change_saved_by_store() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test live events");
+        let store = Store::new();
+        let service = Service::new(store.clone(), DummyCensor);
+        let mut events = runtime.block_on(service.live_events(None)).unwrap();
+        // When
+        let got = runtime.block_on(async {
+            add_people(&store, &["7"]).await;
+            tokio::time::timeout(WAKE_UP_TIMEOUT, events.next()).await
+        });
+        // Then
+        let event = got.expect("the change did not wake up the stream").unwrap();
+        assert_eq!(event.kind, EventKind::PersonCreated);
+        assert_eq!(event.aggregate_id, "7");
+    }
+
+    async fn add_people(store: &Store, ids: &[&str]) {
+        for id in ids {
+            store
+                .add_person(
+                    Person::new(
+                        PersonID(id.to_string()),
+                        "Esme".to_string(),
+                        "Weatherwax".to_string(),
+                    ),
+                    &AuditContext::default(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct DummyCensor;
+
+    #[async_trait]
+    impl Censorious for DummyCensor {
+        async fn censor(&self, word: String) -> Result<String, error::Error> {
+            Ok(word)
+        }
+
+        async fn censor_with_backoff(&self, word: String) -> Result<String, error::Error> {
+            Ok(word)
+        }
+    }
+}
@@ -1,7 +1,15 @@
 pub mod censor;
+pub mod duplicates;
+mod duplicates_tests;
 pub mod handler;
 mod handler_tests;
+pub mod live;
+mod live_tests;
+pub mod outbox;
+mod outbox_tests;
 pub mod service;
 #[cfg(test)]
 mod service_test;
 pub mod storage;
+pub mod webhooks;
+mod webhooks_tests;
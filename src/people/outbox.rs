@@ -0,0 +1,95 @@
+use crate::errors::error::Error;
+use crate::people::storage;
+use crate::types::events::DomainEvent;
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{debug, error};
+
+/// The most events read from the outbox at the same time
+const DEFAULT_BATCH_SIZE: i32 = 100;
+
+/// Publisher delivers the events of the outbox to the systems downstream.
+#[async_trait]
+pub trait Publisher {
+    async fn publish(&self, event: &DomainEvent) -> Result<(), Error>;
+}
+
+/// Dispatcher delivers the pending events of the outbox in the order they
+/// were saved. An event is marked as sent only after it was published, so
+/// after a crash it may be published again but it is never lost.
+#[derive(Debug, Clone)]
+pub struct Dispatcher<T: storage::Storer, P: Publisher> {
+    store: T,
+    publisher: P,
+    batch_size: i32,
+}
+
+impl<T: storage::Storer, P: Publisher> Dispatcher<T, P> {
+    pub fn new(store: T, publisher: P) -> Self {
+        Dispatcher {
+            store,
+            publisher,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// with_batch_size sets how many events are read from the outbox at the
+    /// same time.
+    pub fn with_batch_size(mut self, batch_size: i32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// dispatch publishes a batch of pending events and returns how many
+    /// were sent. It stops at the first event which cannot be published, so
+    /// no event is delivered before the ones saved earlier.
+    pub async fn dispatch(&self) -> Result<usize, Error> {
+        let events = self.store.get_pending_events(self.batch_size).await?;
+
+        let mut sent = Vec::with_capacity(events.len());
+        let mut failure = None;
+        for event in events.iter() {
+            if let Err(e) = self.publisher.publish(event).await {
+                failure = Some(e);
+                break;
+            }
+            sent.push(event.id);
+        }
+
+        if !sent.is_empty() {
+            self.store.mark_events_sent(&sent).await?;
+            debug!("{} events were sent", sent.len());
+        }
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(sent.len()),
+        }
+    }
+}
+
+impl<T, P> Dispatcher<T, P>
+where
+    T: storage::Storer + Send + Sync + 'static,
+    P: Publisher + Send + Sync + 'static,
+{
+    /// spawn dispatches the pending events every given period, a full batch
+    /// is followed by the next one right away.
+    pub fn spawn(self, period: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                loop {
+                    match self.dispatch().await {
+                        Ok(sent) if sent == self.batch_size as usize => continue,
+                        Ok(_) => break,
+                        Err(e) => {
+                            error!("dispatching outbox events: {:?}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
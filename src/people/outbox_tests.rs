@@ -0,0 +1,114 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod outbox_tests {
+    use crate::errors::error;
+    use crate::people::outbox::{Dispatcher, Publisher};
+    use crate::people::storage::Storer;
+    use crate::storage::memory::Store;
+    use crate::types::audit::AuditContext;
+    use crate::types::events::{DomainEvent, EventKind};
+    use crate::types::people::{Person, PersonID};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_dispatch_in_order() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test outbox");
+        let store = Store::new();
+        runtime.block_on(add_people(&store, &["7", "8", "9"]));
+        let publisher = DummyPublisher::default();
+        let dispatcher = Dispatcher::new(store.clone(), publisher.clone()).with_batch_size(2);
+        // When
+        let got_first = runtime.block_on(dispatcher.dispatch());
+        let got_second = runtime.block_on(dispatcher.dispatch());
+        let got_none = runtime.block_on(dispatcher.dispatch());
+        // Then
+        assert_eq!(got_first, Ok(2));
+        assert_eq!(got_second, Ok(1));
+        assert_eq!(got_none, Ok(0));
+        assert_eq!(publisher.published(), vec!["7", "8", "9"]);
+        assert!(runtime
+            .block_on(store.get_pending_events(10))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dispatch_stops_at_failure() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test outbox");
+        let store = Store::new();
+        runtime.block_on(add_people(&store, &["7", "8", "9"]));
+        let publisher = DummyPublisher {
+            failing: Some("8".to_string()),
+            ..Default::default()
+        };
+        let dispatcher = Dispatcher::new(store.clone(), publisher.clone());
+        // When
+        let got = runtime.block_on(dispatcher.dispatch());
+        // Then
+        assert_eq!(
+            got,
+            Err(error::Error::PublishEventError("8 is down".to_string()))
+        );
+        assert_eq!(publisher.published(), vec!["7"]);
+        let pending: Vec<String> = runtime
+            .block_on(store.get_pending_events(10))
+            .unwrap()
+            .into_iter()
+            .map(|event| event.aggregate_id)
+            .collect();
+        assert_eq!(pending, vec!["8", "9"]);
+    }
+
+    async fn add_people(store: &Store, ids: &[&str]) {
+        for id in ids {
+            store
+                .add_person(
+                    Person::new(
+                        PersonID(id.to_string()),
+                        "Esme".to_string(),
+                        "Weatherwax".to_string(),
+                    ),
+                    &AuditContext::default(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct DummyPublisher {
+        published: Arc<Mutex<Vec<DomainEvent>>>,
+        /// The aggregate whose events cannot be published
+        failing: Option<String>,
+    }
+
+    impl DummyPublisher {
+        fn published(&self) -> Vec<String> {
+            self.published
+                .lock()
+                .unwrap()
+                .iter()
+                .inspect(|event| assert_eq!(event.kind, EventKind::PersonCreated))
+                .map(|event| event.aggregate_id.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl Publisher for DummyPublisher {
+        async fn publish(&self, event: &DomainEvent) -> Result<(), error::Error> {
+            if self.failing.as_ref() == Some(&event.aggregate_id) {
+                return Err(error::Error::PublishEventError(format!(
+                    "{} is down",
+                    event.aggregate_id
+                )));
+            }
+            self.published.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+}
@@ -1,17 +1,42 @@
 use crate::errors::error::Error;
 use crate::people::censor;
+use crate::people::duplicates::DuplicateDetection;
+use crate::people::live::EventStream;
 use crate::people::storage;
+use crate::people::webhooks::WebhookClient;
 use crate::types::{
-    people::{NewPerson, Person, PersonID},
-    pets::{NewPet, Pet},
+    audit::{AuditContext, AuditEntry, AuditQuery},
+    csv::ImportRow,
+    history::{self, PersonRevision},
+    merges::MergeRequest,
+    pagination::{Cursor, Pagination, MAX_PAGE_SIZE},
+    people::{NewPerson, PeoplePage, Person, PersonID, PersonMatch, PersonWithPets},
+    pets::{DeletePolicy, NewPet, Pet, PetID},
+    query::PeopleQuery,
+    relationships::{GraphPerson, NewRelationship, Relationship, RelationshipGraph},
+    webhooks::{NewWebhook, Webhook, WebhookDelivery, WebhookID},
 };
+use chrono::{DateTime, Duration, Utc};
+use futures::stream::{self, StreamExt};
 use log::error;
+use std::collections::HashMap;
 use tracing::debug;
 
+/// The most people whose names are censored at the same time in a batch
+const CENSOR_CONCURRENCY: usize = 8;
+/// The most people saved in the same transaction in a batch
+const BATCH_CHUNK_SIZE: usize = 100;
+/// The most merges followed to find the survivor of a merged person
+const MAX_MERGE_HOPS: usize = 10;
+
 #[derive(Debug, Clone)]
 pub struct Service<T: storage::Storer, C: censor::Censorious> {
     store: T,
     censorious: C,
+    delete_policy: DeletePolicy,
+    duplicate_detection: DuplicateDetection,
+    audit_context: AuditContext,
+    webhook_client: WebhookClient,
 }
 
 impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
@@ -19,13 +44,70 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
         Service {
             store: a_store,
             censorious: a_censorious,
+            delete_policy: DeletePolicy::default(),
+            duplicate_detection: DuplicateDetection::default(),
+            audit_context: AuditContext::default(),
+            webhook_client: WebhookClient::default(),
         }
     }
 
-    pub async fn get_people(&self, limit: Option<i32>, offset: i32) -> Result<Vec<Person>, Error> {
+    /// with_delete_policy sets what happens to the pets of a person deleted
+    /// without a policy of their own.
+    pub fn with_delete_policy(mut self, policy: DeletePolicy) -> Self {
+        self.delete_policy = policy;
+        self
+    }
+
+    /// with_duplicate_detection sets how a new person is compared to the
+    /// people already saved.
+    pub fn with_duplicate_detection(mut self, detection: DuplicateDetection) -> Self {
+        self.duplicate_detection = detection;
+        self
+    }
+
+    /// with_audit_context sets who makes the request the service is serving,
+    /// every change it makes is audited with it.
+    pub fn with_audit_context(mut self, context: AuditContext) -> Self {
+        self.audit_context = context;
+        self
+    }
+
+    /// with_webhook_client sets how webhooks are redelivered.
+    pub fn with_webhook_client(mut self, client: WebhookClient) -> Self {
+        self.webhook_client = client;
+        self
+    }
+
+    pub async fn get_people(
+        &self,
+        query: PeopleQuery,
+        pagination: Pagination,
+    ) -> Result<PeoplePage, Error> {
         debug!("start querying people");
 
-        let mut res = match self.store.get_people(limit, offset).await {
+        // a cursor only makes sense in the order it was taken from.
+        if let Some(after) = &pagination.after {
+            if after.sort != query.sort {
+                return Err(Error::InvalidCursor);
+            }
+        }
+
+        // ask for one more person to know whether there is a next page.
+        let res = match query.as_of {
+            Some(at) => self.store.get_people_as_of(at).await.map(|people| {
+                let people: Vec<Person> = people
+                    .into_iter()
+                    .map(|person| history::seen_at(person, at))
+                    .collect();
+                query.select(people.iter(), pagination.limit + 1, pagination.after)
+            }),
+            None => {
+                self.store
+                    .get_people(&query, pagination.limit + 1, pagination.after)
+                    .await
+            }
+        };
+        let mut res = match res {
             Ok(res) => res,
             Err(e) => {
                 error!("getting people from repository: {:?}", e);
@@ -33,16 +115,73 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
             }
         };
 
-        res.sort();
+        let mut next = None;
+        if res.len() > pagination.limit as usize {
+            res.truncate(pagination.limit as usize);
+            next = res.last().map(|last| Cursor::new(query.sort, last));
+        }
 
-        Ok(res)
+        Ok(PeoplePage { people: res, next })
+    }
+
+    /// expand_pets adds their pets to the people, reading the pets of all of
+    /// them at once.
+    pub async fn expand_pets(&self, people: Vec<Person>) -> Result<Vec<PersonWithPets>, Error> {
+        debug!("start expanding the pets of {} people", people.len());
+
+        let mut pets_by_owner: HashMap<PersonID, Vec<Pet>> = HashMap::new();
+        if !people.is_empty() {
+            let owners: Vec<PersonID> = people.iter().map(|person| person.id.clone()).collect();
+            let pets = self.store.get_pets(&owners).await.map_err(|e| {
+                error!("getting pets from repository: {:?}", e);
+                Error::GetPetsError
+            })?;
+            for pet in pets {
+                if let Some(owner) = pet.person_id.clone() {
+                    pets_by_owner.entry(owner).or_default().push(pet);
+                }
+            }
+        }
+
+        Ok(people
+            .into_iter()
+            .map(|person| PersonWithPets {
+                pets: Some(pets_by_owner.remove(&person.id).unwrap_or_default()),
+                person,
+            })
+            .collect())
+    }
+
+    pub async fn search_people(
+        &self,
+        terms: String,
+        limit: i32,
+    ) -> Result<Vec<PersonMatch>, Error> {
+        debug!("start searching people by {}", terms);
+
+        if terms.trim().is_empty() {
+            return Err(Error::MissingParameters);
+        }
+
+        match self.store.search_people(terms.trim(), limit).await {
+            Ok(matches) => Ok(matches),
+            Err(e) => {
+                error!("searching people in repository: {:?}", e);
+                Err(Error::SearchPeopleError)
+            }
+        }
     }
 
-    pub async fn get_person(&self, person_id: PersonID) -> Result<Person, Error> {
+    pub async fn get_person(
+        &self,
+        person_id: PersonID,
+        include_deleted: bool,
+    ) -> Result<Person, Error> {
         debug!("start getting person {}", person_id);
 
-        match self.store.get_person(person_id).await {
+        match self.store.get_person(person_id, include_deleted).await {
             Ok(person) => Ok(person),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
             Err(e) => {
                 error!("getting person from repository: {:?}", e);
                 Err(Error::GetPersonError)
@@ -50,10 +189,56 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
         }
     }
 
+    /// get_person_as_of returns the person as they were at the moment, pets
+    /// and relationships are not versioned so only the person is.
+    pub async fn get_person_as_of(
+        &self,
+        person_id: PersonID,
+        at: DateTime<Utc>,
+        include_deleted: bool,
+    ) -> Result<Person, Error> {
+        debug!("start getting person {} as of {}", person_id, at);
+
+        let versions = self.person_history(person_id).await?;
+        match history::person_as_of(&versions, at) {
+            Some(person) if include_deleted || person.deleted_at.is_none() => Ok(person),
+            _ => Err(Error::PersonNotFound),
+        }
+    }
+
+    /// get_person_history returns every version of the person, oldest first,
+    /// with what changed from the version before.
+    pub async fn get_person_history(
+        &self,
+        person_id: PersonID,
+    ) -> Result<Vec<PersonRevision>, Error> {
+        debug!("start getting history of person {}", person_id);
+
+        let versions = self.person_history(person_id).await?;
+        match versions.is_empty() {
+            true => Err(Error::PersonNotFound),
+            false => Ok(history::revisions(versions)),
+        }
+    }
+
+    async fn person_history(
+        &self,
+        person_id: PersonID,
+    ) -> Result<Vec<history::PersonVersion>, Error> {
+        self.store.get_person_history(person_id).await.map_err(|e| {
+            error!("getting person history from repository: {:?}", e);
+            Error::GetPersonHistoryError
+        })
+    }
+
     pub async fn update_person(&self, person: Person) -> Result<Person, Error> {
         debug!("start updating person {}", person.id);
-        match self.store.update_person(person).await {
+        person.profile.validate()?;
+
+        match self.store.update_person(person, &self.audit_context).await {
             Ok(person) => Ok(person),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(Error::PersonVersionConflict) => Err(Error::PersonVersionConflict),
             Err(e) => {
                 error!("updating person from repository: {:?}", e);
                 Err(Error::UpdatePersonError)
@@ -61,20 +246,113 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
         }
     }
 
-    pub async fn add_person(&self, new_person: NewPerson) -> Result<Person, Error> {
-        debug!("start adding people {:?}", new_person);
+    /// merge_people merges the source of the request into the person, who
+    /// keeps their id, takes the pets and relationships of the source and the
+    /// field values chosen from them. The source is deleted. When a version is
+    /// given the person must still have it.
+    pub async fn merge_people(
+        &self,
+        person_id: PersonID,
+        request: MergeRequest,
+        version: Option<i32>,
+    ) -> Result<Person, Error> {
+        debug!(
+            "start merging person {} into {}",
+            request.source_id, person_id
+        );
+
+        if person_id == request.source_id {
+            return Err(Error::InvalidMerge(
+                "a person cannot be merged into themselves".to_string(),
+            ));
+        }
 
-        let person = self.build_new_person(new_person).await;
+        let target = self.get_person(person_id, false).await?;
+        let source = self.get_person(request.source_id.clone(), false).await?;
+        if version.is_some_and(|version| version != target.version) {
+            return Err(Error::PersonVersionConflict);
+        }
+
+        let person = request.merged(&target, &source);
+        person.profile.validate()?;
+
+        match self
+            .store
+            .merge_people(person, request.source_id, &self.audit_context)
+            .await
+        {
+            Ok(person) => Ok(person),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(Error::PersonVersionConflict) => Err(Error::PersonVersionConflict),
+            Err(e) => {
+                error!("merging people in repository: {:?}", e);
+                Err(Error::MergePeopleError)
+            }
+        }
+    }
+
+    /// find_survivor follows the merges of a person who is not found to the
+    /// person they were merged into, if that person is not deleted.
+    pub async fn find_survivor(&self, person_id: PersonID) -> Result<Option<PersonID>, Error> {
+        let mut merged_id = person_id;
+        for _ in 0..MAX_MERGE_HOPS {
+            let merge = match self.store.get_merge(merged_id).await {
+                Ok(Some(merge)) => merge,
+                Ok(None) => return Ok(None),
+                Err(e) => {
+                    error!("getting merge from repository: {:?}", e);
+                    return Err(Error::GetPersonError);
+                }
+            };
 
-        if person.is_err() {
-            error!("checking bad words in first and last name values");
-            return Err(Error::ValidateBadWordsError);
+            match self.store.get_person(merge.target_id.clone(), false).await {
+                Ok(_) => return Ok(Some(merge.target_id)),
+                Err(Error::PersonNotFound) => merged_id = merge.target_id,
+                Err(e) => {
+                    error!("getting person from repository: {:?}", e);
+                    return Err(Error::GetPersonError);
+                }
+            }
         }
 
+        Ok(None)
+    }
+
+    /// add_person saves a new person unless their name matches the name of
+    /// someone already saved, then the possible duplicates are returned
+    /// instead. Forcing it saves the person anyway.
+    pub async fn add_person(&self, new_person: NewPerson, force: bool) -> Result<Person, Error> {
+        debug!("start adding people {:?}", new_person);
+
+        let person = match self.build_new_person(new_person).await {
+            Ok(person) => person,
+            Err(e @ Error::InvalidProfile(_)) => return Err(e),
+            Err(_) => {
+                error!("checking bad words in first and last name values");
+                return Err(Error::ValidateBadWordsError);
+            }
+        };
+
         debug!("new person with id {:?} is about to be saved", person);
 
-        match self.store.add_person(person.unwrap()).await {
+        let res = match force {
+            true => self.store.add_person(person, &self.audit_context).await,
+            false => self
+                .store
+                .add_people_unless_duplicates(
+                    vec![person],
+                    &self.duplicate_detection,
+                    &self.audit_context,
+                )
+                .await
+                .and_then(|mut results| results.pop().unwrap_or(Err(Error::CreatePersonError))),
+        };
+        match res {
             Ok(person) => Ok(person),
+            Err(Error::PossibleDuplicates(candidates)) => {
+                debug!("new person may already exist as {:?}", candidates);
+                Err(Error::PossibleDuplicates(candidates))
+            }
             Err(e) => {
                 error!("adding person into repository: {:?}", e);
                 Err(Error::CreatePersonError)
@@ -82,7 +360,164 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
         }
     }
 
+    /// add_people censors and saves every new person, the result of each one
+    /// is returned in the given order. Like add_person, a person who may
+    /// already exist is not saved unless force is true.
+    pub async fn add_people(
+        &self,
+        new_people: Vec<NewPerson>,
+        force: bool,
+    ) -> Vec<Result<Person, Error>> {
+        debug!("start adding {} people", new_people.len());
+
+        let mut results: Vec<Result<Person, Error>> = stream::iter(new_people)
+            .map(|new_person| self.build_new_person(new_person))
+            .buffered(CENSOR_CONCURRENCY)
+            .map(|person| {
+                person.map_err(|err| match err {
+                    Error::InvalidProfile(_) => err,
+                    _ => {
+                        error!("checking bad words in first and last name values: {}", err);
+                        Error::ValidateBadWordsError
+                    }
+                })
+            })
+            .collect()
+            .await;
+
+        let accepted: Vec<usize> = (0..results.len())
+            .filter(|index| results[*index].is_ok())
+            .collect();
+
+        for chunk in accepted.chunks(BATCH_CHUNK_SIZE) {
+            let people: Vec<Person> = chunk
+                .iter()
+                .filter_map(|index| results[*index].as_ref().ok().cloned())
+                .collect();
+
+            let saved = match force {
+                true => self
+                    .store
+                    .add_people(people, &self.audit_context)
+                    .await
+                    .map(|people| people.into_iter().map(Ok).collect()),
+                false => {
+                    self.store
+                        .add_people_unless_duplicates(
+                            people,
+                            &self.duplicate_detection,
+                            &self.audit_context,
+                        )
+                        .await
+                }
+            };
+
+            match saved {
+                Ok(saved) => {
+                    for (index, person) in chunk.iter().zip(saved) {
+                        results[*index] = person;
+                    }
+                }
+                Err(e) => {
+                    error!("adding people into repository: {:?}", e);
+                    for index in chunk {
+                        results[*index] = Err(Error::CreatePersonError);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// import_people adds the people of the rows and then their pets, the pets
+    /// of a person which could not be added are not added either. A person
+    /// who may already exist is not imported unless force is true.
+    pub async fn import_people(
+        &self,
+        rows: Vec<ImportRow>,
+        force: bool,
+    ) -> Vec<Result<(Person, Vec<Result<Pet, Error>>), Error>> {
+        let new_people = rows.iter().map(|row| row.new_person.clone()).collect();
+        let people = self.add_people(new_people, force).await;
+
+        stream::iter(rows.into_iter().zip(people))
+            .map(|(row, person)| async move {
+                let person = person?;
+                let mut pets = Vec::with_capacity(row.pet_names.len());
+                for pet_name in row.pet_names {
+                    let new_pet = NewPet::new(pet_name, person.id.clone());
+                    pets.push(self.add_pet(new_pet).await);
+                }
+                Ok((person, pets))
+            })
+            .buffered(CENSOR_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// export_people returns every person matching the query and, when asked,
+    /// their pets.
+    pub async fn export_people(
+        &self,
+        query: PeopleQuery,
+        include_pets: bool,
+    ) -> Result<(Vec<Person>, Option<Vec<Pet>>), Error> {
+        debug!("start exporting people");
+
+        let mut people: Vec<Person> = Vec::new();
+        let mut pets: Vec<Pet> = Vec::new();
+        let mut after = None;
+        loop {
+            let page = self
+                .store
+                .get_people(&query, MAX_PAGE_SIZE, after)
+                .await
+                .map_err(|e| {
+                    error!("getting people from repository: {:?}", e);
+                    Error::GetPeopleError
+                })?;
+
+            if include_pets && !page.is_empty() {
+                let owners: Vec<PersonID> = page.iter().map(|person| person.id.clone()).collect();
+                let page_pets = self.store.get_pets(&owners).await.map_err(|e| {
+                    error!("getting pets from repository: {:?}", e);
+                    Error::GetPetsError
+                })?;
+                pets.extend(page_pets);
+            }
+
+            after = match page.len() < MAX_PAGE_SIZE as usize {
+                true => None,
+                false => page.last().map(|last| Cursor::new(query.sort, last)),
+            };
+            people.extend(page);
+            if after.is_none() {
+                break;
+            }
+        }
+
+        Ok((people, include_pets.then_some(pets)))
+    }
+
+    /// stream_people returns every person, and their pets when asked, as they
+    /// are read from the store.
+    pub async fn stream_people(&self, include_pets: bool) -> Result<storage::PeopleStream, Error> {
+        debug!("start streaming people");
+
+        match self.store.stream_people(include_pets).await {
+            Ok(people) => Ok(people),
+            Err(e) => {
+                error!("streaming people from repository: {:?}", e);
+                Err(Error::GetPeopleError)
+            }
+        }
+    }
+
     async fn build_new_person(&self, new_person: NewPerson) -> Result<Person, Error> {
+        // there is no point in censoring the names of a person who is not valid.
+        new_person.profile.validate()?;
+
         // https://ryhl.io/blog/actors-with-tokio/
         // https://github.com/tokio-rs/tokio/discussions/4426
         debug!("checking bad words in first name value");
@@ -92,30 +527,45 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
 
         let (new_first_name, new_last_name) = tokio::join!(new_first_name, new_last_name);
 
-        if new_first_name.is_err() {
-            let err = new_first_name.unwrap_err();
-            error!("checking bad words in first name value: {}", err);
-            return Err(err);
-        }
+        let new_first_name = match new_first_name {
+            Ok(first_name) => first_name,
+            Err(err) => {
+                error!("checking bad words in first name value: {}", err);
+                return Err(err);
+            }
+        };
 
-        if new_last_name.is_err() {
-            let err = new_first_name.unwrap_err();
-            error!("checking bad words in last name value: {}", err);
-            return Err(err);
-        }
+        let new_last_name = match new_last_name {
+            Ok(last_name) => last_name,
+            Err(err) => {
+                error!("checking bad words in last name value: {}", err);
+                return Err(err);
+            }
+        };
 
         let mut person = new_person.to_person();
-        person.first_name = new_first_name.unwrap();
-        person.last_name = new_last_name.unwrap();
+        person.first_name = new_first_name;
+        person.last_name = new_last_name;
 
         Ok(person)
     }
 
-    pub async fn delete_person(&self, person_id: PersonID) -> Result<bool, Error> {
+    pub async fn delete_person(
+        &self,
+        person_id: PersonID,
+        policy: Option<DeletePolicy>,
+    ) -> Result<bool, Error> {
         debug!("start deleting person {}", person_id);
 
-        match self.store.delete_person(person_id).await {
+        let policy = policy.unwrap_or(self.delete_policy);
+        match self
+            .store
+            .delete_person(person_id, policy, &self.audit_context)
+            .await
+        {
             Ok(ok) => Ok(ok),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(Error::PersonHasPets(pets)) => Err(Error::PersonHasPets(pets)),
             Err(e) => {
                 error!("deleting person from repository: {:?}", e);
                 Err(Error::DeletePersonError)
@@ -123,17 +573,392 @@ impl<T: storage::Storer, C: censor::Censorious> Service<T, C> {
         }
     }
 
+    pub async fn restore_person(&self, person_id: PersonID) -> Result<Person, Error> {
+        debug!("start restoring person {}", person_id);
+
+        match self
+            .store
+            .restore_person(person_id, &self.audit_context)
+            .await
+        {
+            Ok(person) => Ok(person),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(Error::PersonNotDeleted) => Err(Error::PersonNotDeleted),
+            Err(Error::PersonMerged(target_id)) => Err(Error::PersonMerged(target_id)),
+            Err(e) => {
+                error!("restoring person from repository: {:?}", e);
+                Err(Error::RestorePersonError)
+            }
+        }
+    }
+
+    /// purge_people removes for good the people deleted longer ago than the
+    /// retention period.
+    pub async fn purge_people(&self, retention: Duration) -> Result<u64, Error> {
+        let deleted_before = Utc::now() - retention;
+        debug!("start purging people deleted before {}", deleted_before);
+
+        match self
+            .store
+            .purge_people(deleted_before, &self.audit_context)
+            .await
+        {
+            Ok(purged) => Ok(purged.len() as u64),
+            Err(e) => {
+                error!("purging people from repository: {:?}", e);
+                Err(Error::PurgePeopleError)
+            }
+        }
+    }
+
     pub async fn add_pet(&self, new_pet: NewPet) -> Result<Pet, Error> {
         debug!("start adding pet {:?}", new_pet);
 
-        let pet = new_pet.to_pet();
+        let mut pet = new_pet.to_pet();
+        pet.name = self.censor_pet_name(pet.name).await?;
 
-        match self.store.add_pet(pet.clone()).await {
+        match self.store.add_pet(pet, &self.audit_context).await {
             Ok(pet) => Ok(pet),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
             Err(e) => {
                 error!("adding pet into repository: {:?}", e);
-                Err(Error::DeletePersonError)
+                Err(Error::AddPetError)
+            }
+        }
+    }
+
+    pub async fn get_pet(&self, pet_id: PetID) -> Result<Pet, Error> {
+        debug!("start getting pet {}", pet_id);
+
+        match self.store.get_pet(pet_id).await {
+            Ok(pet) => Ok(pet),
+            Err(Error::PetNotFound) => Err(Error::PetNotFound),
+            Err(e) => {
+                error!("getting pet from repository: {:?}", e);
+                Err(Error::GetPetError)
+            }
+        }
+    }
+
+    /// get_person_pets returns the pets of a person who is not deleted.
+    pub async fn get_person_pets(&self, person_id: PersonID) -> Result<Vec<Pet>, Error> {
+        debug!("start getting the pets of person {}", person_id);
+
+        self.get_person(person_id.clone(), false).await?;
+
+        match self.store.get_pets(&[person_id]).await {
+            Ok(pets) => Ok(pets),
+            Err(e) => {
+                error!("getting pets from repository: {:?}", e);
+                Err(Error::GetPetsError)
+            }
+        }
+    }
+
+    pub async fn rename_pet(&self, pet_id: PetID, name: String) -> Result<Pet, Error> {
+        debug!("start renaming pet {}", pet_id);
+
+        let mut pet = self.get_pet(pet_id).await?;
+        pet.name = self.censor_pet_name(name).await?;
+        self.update_pet(pet).await
+    }
+
+    /// transfer_pet gives the pet to another person, who must not be deleted.
+    pub async fn transfer_pet(&self, pet_id: PetID, person_id: PersonID) -> Result<Pet, Error> {
+        debug!("start transferring pet {} to person {}", pet_id, person_id);
+
+        let mut pet = self.get_pet(pet_id).await?;
+        pet.person_id = Some(person_id);
+        self.update_pet(pet).await
+    }
+
+    async fn update_pet(&self, pet: Pet) -> Result<Pet, Error> {
+        match self.store.update_pet(pet, &self.audit_context).await {
+            Ok(pet) => Ok(pet),
+            Err(Error::PetNotFound) => Err(Error::PetNotFound),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(e) => {
+                error!("updating pet from repository: {:?}", e);
+                Err(Error::UpdatePetError)
+            }
+        }
+    }
+
+    pub async fn delete_pet(&self, pet_id: PetID) -> Result<bool, Error> {
+        debug!("start deleting pet {}", pet_id);
+
+        match self.store.delete_pet(pet_id, &self.audit_context).await {
+            Ok(ok) => Ok(ok),
+            Err(Error::PetNotFound) => Err(Error::PetNotFound),
+            Err(e) => {
+                error!("deleting pet from repository: {:?}", e);
+                Err(Error::DeletePetError)
+            }
+        }
+    }
+
+    /// add_relationship relates the person to another one, the reciprocal
+    /// relationship is added too.
+    pub async fn add_relationship(
+        &self,
+        person_id: PersonID,
+        new_relationship: NewRelationship,
+    ) -> Result<Relationship, Error> {
+        debug!(
+            "start relating person {} to {}",
+            person_id, new_relationship.related_id
+        );
+
+        if person_id == new_relationship.related_id {
+            return Err(Error::InvalidRelationship(
+                "a person cannot be related to themselves".to_string(),
+            ));
+        }
+
+        let relationship = Relationship::new(person_id, new_relationship);
+        match self.store.add_relationship(relationship).await {
+            Ok(relationship) => Ok(relationship),
+            Err(Error::PersonNotFound) => Err(Error::PersonNotFound),
+            Err(Error::RelationshipExists) => Err(Error::RelationshipExists),
+            Err(e) => {
+                error!("adding relationship into repository: {:?}", e);
+                Err(Error::AddRelationshipError)
+            }
+        }
+    }
+
+    /// get_person_relationships returns the relationships of a person who is
+    /// not deleted.
+    pub async fn get_person_relationships(
+        &self,
+        person_id: PersonID,
+    ) -> Result<Vec<Relationship>, Error> {
+        debug!("start getting the relationships of person {}", person_id);
+
+        self.get_person(person_id.clone(), false).await?;
+        self.get_relationships(&[person_id]).await
+    }
+
+    /// get_relationship_graph walks the relationships from a person, breadth
+    /// first, up to the given depth. Every person is listed once, at the
+    /// depth they were first reached.
+    pub async fn get_relationship_graph(
+        &self,
+        person_id: PersonID,
+        depth: u32,
+    ) -> Result<RelationshipGraph, Error> {
+        debug!(
+            "start walking the relationships of person {} up to depth {}",
+            person_id, depth
+        );
+
+        let person = self.get_person(person_id.clone(), false).await?;
+        let mut reached: HashMap<PersonID, u32> = HashMap::from([(person_id.clone(), 0)]);
+        let mut people = vec![GraphPerson { person, depth: 0 }];
+        let mut relationships = vec![];
+        let mut frontier = vec![person_id];
+
+        for level in 1..=depth {
+            if frontier.is_empty() {
+                break;
+            }
+
+            let mut next = vec![];
+            for relationship in self.get_relationships(&frontier).await? {
+                if !reached.contains_key(&relationship.related_id) {
+                    reached.insert(relationship.related_id.clone(), level);
+                    next.push(relationship.related_id.clone());
+                }
+                relationships.push(relationship);
+            }
+
+            for related_id in next.iter() {
+                let person = self.get_person(related_id.clone(), false).await?;
+                people.push(GraphPerson {
+                    person,
+                    depth: level,
+                });
+            }
+            frontier = next;
+        }
+
+        Ok(RelationshipGraph {
+            people,
+            relationships,
+        })
+    }
+
+    /// delete_relationship removes the relationship between both people,
+    /// whichever of them is given first.
+    pub async fn delete_relationship(
+        &self,
+        person_id: PersonID,
+        related_id: PersonID,
+    ) -> Result<bool, Error> {
+        debug!("start unrelating person {} from {}", person_id, related_id);
+
+        match self.store.delete_relationship(person_id, related_id).await {
+            Ok(ok) => Ok(ok),
+            Err(Error::RelationshipNotFound) => Err(Error::RelationshipNotFound),
+            Err(e) => {
+                error!("deleting relationship from repository: {:?}", e);
+                Err(Error::DeleteRelationshipError)
+            }
+        }
+    }
+
+    async fn get_relationships(&self, people: &[PersonID]) -> Result<Vec<Relationship>, Error> {
+        self.store.get_relationships(people).await.map_err(|e| {
+            error!("getting relationships from repository: {:?}", e);
+            Error::GetRelationshipsError
+        })
+    }
+
+    /// get_audit_entries returns the audit entries matching the query, the
+    /// newest first.
+    pub async fn get_audit_entries(&self, query: AuditQuery) -> Result<Vec<AuditEntry>, Error> {
+        debug!("start getting audit entries {:?}", query);
+
+        match self.store.get_audit_entries(&query).await {
+            Ok(entries) => Ok(entries),
+            Err(e) => {
+                error!("getting audit entries from repository: {:?}", e);
+                Err(Error::GetAuditError)
+            }
+        }
+    }
+
+    /// add_webhook subscribes the URL to the events of the given kinds, the
+    /// secret is not returned.
+    pub async fn add_webhook(&self, new_webhook: NewWebhook) -> Result<Webhook, Error> {
+        debug!("start adding webhook {}", new_webhook.url);
+
+        new_webhook.validate()?;
+        match self.store.add_webhook(new_webhook.to_webhook()).await {
+            Ok(webhook) => Ok(webhook.redacted()),
+            Err(e) => {
+                error!("adding webhook into repository: {:?}", e);
+                Err(Error::AddWebhookError)
+            }
+        }
+    }
+
+    /// get_webhooks returns every webhook without its secret.
+    pub async fn get_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        debug!("start getting webhooks");
+
+        match self.store.get_webhooks().await {
+            Ok(webhooks) => Ok(webhooks.into_iter().map(Webhook::redacted).collect()),
+            Err(e) => {
+                error!("getting webhooks from repository: {:?}", e);
+                Err(Error::GetWebhooksError)
+            }
+        }
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, Error> {
+        debug!("start deleting webhook {}", webhook_id);
+
+        match self.store.delete_webhook(webhook_id).await {
+            Ok(ok) => Ok(ok),
+            Err(Error::WebhookNotFound) => Err(Error::WebhookNotFound),
+            Err(e) => {
+                error!("deleting webhook from repository: {:?}", e);
+                Err(Error::DeleteWebhookError)
             }
         }
     }
+
+    /// get_webhook_deliveries returns the latest deliveries of the webhook,
+    /// the newest first.
+    pub async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookID,
+        limit: i32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        debug!("start getting the deliveries of webhook {}", webhook_id);
+
+        self.get_webhook(webhook_id.clone()).await?;
+        match self.store.get_webhook_deliveries(webhook_id, limit).await {
+            Ok(deliveries) => Ok(deliveries),
+            Err(e) => {
+                error!("getting webhook deliveries from repository: {:?}", e);
+                Err(Error::GetWebhookDeliveriesError)
+            }
+        }
+    }
+
+    /// redeliver_webhook posts the payload of a delivery of the webhook again
+    /// and logs it as a new delivery, whether it succeeds or not.
+    pub async fn redeliver_webhook(
+        &self,
+        webhook_id: WebhookID,
+        delivery_id: String,
+    ) -> Result<WebhookDelivery, Error> {
+        debug!(
+            "start redelivering delivery {} of webhook {}",
+            delivery_id, webhook_id
+        );
+
+        let webhook = self.get_webhook(webhook_id).await?;
+        let delivery = match self.store.get_webhook_delivery(delivery_id).await {
+            Ok(delivery) if delivery.webhook_id == webhook.id => delivery,
+            Ok(_) | Err(Error::WebhookDeliveryNotFound) => {
+                return Err(Error::WebhookDeliveryNotFound)
+            }
+            Err(e) => {
+                error!("getting webhook delivery from repository: {:?}", e);
+                return Err(Error::RedeliverWebhookError);
+            }
+        };
+
+        let redelivery = self
+            .webhook_client
+            .deliver(&webhook, delivery.event_id, delivery.kind, delivery.payload)
+            .await;
+        match self.store.add_webhook_delivery(redelivery.clone()).await {
+            Ok(()) => Ok(redelivery),
+            Err(e) => {
+                error!("adding webhook delivery into repository: {:?}", e);
+                Err(Error::RedeliverWebhookError)
+            }
+        }
+    }
+
+    /// live_events returns the events saved after the given one, then every
+    /// event as the changes are committed. Without an event the stream starts
+    /// with the next change.
+    pub async fn live_events(&self, last_event_id: Option<i64>) -> Result<EventStream, Error>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let after = match last_event_id {
+            Some(id) => id,
+            None => self.store.get_last_event_id().await.map_err(|e| {
+                error!("getting last event from repository: {:?}", e);
+                Error::GetEventsError
+            })?,
+        };
+        debug!("start streaming live events after {}", after);
+
+        Ok(self.store.live_events().stream(self.store.clone(), after))
+    }
+
+    async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, Error> {
+        match self.store.get_webhook(webhook_id).await {
+            Ok(webhook) => Ok(webhook),
+            Err(Error::WebhookNotFound) => Err(Error::WebhookNotFound),
+            Err(e) => {
+                error!("getting webhook from repository: {:?}", e);
+                Err(Error::GetWebhooksError)
+            }
+        }
+    }
+
+    async fn censor_pet_name(&self, name: String) -> Result<String, Error> {
+        self.censorious.censor(name).await.map_err(|err| {
+            error!("checking bad words in pet name value: {}", err);
+            Error::ValidateBadWordsError
+        })
+    }
 }
@@ -1,8 +1,25 @@
 use crate::errors::error;
+use crate::people::duplicates::{DuplicateDetection, MatchAlgorithm};
+use crate::people::live::LiveEvents;
 use crate::people::{censor, service, storage};
-use crate::types::people::{NewPerson, Person, PersonID};
-use crate::types::pets::Pet;
+use crate::types::accounts::AccountID;
+use crate::types::audit::{AuditAction, AuditContext, AuditEntity, AuditEntry, AuditQuery};
+use crate::types::events::{DomainEvent, EventKind};
+use crate::types::history::PersonVersion;
+use crate::types::merges::{Merge, MergeField, MergeRequest};
+use crate::types::pagination::{Cursor, Pagination};
+use crate::types::people::{NewPerson, Person, PersonID, PersonMatch, PersonWithPets};
+use crate::types::pets::{DeletePolicy, NewPet, Pet, PetID};
+use crate::types::profile::Profile;
+use crate::types::query::{PeopleQuery, Sort, SortField, SortOrder};
+use crate::types::relationships::{
+    GraphPerson, NewRelationship, Relationship, RelationshipGraph, RelationshipKind,
+};
+use crate::types::webhooks::{DeliveryStatus, NewWebhook, Webhook, WebhookDelivery, WebhookID};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
 
 #[test]
@@ -13,11 +30,19 @@ fn test_get_people() {
             id: PersonID("1".to_string()),
             first_name: "Luis".to_string(),
             last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         },
         Person {
             id: PersonID("2".to_string()),
             first_name: "Fernando".to_string(),
             last_name: "Fernando".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         },
     ];
 
@@ -26,11 +51,19 @@ fn test_get_people() {
             id: PersonID("1".to_string()),
             first_name: "Luis".to_string(),
             last_name: "Luis".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         },
         Person {
             id: PersonID("2".to_string()),
             first_name: "Fernando".to_string(),
             last_name: "Fernando".to_string(),
+            created_on: DateTime::default(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         },
     ];
 
@@ -39,21 +72,99 @@ fn test_get_people() {
 
     let person_service = service::Service::new(a_store, a_censor);
 
-    let limit = Some(10);
-    let offset = 0;
+    let pagination = Pagination {
+        limit: 10,
+        after: None,
+    };
 
     let runtime = Runtime::new().expect("Unable to create a runtime");
 
     // When
-    let got = runtime.block_on(person_service.get_people(limit, offset));
+    let got = runtime.block_on(person_service.get_people(PeopleQuery::default(), pagination));
 
     // Then
     match got {
-        Ok(people_got) => assert_eq!(want, people_got),
+        Ok(page_got) => {
+            assert_eq!(want, page_got.people);
+            assert_eq!(None, page_got.next);
+        }
         Err(err) => panic!("unexpected error: {:?}", err),
     }
 }
 
+#[test]
+fn test_get_people_with_next_page() {
+    // Given
+    let people_store = vec![
+        Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        ),
+        Person::new(
+            PersonID("2".to_string()),
+            "Fernando".to_string(),
+            "Fernando".to_string(),
+        ),
+    ];
+    let a_store = DummyStore::new_with_get_people(people_store.clone(), false);
+    let a_censor = DummyCensor::new("".to_string(), false);
+    let person_service = service::Service::new(a_store, a_censor);
+    let pagination = Pagination {
+        limit: 1,
+        after: None,
+    };
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    // When
+    let got = runtime.block_on(person_service.get_people(PeopleQuery::default(), pagination));
+    // Then
+    let page_got = got.unwrap();
+    assert_eq!(page_got.people, vec![people_store[0].clone()]);
+    assert_eq!(
+        page_got.next,
+        Some(Cursor::new(Sort::default(), &people_store[0]))
+    );
+}
+
+#[test]
+fn test_get_people_with_cursor_from_another_sort() {
+    // Given
+    let a_store = DummyStore::new_with_get_people(vec![], false);
+    let a_censor = DummyCensor::new("".to_string(), false);
+    let person_service = service::Service::new(a_store, a_censor);
+    let a_person = Person::new(
+        PersonID("1".to_string()),
+        "Luis".to_string(),
+        "Luis".to_string(),
+    );
+    let by_first_name = Sort {
+        field: SortField::FirstName,
+        order: SortOrder::Asc,
+    };
+    let pagination = Pagination {
+        limit: 10,
+        after: Some(Cursor::new(by_first_name, &a_person)),
+    };
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    // When
+    let got = runtime.block_on(person_service.get_people(PeopleQuery::default(), pagination));
+    // Then
+    assert_eq!(got, Err(error::Error::InvalidCursor));
+}
+
+#[test]
+fn test_search_people_without_terms() {
+    // Given
+    let a_store = DummyStore::new_with_get_people(vec![], false);
+    let a_censor = DummyCensor::new("".to_string(), false);
+    let person_service = service::Service::new(a_store, a_censor);
+    let runtime = Runtime::new().expect("Unable to create a runtime");
+    // When
+    let got = runtime.block_on(person_service.search_people("  ".to_string(), 10));
+    // Then
+    assert_eq!(got, Err(error::Error::MissingParameters));
+}
+
 #[test]
 fn test_get_person() {
     // Given
@@ -61,6 +172,10 @@ fn test_get_person() {
         id: PersonID("1".to_string()),
         first_name: "Luis".to_string(),
         last_name: "Luis".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
 
     let a_store = DummyStore::new_with_get_person(Some(person_store), false);
@@ -71,10 +186,14 @@ fn test_get_person() {
         id: PersonID("1".to_string()),
         first_name: "Luis".to_string(),
         last_name: "Luis".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let runtime = Runtime::new().expect("unable to create runtime to test get person");
     // When
-    let got = runtime.block_on(person_service.get_person(person_id));
+    let got = runtime.block_on(person_service.get_person(person_id, false));
     // Then
     match got {
         Ok(person_got) => assert_eq!(want, person_got),
@@ -92,7 +211,7 @@ fn test_get_not_found_person() {
     let person_id = PersonID("2000".to_string());
     let runtime = Runtime::new().expect("unable to create runtime to test get person");
     // When
-    let got = runtime.block_on(person_service.get_person(person_id));
+    let got = runtime.block_on(person_service.get_person(person_id, false));
     // Then
     match got {
         Ok(person) => panic!("unexpected result {:?}", person),
@@ -107,11 +226,19 @@ fn test_create_person() {
         id: PersonID("3".to_string()),
         first_name: "esme".to_string(),
         last_name: "esme".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let want = Person {
         id: PersonID("3".to_string()),
         first_name: "esme".to_string(),
         last_name: "esme".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let new_person = NewPerson::new("esme".to_string(), "esme".to_string());
     let a_censor = DummyCensor::new("esme".to_string(), false);
@@ -119,9 +246,9 @@ fn test_create_person() {
     let person_service = service::Service::new(a_store, a_censor);
     let runtime = Runtime::new().expect("unable to create runtime to test create person");
     // When
-    let got = runtime.block_on(person_service.add_person(new_person));
+    let got = runtime.block_on(person_service.add_person(new_person, false));
     // Then
-    assert_eq!(false, got.is_err());
+    assert!(got.is_ok());
 
     match got {
         Ok(person) => assert_eq!(want, person),
@@ -129,6 +256,212 @@ fn test_create_person() {
     }
 }
 
+#[test]
+fn test_add_person_with_possible_duplicates() {
+    // Given
+    let existing = vec![
+        Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        ),
+        Person::new(
+            PersonID("2".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        ),
+    ];
+    let added = Person::new(
+        PersonID("3".to_string()),
+        "Esmé".to_string(),
+        "Weatherwax".to_string(),
+    );
+    let person_service = service::Service::new(
+        DummyStore {
+            get_people_values: Some(existing.clone()),
+            add_person_value: Some(added.clone()),
+            add_person_error: Some(false),
+            ..Default::default()
+        },
+        DummyCensor::new("".to_string(), false),
+    )
+    .with_duplicate_detection(DuplicateDetection {
+        algorithm: MatchAlgorithm::Exact,
+        threshold: 1.0,
+    });
+    let new_person = NewPerson::new("Esmé".to_string(), "Weatherwax".to_string());
+    let runtime = Runtime::new().expect("unable to create runtime to test create person");
+    // When
+    let got = runtime.block_on(person_service.add_person(new_person.clone(), false));
+    let got_forced = runtime.block_on(person_service.add_person(new_person, true));
+    // Then
+    assert_eq!(
+        got,
+        Err(error::Error::PossibleDuplicates(vec![PersonMatch {
+            person: existing[0].clone(),
+            score: 1.0,
+        }]))
+    );
+    assert_eq!(got_forced, Ok(added));
+}
+
+#[test]
+fn test_add_people() {
+    // Given
+    let new_people = vec![
+        NewPerson::new("esme".to_string(), "esme".to_string()),
+        NewPerson::new("luis".to_string(), "luis".to_string()),
+    ];
+    let person_service = service::Service::new(
+        DummyStore::new_with_add_person(None, false),
+        DummyCensor::new("".to_string(), false),
+    );
+    let censor_failing_service = service::Service::new(
+        DummyStore::new_with_add_person(None, false),
+        DummyCensor::new("".to_string(), true),
+    );
+    let store_failing_service = service::Service::new(
+        DummyStore::new_with_add_person(None, true),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test add people");
+    // When
+    let got = runtime.block_on(person_service.add_people(new_people.clone(), false));
+    let got_censor_error =
+        runtime.block_on(censor_failing_service.add_people(new_people.clone(), false));
+    let got_store_error = runtime.block_on(store_failing_service.add_people(new_people, false));
+    // Then
+    let names: Vec<String> = got
+        .into_iter()
+        .map(|person| person.unwrap().first_name)
+        .collect();
+    assert_eq!(names, vec!["esme", "luis"]);
+    assert_eq!(
+        got_censor_error,
+        vec![
+            Err(error::Error::ValidateBadWordsError),
+            Err(error::Error::ValidateBadWordsError)
+        ]
+    );
+    assert_eq!(
+        got_store_error,
+        vec![
+            Err(error::Error::CreatePersonError),
+            Err(error::Error::CreatePersonError)
+        ]
+    );
+}
+
+#[test]
+fn test_add_people_with_possible_duplicates() {
+    // Given
+    let existing = Person::new(
+        PersonID("1".to_string()),
+        "Esme".to_string(),
+        "Weatherwax".to_string(),
+    );
+    let person_service = service::Service::new(
+        DummyStore {
+            get_people_values: Some(vec![existing.clone()]),
+            add_person_error: Some(false),
+            ..Default::default()
+        },
+        DummyCensor::new("".to_string(), false),
+    )
+    .with_duplicate_detection(DuplicateDetection {
+        algorithm: MatchAlgorithm::Exact,
+        threshold: 1.0,
+    });
+    let new_people = vec![
+        NewPerson::new("Esmé".to_string(), "Weatherwax".to_string()),
+        NewPerson::new("Luis".to_string(), "Luis".to_string()),
+    ];
+    let runtime = Runtime::new().expect("unable to create runtime to test add people");
+    // When
+    let got = runtime.block_on(person_service.add_people(new_people.clone(), false));
+    let got_forced = runtime.block_on(person_service.add_people(new_people, true));
+    // Then
+    assert_eq!(
+        got[0],
+        Err(error::Error::PossibleDuplicates(vec![PersonMatch {
+            person: existing,
+            score: 1.0,
+        }]))
+    );
+    assert_eq!(
+        got[1].as_ref().map(|person| person.first_name.as_str()),
+        Ok("Luis")
+    );
+    let names: Vec<String> = got_forced
+        .into_iter()
+        .map(|person| person.unwrap().first_name)
+        .collect();
+    assert_eq!(names, vec!["Esmé", "Luis"]);
+}
+
+#[test]
+fn test_add_people_with_invalid_profile() {
+    // Given
+    let mut invalid_person = NewPerson::new("luis".to_string(), "luis".to_string());
+    invalid_person.profile.email = Some("luis".to_string());
+    let new_people = vec![
+        NewPerson::new("esme".to_string(), "esme".to_string()),
+        invalid_person,
+    ];
+    let person_service = service::Service::new(
+        DummyStore::new_with_add_person(None, false),
+        DummyCensor::new("".to_string(), true),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test add people");
+    // When
+    let got = runtime.block_on(person_service.add_people(new_people, false));
+    // Then
+    assert_eq!(
+        got,
+        vec![
+            Err(error::Error::ValidateBadWordsError),
+            Err(error::Error::InvalidProfile(
+                "email luis is not valid".to_string()
+            ))
+        ]
+    );
+}
+
+#[test]
+fn test_export_people() {
+    // Given
+    let people = vec![
+        Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        ),
+        Person::new(
+            PersonID("2".to_string()),
+            "Fernando".to_string(),
+            "Fernando".to_string(),
+        ),
+    ];
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(PersonID("1".to_string())),
+    };
+    let a_store = DummyStore {
+        add_pet_value: Some(pet.clone()),
+        ..DummyStore::new_with_get_people(people.clone(), false)
+    };
+    let person_service = service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+    let runtime = Runtime::new().expect("unable to create runtime to test export people");
+    // When
+    let got = runtime.block_on(person_service.export_people(PeopleQuery::default(), true));
+    let got_without_pets =
+        runtime.block_on(person_service.export_people(PeopleQuery::default(), false));
+    // Then
+    assert_eq!(got, Ok((people.clone(), Some(vec![pet]))));
+    assert_eq!(got_without_pets, Ok((people, None)));
+}
+
 #[test]
 fn test_delete_person() {
     // Given
@@ -139,7 +472,7 @@ fn test_delete_person() {
     let want = true;
     let runtime = Runtime::new().expect("unable to create runtime to test delete person");
     // When
-    let got = runtime.block_on(person_service.delete_person(person_id));
+    let got = runtime.block_on(person_service.delete_person(person_id, None));
     // Then
     match got {
         Ok(result_got) => assert_eq!(want, result_got),
@@ -156,7 +489,7 @@ fn test_delete_person_but_not_found() {
     let person_id = PersonID("2000".to_string());
     let runtime = Runtime::new().expect("unable to create runtime to test delete person");
     // When
-    let got = runtime.block_on(person_service.delete_person(person_id));
+    let got = runtime.block_on(person_service.delete_person(person_id, None));
     // Then
     match got {
         Ok(result) => panic!("unexpected result {}", result),
@@ -164,6 +497,36 @@ fn test_delete_person_but_not_found() {
     }
 }
 
+#[test]
+fn test_delete_person_with_pets() {
+    // Given
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(PersonID("1".to_string())),
+    };
+    let a_store = DummyStore {
+        add_pet_value: Some(pet.clone()),
+        ..DummyStore::new_with_delete_person(true, false)
+    };
+    let person_service = service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+    let cascade_service = person_service
+        .clone()
+        .with_delete_policy(DeletePolicy::Cascade);
+    let runtime = Runtime::new().expect("unable to create runtime to test delete person");
+    // When
+    let got = runtime.block_on(person_service.delete_person(PersonID("1".to_string()), None));
+    let got_orphan = runtime.block_on(
+        person_service.delete_person(PersonID("1".to_string()), Some(DeletePolicy::Orphan)),
+    );
+    let got_cascade =
+        runtime.block_on(cascade_service.delete_person(PersonID("1".to_string()), None));
+    // Then
+    assert_eq!(got, Err(error::Error::PersonHasPets(vec![pet])));
+    assert_eq!(got_orphan, Ok(true));
+    assert_eq!(got_cascade, Ok(true));
+}
+
 #[test]
 fn test_update_person() {
     // Given
@@ -171,11 +534,19 @@ fn test_update_person() {
         id: PersonID("1".to_string()),
         first_name: "Luisfer".to_string(),
         last_name: "Luisfer".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let person_to_return = Some(Person {
         id: PersonID("1".to_string()),
         first_name: "Luisfer".to_string(),
         last_name: "Luisfer".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     });
     let a_store = DummyStore::new_with_update_person(person_to_return, false);
     let a_censor = DummyCensor::new("".to_string(), false);
@@ -184,6 +555,10 @@ fn test_update_person() {
         id: PersonID("1".to_string()),
         first_name: "Luisfer".to_string(),
         last_name: "Luisfer".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let runtime = Runtime::new().expect("unable to create runtime to test update person");
     // When
@@ -202,6 +577,10 @@ fn test_update_person_but_not_found() {
         id: PersonID("1".to_string()),
         first_name: "Luisfer".to_string(),
         last_name: "Luisfer".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
     };
     let a_store = DummyStore::new_with_update_person(None, true);
     let a_censor = DummyCensor::new("".to_string(), false);
@@ -216,6 +595,639 @@ fn test_update_person_but_not_found() {
     }
 }
 
+#[test]
+fn test_person_not_found_is_kept() {
+    // Given
+    let person_service = service::Service::new(
+        DummyStore::new_with_person_not_found(),
+        DummyCensor::new("".to_string(), false),
+    );
+    let a_person = Person {
+        id: PersonID("2000".to_string()),
+        first_name: "Luisfer".to_string(),
+        last_name: "Luisfer".to_string(),
+        created_on: DateTime::default(),
+        version: 1,
+        deleted_at: None,
+        profile: Profile::default(),
+    };
+    let runtime = Runtime::new().expect("unable to create runtime to test person not found");
+    // When
+    let got_person =
+        runtime.block_on(person_service.get_person(PersonID("2000".to_string()), false));
+    let got_update = runtime.block_on(person_service.update_person(a_person));
+    let got_delete =
+        runtime.block_on(person_service.delete_person(PersonID("2000".to_string()), None));
+    let got_restore = runtime.block_on(person_service.restore_person(PersonID("2000".to_string())));
+    // Then
+    assert_eq!(got_person, Err(error::Error::PersonNotFound));
+    assert_eq!(got_update, Err(error::Error::PersonNotFound));
+    assert_eq!(got_delete, Err(error::Error::PersonNotFound));
+    assert_eq!(got_restore, Err(error::Error::PersonNotFound));
+}
+
+#[test]
+fn test_purge_people() {
+    // Given
+    let person_service = service::Service::new(
+        DummyStore::new_with_purge_people(3, false),
+        DummyCensor::new("".to_string(), false),
+    );
+    let failing_service = service::Service::new(
+        DummyStore::new_with_purge_people(0, true),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test purge people");
+    // When
+    let got = runtime.block_on(person_service.purge_people(chrono::Duration::days(30)));
+    let got_error = runtime.block_on(failing_service.purge_people(chrono::Duration::days(30)));
+    // Then
+    assert_eq!(got, Ok(3));
+    assert_eq!(got_error, Err(error::Error::PurgePeopleError));
+}
+
+#[test]
+fn test_expand_pets() {
+    // Given
+    let people = vec![
+        Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        ),
+        Person::new(
+            PersonID("2".to_string()),
+            "Fernando".to_string(),
+            "Fernando".to_string(),
+        ),
+    ];
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(PersonID("2".to_string())),
+    };
+    let a_store = DummyStore {
+        add_pet_value: Some(pet.clone()),
+        ..Default::default()
+    };
+    let person_service = service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+    let runtime = Runtime::new().expect("unable to create runtime to test expand pets");
+    // When
+    let got = runtime.block_on(person_service.expand_pets(people.clone()));
+    // Then
+    assert_eq!(
+        got,
+        Ok(vec![
+            PersonWithPets {
+                person: people[0].clone(),
+                pets: Some(vec![]),
+            },
+            PersonWithPets {
+                person: people[1].clone(),
+                pets: Some(vec![pet]),
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_add_pet() {
+    // Given
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(PersonID("1".to_string())),
+    };
+    let person_service = service::Service::new(
+        DummyStore {
+            add_pet_value: Some(pet.clone()),
+            add_pet_error: Some(false),
+            ..Default::default()
+        },
+        DummyCensor::new("".to_string(), false),
+    );
+    let failing_service = service::Service::new(
+        DummyStore {
+            add_pet_error: Some(true),
+            ..Default::default()
+        },
+        DummyCensor::new("".to_string(), false),
+    );
+    let new_pet = NewPet::new("Firulais".to_string(), PersonID("1".to_string()));
+    let runtime = Runtime::new().expect("unable to create runtime to test add pet");
+    // When
+    let got = runtime.block_on(person_service.add_pet(new_pet.clone()));
+    let got_error = runtime.block_on(failing_service.add_pet(new_pet));
+    // Then
+    assert_eq!(got, Ok(pet));
+    assert_eq!(got_error, Err(error::Error::AddPetError));
+}
+
+#[test]
+fn test_rename_transfer_and_delete_pet() {
+    // Given
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(PersonID("1".to_string())),
+    };
+    let person_service = service::Service::new(
+        DummyStore {
+            add_pet_value: Some(pet.clone()),
+            add_pet_error: Some(false),
+            ..Default::default()
+        },
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test pets");
+    // When
+    let got_renamed =
+        runtime.block_on(person_service.rename_pet(pet.id.clone(), "Michi".to_string()));
+    let got_transferred =
+        runtime.block_on(person_service.transfer_pet(pet.id.clone(), PersonID("2".to_string())));
+    let got_deleted = runtime.block_on(person_service.delete_pet(pet.id.clone()));
+    let got_not_found = runtime.block_on(person_service.delete_pet(PetID("2000".to_string())));
+    // Then
+    assert_eq!(
+        got_renamed,
+        Ok(Pet {
+            name: "Michi".to_string(),
+            ..pet.clone()
+        })
+    );
+    assert_eq!(
+        got_transferred,
+        Ok(Pet {
+            person_id: Some(PersonID("2".to_string())),
+            ..pet
+        })
+    );
+    assert_eq!(got_deleted, Ok(true));
+    assert_eq!(got_not_found, Err(error::Error::PetNotFound));
+}
+
+#[test]
+fn test_get_person_pets_but_person_not_found() {
+    // Given
+    let person_service = service::Service::new(
+        DummyStore::new_with_person_not_found(),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test person pets");
+    // When
+    let got = runtime.block_on(person_service.get_person_pets(PersonID("2000".to_string())));
+    // Then
+    assert_eq!(got, Err(error::Error::PersonNotFound));
+}
+
+/// family is a parent with two children, every relationship with its
+/// reciprocal.
+fn family() -> (Vec<Person>, Vec<Relationship>) {
+    let people: Vec<Person> = ["1", "2", "3"]
+        .into_iter()
+        .map(|id| Person::new(PersonID(id.to_string()), id.to_string(), id.to_string()))
+        .collect();
+    let relationship = |person_id: &str, related_id: &str, kind| Relationship {
+        person_id: PersonID(person_id.to_string()),
+        related_id: PersonID(related_id.to_string()),
+        kind,
+    };
+    let relationships = vec![
+        relationship("1", "2", RelationshipKind::Parent),
+        relationship("2", "1", RelationshipKind::Child),
+        relationship("2", "3", RelationshipKind::Sibling),
+        relationship("3", "2", RelationshipKind::Sibling),
+    ];
+    (people, relationships)
+}
+
+#[test]
+fn test_add_relationship() {
+    // Given
+    let (people, relationships) = family();
+    let person_service = service::Service::new(
+        DummyStore::new_with_relationships(people, relationships),
+        DummyCensor::new("".to_string(), false),
+    );
+    let new_relationship = |related_id: &str| NewRelationship {
+        related_id: PersonID(related_id.to_string()),
+        kind: RelationshipKind::Parent,
+    };
+    let runtime = Runtime::new().expect("unable to create runtime to test relationships");
+    // When
+    let got = runtime.block_on(
+        person_service.add_relationship(PersonID("1".to_string()), new_relationship("3")),
+    );
+    let got_self = runtime.block_on(
+        person_service.add_relationship(PersonID("1".to_string()), new_relationship("1")),
+    );
+    let got_related = runtime.block_on(
+        person_service.add_relationship(PersonID("1".to_string()), new_relationship("2")),
+    );
+    // Then
+    assert_eq!(
+        got,
+        Ok(Relationship {
+            person_id: PersonID("1".to_string()),
+            related_id: PersonID("3".to_string()),
+            kind: RelationshipKind::Parent,
+        })
+    );
+    assert_eq!(
+        got_self,
+        Err(error::Error::InvalidRelationship(
+            "a person cannot be related to themselves".to_string()
+        ))
+    );
+    assert_eq!(got_related, Err(error::Error::RelationshipExists));
+}
+
+#[test]
+fn test_get_relationship_graph() {
+    // Given
+    let (people, relationships) = family();
+    let person_service = service::Service::new(
+        DummyStore::new_with_relationships(people.clone(), relationships.clone()),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test relationships");
+    // When
+    let got_one_level =
+        runtime.block_on(person_service.get_relationship_graph(PersonID("1".to_string()), 1));
+    let got_two_levels =
+        runtime.block_on(person_service.get_relationship_graph(PersonID("1".to_string()), 2));
+    let got_no_level =
+        runtime.block_on(person_service.get_relationship_graph(PersonID("3".to_string()), 0));
+    // Then
+    let at = |index: usize, depth| GraphPerson {
+        person: people[index].clone(),
+        depth,
+    };
+    assert_eq!(
+        got_one_level,
+        Ok(RelationshipGraph {
+            people: vec![at(0, 0), at(1, 1)],
+            relationships: vec![relationships[0].clone()],
+        })
+    );
+    assert_eq!(
+        got_two_levels,
+        Ok(RelationshipGraph {
+            people: vec![at(0, 0), at(1, 1), at(2, 2)],
+            relationships: relationships[..3].to_vec(),
+        })
+    );
+    assert_eq!(
+        got_no_level,
+        Ok(RelationshipGraph {
+            people: vec![at(2, 0)],
+            relationships: vec![],
+        })
+    );
+}
+
+#[test]
+fn test_delete_relationship() {
+    // Given
+    let (people, relationships) = family();
+    let person_service = service::Service::new(
+        DummyStore::new_with_relationships(people, relationships),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test relationships");
+    // When
+    let got = runtime.block_on(
+        person_service.delete_relationship(PersonID("2".to_string()), PersonID("1".to_string())),
+    );
+    let got_not_found = runtime.block_on(
+        person_service.delete_relationship(PersonID("1".to_string()), PersonID("3".to_string())),
+    );
+    // Then
+    assert_eq!(got, Ok(true));
+    assert_eq!(got_not_found, Err(error::Error::RelationshipNotFound));
+}
+
+#[test]
+fn test_merge_people() {
+    // Given
+    let target = Person {
+        profile: Profile {
+            email: Some("esme@anydomain.com".to_string()),
+            ..Default::default()
+        },
+        ..Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        )
+    };
+    let source = Person {
+        profile: Profile {
+            phone: Some("+34600000000".to_string()),
+            ..Default::default()
+        },
+        ..Person::new(
+            PersonID("2".to_string()),
+            "Esmé".to_string(),
+            "Wetherwax".to_string(),
+        )
+    };
+    let person_service = service::Service::new(
+        DummyStore::new_with_relationships(vec![target.clone(), source.clone()], vec![]),
+        DummyCensor::new("".to_string(), false),
+    );
+    let request = MergeRequest {
+        source_id: source.id.clone(),
+        from_source: vec![MergeField::LastName],
+    };
+    let runtime = Runtime::new().expect("unable to create runtime to test merge people");
+    // When
+    let got =
+        runtime.block_on(person_service.merge_people(target.id.clone(), request.clone(), Some(1)));
+    let got_old_version =
+        runtime.block_on(person_service.merge_people(target.id.clone(), request, Some(7)));
+    // Then
+    let got = got.unwrap();
+    assert_eq!(got.id, target.id);
+    assert_eq!(got.first_name, "Esme");
+    assert_eq!(got.last_name, "Wetherwax");
+    assert_eq!(got.profile.email, Some("esme@anydomain.com".to_string()));
+    assert_eq!(got.profile.phone, Some("+34600000000".to_string()));
+    assert_eq!(got.version, 2);
+    assert_eq!(got_old_version, Err(error::Error::PersonVersionConflict));
+}
+
+#[test]
+fn test_merge_person_into_themselves() {
+    // Given
+    let person_service = service::Service::new(
+        DummyStore::new_with_person_not_found(),
+        DummyCensor::new("".to_string(), false),
+    );
+    let request = MergeRequest {
+        source_id: PersonID("1".to_string()),
+        from_source: vec![],
+    };
+    let runtime = Runtime::new().expect("unable to create runtime to test merge people");
+    // When
+    let got = runtime.block_on(person_service.merge_people(
+        PersonID("1".to_string()),
+        request.clone(),
+        None,
+    ));
+    let got_not_found =
+        runtime.block_on(person_service.merge_people(PersonID("2".to_string()), request, None));
+    // Then
+    assert_eq!(
+        got,
+        Err(error::Error::InvalidMerge(
+            "a person cannot be merged into themselves".to_string()
+        ))
+    );
+    assert_eq!(got_not_found, Err(error::Error::PersonNotFound));
+}
+
+#[test]
+fn test_find_survivor() {
+    // Given
+    let survivor = Person::new(
+        PersonID("1".to_string()),
+        "Esme".to_string(),
+        "Weatherwax".to_string(),
+    );
+    let merges = vec![
+        Merge::new(PersonID("2".to_string()), PersonID("1".to_string())),
+        Merge::new(PersonID("3".to_string()), PersonID("2".to_string())),
+    ];
+    let person_service = service::Service::new(
+        DummyStore::new_with_merges(vec![survivor], merges),
+        DummyCensor::new("".to_string(), false),
+    );
+    let runtime = Runtime::new().expect("unable to create runtime to test find survivor");
+    // When
+    let got = runtime.block_on(person_service.find_survivor(PersonID("3".to_string())));
+    let got_not_merged = runtime.block_on(person_service.find_survivor(PersonID("4".to_string())));
+    // Then
+    assert_eq!(got, Ok(Some(PersonID("1".to_string()))));
+    assert_eq!(got_not_merged, Ok(None));
+}
+
+#[test]
+fn test_audit_changes() {
+    // Given
+    let person = Person::new(
+        PersonID("1".to_string()),
+        "Luis".to_string(),
+        "Fernando".to_string(),
+    );
+    let updated = Person {
+        first_name: "Luisfer".to_string(),
+        version: 2,
+        ..person.clone()
+    };
+    let pet = Pet {
+        id: PetID("10".to_string()),
+        name: "Firulais".to_string(),
+        person_id: Some(person.id.clone()),
+    };
+    let store = DummyStore {
+        get_person_value: Some(person.clone()),
+        get_person_error: Some(false),
+        update_person_value: Some(updated.clone()),
+        update_person_error: Some(false),
+        delete_person_value: Some(true),
+        delete_person_error: Some(false),
+        add_pet_value: Some(pet.clone()),
+        add_pet_error: Some(false),
+        ..Default::default()
+    };
+    let context = AuditContext::new(Some(AccountID("42".to_string())), "request-1".to_string());
+    let person_service = service::Service::new(store, DummyCensor::new("".to_string(), false))
+        .with_audit_context(context);
+    let runtime = Runtime::new().expect("unable to create runtime to test audit");
+    // When
+    runtime
+        .block_on(person_service.update_person(updated.clone()))
+        .unwrap();
+    runtime
+        .block_on(person_service.delete_person(person.id.clone(), Some(DeletePolicy::Orphan)))
+        .unwrap();
+    let got = runtime
+        .block_on(person_service.get_audit_entries(AuditQuery::default()))
+        .unwrap();
+    let got_of_pet = runtime
+        .block_on(person_service.get_audit_entries(AuditQuery {
+            entity_id: Some("10".to_string()),
+            ..Default::default()
+        }))
+        .unwrap();
+    let got_of_nobody = runtime
+        .block_on(person_service.get_audit_entries(AuditQuery {
+            actor: Some(AccountID("7".to_string())),
+            ..Default::default()
+        }))
+        .unwrap();
+    // Then
+    let summary: Vec<(AuditEntity, String, AuditAction)> = got
+        .iter()
+        .map(|entry| (entry.entity, entry.entity_id.clone(), entry.action))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (AuditEntity::Pet, "10".to_string(), AuditAction::Update),
+            (AuditEntity::Person, "1".to_string(), AuditAction::Delete),
+            (AuditEntity::Person, "1".to_string(), AuditAction::Update),
+        ]
+    );
+    assert!(got
+        .iter()
+        .all(|entry| entry.request_id == "request-1"
+            && entry.actor == Some(AccountID("42".to_string()))));
+    let update = &got[2];
+    assert_eq!(update.before, serde_json::to_value(&person).ok());
+    assert_eq!(update.after, serde_json::to_value(&updated).ok());
+    assert_eq!(got[1].after, None);
+    assert_eq!(got_of_pet.len(), 1);
+    assert_eq!(
+        got_of_pet[0]
+            .after
+            .as_ref()
+            .map(|pet| pet["person_id"].clone()),
+        Some(serde_json::Value::Null)
+    );
+    assert!(got_of_nobody.is_empty());
+}
+
+#[test]
+fn test_get_person_history_and_as_of() {
+    // Given
+    let added = Person::new(
+        PersonID("1".to_string()),
+        "Esme".to_string(),
+        "Ogg".to_string(),
+    );
+    let renamed = Person {
+        last_name: "Weatherwax".to_string(),
+        version: 2,
+        ..added.clone()
+    };
+    let deleted = Person {
+        deleted_at: Some("2026-10-12T00:00:00Z".parse().unwrap()),
+        ..renamed.clone()
+    };
+    let version = |saved_at: &str, person: &Person| PersonVersion {
+        saved_at: saved_at.parse().unwrap(),
+        person: person.clone(),
+    };
+    let a_store = DummyStore {
+        history: vec![
+            version("2026-10-01T00:00:00Z", &added),
+            version("2026-10-05T00:00:00Z", &renamed),
+            version("2026-10-12T00:00:00Z", &deleted),
+        ],
+        ..Default::default()
+    };
+    let person_service = service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+    let runtime = Runtime::new().expect("unable to create runtime to test person history");
+    let as_of = |at: &str| PeopleQuery {
+        as_of: Some(at.parse().unwrap()),
+        ..Default::default()
+    };
+    // When
+    let got_history =
+        runtime.block_on(person_service.get_person_history(PersonID("1".to_string())));
+    let got_no_history =
+        runtime.block_on(person_service.get_person_history(PersonID("2".to_string())));
+    let got_added = runtime.block_on(person_service.get_person_as_of(
+        PersonID("1".to_string()),
+        "2026-10-02T00:00:00Z".parse().unwrap(),
+        false,
+    ));
+    let got_deleted = runtime.block_on(person_service.get_person_as_of(
+        PersonID("1".to_string()),
+        "2026-10-13T00:00:00Z".parse().unwrap(),
+        false,
+    ));
+    let got_people = runtime
+        .block_on(person_service.get_people(as_of("2026-10-06T00:00:00Z"), Pagination::default()));
+    let got_no_people = runtime
+        .block_on(person_service.get_people(as_of("2026-10-13T00:00:00Z"), Pagination::default()));
+    // Then
+    let got_history = got_history.unwrap();
+    assert_eq!(got_history.len(), 3);
+    assert_eq!(got_history[1].changes[0].field, "last_name");
+    assert_eq!(got_no_history, Err(error::Error::PersonNotFound));
+    assert_eq!(got_added.unwrap().last_name, "Ogg");
+    assert_eq!(got_deleted, Err(error::Error::PersonNotFound));
+    assert_eq!(got_people.unwrap().people, vec![renamed]);
+    assert!(got_no_people.unwrap().people.is_empty());
+}
+
+#[test]
+fn test_webhooks() {
+    // Given
+    let new_webhook = NewWebhook {
+        url: "https://partner.example.com/hooks".to_string(),
+        secret: "a-long-enough-secret".to_string(),
+        events: vec![EventKind::PersonCreated],
+    };
+    let webhook = new_webhook.to_webhook();
+    let other = NewWebhook {
+        url: "https://other.example.com/hooks".to_string(),
+        ..new_webhook.clone()
+    }
+    .to_webhook();
+    let delivery = WebhookDelivery {
+        id: "d1".to_string(),
+        webhook_id: other.id.clone(),
+        event_id: 1,
+        kind: EventKind::PersonCreated,
+        payload: serde_json::json!({"event_id": 1}),
+        status: DeliveryStatus::Failed,
+        response_status: Some(500),
+        error: Some("receiver answered 500".to_string()),
+        delivered_at: Utc::now(),
+    };
+    let a_store = DummyStore {
+        webhooks: vec![webhook.clone(), other.clone()],
+        webhook_deliveries: Arc::new(Mutex::new(vec![delivery])),
+        ..Default::default()
+    };
+    let person_service = service::Service::new(a_store, DummyCensor::new("".to_string(), false));
+    let runtime = Runtime::new().expect("unable to create runtime to test webhooks");
+    // When
+    let got_added = runtime.block_on(person_service.add_webhook(new_webhook.clone()));
+    let got_invalid = runtime.block_on(person_service.add_webhook(NewWebhook {
+        url: "partner.example.com".to_string(),
+        ..new_webhook
+    }));
+    let got_webhooks = runtime.block_on(person_service.get_webhooks());
+    let got_deleted_unknown =
+        runtime.block_on(person_service.delete_webhook(WebhookID("unknown".to_string())));
+    let got_deliveries = runtime.block_on(person_service.get_webhook_deliveries(other.id, 10));
+    let got_unknown_deliveries = runtime
+        .block_on(person_service.get_webhook_deliveries(WebhookID("unknown".to_string()), 10));
+    let got_other_delivery =
+        runtime.block_on(person_service.redeliver_webhook(webhook.id, "d1".to_string()));
+    // Then
+    let got_added = got_added.unwrap();
+    assert_eq!(got_added.url, "https://partner.example.com/hooks");
+    assert_eq!(got_added.secret, "");
+    assert!(matches!(got_invalid, Err(error::Error::InvalidWebhook(_))));
+    assert!(got_webhooks
+        .unwrap()
+        .iter()
+        .all(|webhook| webhook.secret.is_empty()));
+    assert_eq!(got_deleted_unknown, Err(error::Error::WebhookNotFound));
+    assert_eq!(got_deliveries.unwrap()[0].id, "d1");
+    assert_eq!(got_unknown_deliveries, Err(error::Error::WebhookNotFound));
+    assert_eq!(
+        got_other_delivery,
+        Err(error::Error::WebhookDeliveryNotFound)
+    );
+}
+
 #[derive(Debug, Clone)]
 struct DummyCensor {
     response: String,
@@ -224,10 +1236,7 @@ struct DummyCensor {
 
 impl DummyCensor {
     fn new(response: String, is_error: bool) -> Self {
-        DummyCensor {
-            response: response,
-            is_error: is_error,
-        }
+        DummyCensor { response, is_error }
     }
 }
 
@@ -257,7 +1266,7 @@ impl censor::Censorious for DummyCensor {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct DummyStore {
     get_people_values: Option<Vec<Person>>,
     get_people_error: Option<bool>,
@@ -271,106 +1280,499 @@ struct DummyStore {
     delete_person_error: Option<bool>,
     add_pet_value: Option<Pet>,
     add_pet_error: Option<bool>,
+    person_not_found: bool,
+    purged_people: u64,
+    purge_people_error: bool,
+    relatives: Vec<Person>,
+    relationships: Vec<Relationship>,
+    merges: Vec<Merge>,
+    audit: Arc<Mutex<Vec<AuditEntry>>>,
+    history: Vec<PersonVersion>,
+    webhooks: Vec<Webhook>,
+    webhook_deliveries: Arc<Mutex<Vec<WebhookDelivery>>>,
+    live_events: LiveEvents,
 }
 
 impl DummyStore {
     fn new_with_get_people(people: Vec<Person>, is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.get_people_values = Some(people);
-        dummy_store.get_people_error = Some(is_error);
-
-        dummy_store
+        DummyStore {
+            get_people_values: Some(people),
+            get_people_error: Some(is_error),
+            ..Default::default()
+        }
     }
     fn new_with_get_person(person: Option<Person>, is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.get_person_value = person;
-        dummy_store.get_person_error = Some(is_error);
-
-        dummy_store
+        DummyStore {
+            get_person_value: person,
+            get_person_error: Some(is_error),
+            ..Default::default()
+        }
     }
     fn new_with_add_person(person: Option<Person>, is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.add_person_value = person;
-        dummy_store.add_person_error = Some(is_error);
-
-        dummy_store
+        DummyStore {
+            add_person_value: person,
+            add_person_error: Some(is_error),
+            ..Default::default()
+        }
     }
     fn new_with_delete_person(result: bool, is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.delete_person_error = Some(is_error);
-        dummy_store.delete_person_value = Some(result);
-
-        dummy_store
+        DummyStore {
+            delete_person_error: Some(is_error),
+            delete_person_value: Some(result),
+            ..Default::default()
+        }
     }
-    fn new_with_update_person(person: Option<Person>, is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.update_person_error = Some(is_error);
-        dummy_store.update_person_value = person;
-
-        dummy_store
+    fn new_with_purge_people(purged: u64, is_error: bool) -> Self {
+        DummyStore {
+            purged_people: purged,
+            purge_people_error: is_error,
+            ..Default::default()
+        }
     }
-}
-
-impl Default for DummyStore {
-    fn default() -> DummyStore {
+    fn new_with_relationships(relatives: Vec<Person>, relationships: Vec<Relationship>) -> Self {
+        DummyStore {
+            relatives,
+            relationships,
+            ..Default::default()
+        }
+    }
+    fn new_with_merges(survivors: Vec<Person>, merges: Vec<Merge>) -> Self {
         DummyStore {
-            get_people_values: Default::default(),
-            get_people_error: Default::default(),
-            get_person_value: Default::default(),
-            get_person_error: Default::default(),
-            add_person_value: Default::default(),
-            add_person_error: Default::default(),
-            update_person_value: Default::default(),
-            update_person_error: Default::default(),
-            delete_person_value: Default::default(),
-            delete_person_error: Default::default(),
-            add_pet_value: Default::default(),
-            add_pet_error: Default::default(),
+            relatives: survivors,
+            merges,
+            ..Default::default()
+        }
+    }
+    fn new_with_person_not_found() -> Self {
+        DummyStore {
+            person_not_found: true,
+            ..Default::default()
+        }
+    }
+    fn new_with_update_person(person: Option<Person>, is_error: bool) -> Self {
+        DummyStore {
+            update_person_error: Some(is_error),
+            update_person_value: person,
+            ..Default::default()
         }
     }
 }
 
 #[async_trait]
 impl storage::Storer for DummyStore {
-    async fn get_people(&self, _: Option<i32>, _: i32) -> Result<Vec<Person>, error::Error> {
+    async fn get_people(
+        &self,
+        _: &PeopleQuery,
+        _: i32,
+        _: Option<Cursor>,
+    ) -> Result<Vec<Person>, error::Error> {
         match self.get_people_error.unwrap() {
             false => Ok(self.get_people_values.clone().unwrap()),
             true => Err(error::Error::GetPeopleError),
         }
     }
 
-    async fn get_person(&self, _: PersonID) -> Result<Person, error::Error> {
-        match &self.get_person_error.unwrap() {
-            false => Ok(self.get_person_value.clone().unwrap()),
-            true => Err(error::Error::GetPersonError),
+    async fn get_person(&self, person_id: PersonID, _: bool) -> Result<Person, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+        if let Some(person) = self.relatives.iter().find(|person| person.id == person_id) {
+            return Ok(person.clone());
+        }
+        // merged people are deleted.
+        if self.merges.iter().any(|merge| merge.source_id == person_id) {
+            return Err(error::Error::PersonNotFound);
+        }
+
+        match self.get_person_error {
+            Some(true) => Err(error::Error::GetPersonError),
+            _ => self
+                .get_person_value
+                .clone()
+                .ok_or(error::Error::PersonNotFound),
         }
     }
 
-    async fn add_person(&self, _: Person) -> Result<Person, error::Error> {
+    async fn add_person(&self, _: Person, _: &AuditContext) -> Result<Person, error::Error> {
         match &self.add_person_error.unwrap() {
             false => Ok(self.add_person_value.clone().unwrap()),
             true => Err(error::Error::CreatePersonError),
         }
     }
 
-    async fn update_person(&self, _: Person) -> Result<Person, error::Error> {
+    async fn add_people(
+        &self,
+        people: Vec<Person>,
+        _: &AuditContext,
+    ) -> Result<Vec<Person>, error::Error> {
+        match self.add_person_error.unwrap_or(false) {
+            false => Ok(people),
+            true => Err(error::Error::CreatePersonError),
+        }
+    }
+
+    async fn stream_people(
+        &self,
+        include_pets: bool,
+    ) -> Result<storage::PeopleStream, error::Error> {
+        let people: Vec<Result<PersonWithPets, error::Error>> = self
+            .get_people_values
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|person| {
+                Ok(PersonWithPets {
+                    person,
+                    pets: include_pets.then(Vec::new),
+                })
+            })
+            .collect();
+        Ok(futures::stream::iter(people).boxed())
+    }
+
+    async fn search_people(&self, _: &str, _: i32) -> Result<Vec<PersonMatch>, error::Error> {
+        match self.get_people_error.unwrap() {
+            false => Ok(self
+                .get_people_values
+                .clone()
+                .unwrap()
+                .into_iter()
+                .map(|person| PersonMatch { person, score: 1.0 })
+                .collect()),
+            true => Err(error::Error::GetPeopleError),
+        }
+    }
+
+    async fn update_person(&self, _: Person, audit: &AuditContext) -> Result<Person, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+
         match &self.update_person_error.unwrap() {
-            false => Ok(self.update_person_value.clone().unwrap()),
+            false => {
+                let updated = self.update_person_value.clone().unwrap();
+                self.audit.lock().unwrap().push(audit.person_entry(
+                    AuditAction::Update,
+                    self.get_person_value.as_ref(),
+                    Some(&updated),
+                ));
+                Ok(updated)
+            }
             true => Err(error::Error::UpdatePersonError),
         }
     }
 
-    async fn delete_person(&self, _: PersonID) -> Result<bool, error::Error> {
+    async fn delete_person(
+        &self,
+        _: PersonID,
+        policy: DeletePolicy,
+        audit: &AuditContext,
+    ) -> Result<bool, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+        if let (DeletePolicy::Refuse, Some(pet)) = (policy, &self.add_pet_value) {
+            return Err(error::Error::PersonHasPets(vec![pet.clone()]));
+        }
+
         match &self.delete_person_error.unwrap() {
-            false => Ok(self.delete_person_value.unwrap()),
+            false => {
+                let mut entries = vec![audit.person_entry(
+                    AuditAction::Delete,
+                    self.get_person_value.as_ref(),
+                    None,
+                )];
+                if let (DeletePolicy::Orphan, Some(pet)) = (policy, &self.add_pet_value) {
+                    let orphan = Pet {
+                        person_id: None,
+                        ..pet.clone()
+                    };
+                    entries.push(audit.pet_entry(AuditAction::Update, Some(pet), Some(&orphan)));
+                }
+                self.audit.lock().unwrap().extend(entries);
+                Ok(self.delete_person_value.unwrap())
+            }
             true => Err(error::Error::DeletePersonError),
         }
     }
 
-    async fn add_pet(&self, _: Pet) -> Result<Pet, error::Error> {
+    async fn restore_person(&self, _: PersonID, _: &AuditContext) -> Result<Person, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+
+        match &self.get_person_error.unwrap() {
+            false => Ok(self.get_person_value.clone().unwrap()),
+            true => Err(error::Error::RestorePersonError),
+        }
+    }
+
+    async fn merge_people(
+        &self,
+        person: Person,
+        _: PersonID,
+        _: &AuditContext,
+    ) -> Result<Person, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+
+        match self.update_person_error.unwrap_or(false) {
+            false => Ok(Person {
+                version: person.version + 1,
+                ..person
+            }),
+            true => Err(error::Error::DatabaseQueryError),
+        }
+    }
+
+    async fn get_merge(&self, source_id: PersonID) -> Result<Option<Merge>, error::Error> {
+        Ok(self
+            .merges
+            .iter()
+            .find(|merge| merge.source_id == source_id)
+            .cloned())
+    }
+
+    async fn purge_people(
+        &self,
+        _: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, error::Error> {
+        if self.purge_people_error {
+            return Err(error::Error::PurgePeopleError);
+        }
+
+        let purged: Vec<Person> = (0..self.purged_people)
+            .map(|id| Person::new(PersonID(id.to_string()), id.to_string(), id.to_string()))
+            .collect();
+        self.audit.lock().unwrap().extend(
+            purged
+                .iter()
+                .map(|person| audit.person_entry(AuditAction::Purge, Some(person), None)),
+        );
+        Ok(purged)
+    }
+
+    async fn add_pet(&self, _: Pet, _: &AuditContext) -> Result<Pet, error::Error> {
         match &self.add_pet_error.unwrap() {
             false => Ok(self.add_pet_value.clone().unwrap()),
             true => Err(error::Error::AddPetError),
         }
     }
+
+    async fn get_pets(&self, owners: &[PersonID]) -> Result<Vec<Pet>, error::Error> {
+        Ok(self
+            .add_pet_value
+            .clone()
+            .into_iter()
+            .filter(|pet| {
+                pet.person_id
+                    .as_ref()
+                    .is_some_and(|owner| owners.contains(owner))
+            })
+            .collect())
+    }
+
+    async fn get_pet(&self, pet_id: PetID) -> Result<Pet, error::Error> {
+        self.add_pet_value
+            .clone()
+            .filter(|pet| pet.id == pet_id)
+            .ok_or(error::Error::PetNotFound)
+    }
+
+    async fn update_pet(&self, pet: Pet, _: &AuditContext) -> Result<Pet, error::Error> {
+        self.get_pet(pet.id.clone()).await?;
+        match self.add_pet_error.unwrap_or(false) {
+            false => Ok(pet),
+            true => Err(error::Error::UpdatePetError),
+        }
+    }
+
+    async fn delete_pet(&self, pet_id: PetID, _: &AuditContext) -> Result<bool, error::Error> {
+        self.get_pet(pet_id).await?;
+        match self.add_pet_error.unwrap_or(false) {
+            false => Ok(true),
+            true => Err(error::Error::DeletePetError),
+        }
+    }
+
+    async fn add_relationship(
+        &self,
+        relationship: Relationship,
+    ) -> Result<Relationship, error::Error> {
+        if self.person_not_found {
+            return Err(error::Error::PersonNotFound);
+        }
+
+        let already_related = self.relationships.iter().any(|saved| {
+            saved.person_id == relationship.person_id && saved.related_id == relationship.related_id
+        });
+        match already_related {
+            false => Ok(relationship),
+            true => Err(error::Error::RelationshipExists),
+        }
+    }
+
+    async fn get_relationships(
+        &self,
+        people: &[PersonID],
+    ) -> Result<Vec<Relationship>, error::Error> {
+        Ok(self
+            .relationships
+            .iter()
+            .filter(|relationship| people.contains(&relationship.person_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_relationship(
+        &self,
+        person_id: PersonID,
+        related_id: PersonID,
+    ) -> Result<bool, error::Error> {
+        let related = self
+            .relationships
+            .iter()
+            .any(|saved| saved.person_id == person_id && saved.related_id == related_id);
+        match related {
+            true => Ok(true),
+            false => Err(error::Error::RelationshipNotFound),
+        }
+    }
+    async fn add_audit_entries(&self, entries: Vec<AuditEntry>) -> Result<(), error::Error> {
+        self.audit.lock().unwrap().extend(entries);
+        Ok(())
+    }
+
+    async fn get_person_history(
+        &self,
+        person_id: PersonID,
+    ) -> Result<Vec<PersonVersion>, error::Error> {
+        Ok(self
+            .history
+            .iter()
+            .filter(|version| version.person.id == person_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_people_as_of(&self, at: DateTime<Utc>) -> Result<Vec<Person>, error::Error> {
+        let mut people: Vec<Person> = Vec::new();
+        for version in self.history.iter().filter(|version| version.saved_at <= at) {
+            people.retain(|person| person.id != version.person.id);
+            people.push(version.person.clone());
+        }
+        Ok(people)
+    }
+
+    async fn get_pending_events(&self, _: i32) -> Result<Vec<DomainEvent>, error::Error> {
+        Ok(vec![])
+    }
+
+    async fn mark_events_sent(&self, _: &[i64]) -> Result<(), error::Error> {
+        Ok(())
+    }
+
+    async fn get_events_after(&self, _: i64, _: i32) -> Result<Vec<DomainEvent>, error::Error> {
+        Ok(vec![])
+    }
+
+    async fn get_last_event_id(&self) -> Result<i64, error::Error> {
+        Ok(0)
+    }
+
+    fn live_events(&self) -> &LiveEvents {
+        &self.live_events
+    }
+
+    async fn add_webhook(&self, webhook: Webhook) -> Result<Webhook, error::Error> {
+        Ok(webhook)
+    }
+
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, error::Error> {
+        Ok(self.webhooks.clone())
+    }
+
+    async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, error::Error> {
+        self.webhooks
+            .iter()
+            .find(|webhook| webhook.id == webhook_id)
+            .cloned()
+            .ok_or(error::Error::WebhookNotFound)
+    }
+
+    async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, error::Error> {
+        self.get_webhook(webhook_id).await.map(|_| true)
+    }
+
+    async fn add_webhook_delivery(&self, delivery: WebhookDelivery) -> Result<(), error::Error> {
+        self.webhook_deliveries.lock().unwrap().push(delivery);
+        Ok(())
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookID,
+        limit: i32,
+    ) -> Result<Vec<WebhookDelivery>, error::Error> {
+        Ok(self
+            .webhook_deliveries
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|delivery| delivery.webhook_id == webhook_id)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_webhook_delivery(
+        &self,
+        delivery_id: String,
+    ) -> Result<WebhookDelivery, error::Error> {
+        self.webhook_deliveries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|delivery| delivery.id == delivery_id)
+            .cloned()
+            .ok_or(error::Error::WebhookDeliveryNotFound)
+    }
+
+    async fn add_people_unless_duplicates(
+        &self,
+        people: Vec<Person>,
+        detection: &DuplicateDetection,
+        _: &AuditContext,
+    ) -> Result<Vec<Result<Person, error::Error>>, error::Error> {
+        if self.add_person_error.unwrap_or(false) {
+            return Err(error::Error::CreatePersonError);
+        }
+
+        let existing = self.get_people_values.clone().unwrap_or_default();
+        Ok(people
+            .into_iter()
+            .map(|person| {
+                let matches = detection.matches(&person, existing.clone());
+                match matches.is_empty() {
+                    true => Ok(self.add_person_value.clone().unwrap_or(person)),
+                    false => Err(error::Error::PossibleDuplicates(matches)),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_audit_entries(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, error::Error> {
+        Ok(self
+            .audit
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|entry| query.matches(entry))
+            .cloned()
+            .collect())
+    }
 }
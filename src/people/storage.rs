@@ -1,19 +1,172 @@
 use crate::errors::error::Error;
+use crate::people::duplicates::DuplicateDetection;
+use crate::people::live::LiveEvents;
 use crate::types::{
-    people::{Person, PersonID},
-    pets::Pet,
+    audit::{AuditContext, AuditEntry, AuditQuery},
+    events::DomainEvent,
+    history::PersonVersion,
+    merges::Merge,
+    pagination::Cursor,
+    people::{Person, PersonID, PersonMatch, PersonWithPets},
+    pets::{DeletePolicy, Pet, PetID},
+    query::PeopleQuery,
+    relationships::Relationship,
+    webhooks::{Webhook, WebhookDelivery, WebhookID},
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
 use std::fmt::{Debug, Error as FmtError, Formatter};
 
+/// PeopleStream yields people one at a time as they are read.
+pub type PeopleStream = BoxStream<'static, Result<PersonWithPets, Error>>;
+
+/// Storer saves people and pets. Every change takes the audit context of the
+/// request making it and saves its audit entries together with the change, so
+/// a change is never saved without them.
 #[async_trait]
 pub trait Storer {
-    async fn add_person(&self, new_person: Person) -> Result<Person, Error>;
-    async fn get_person(&self, person_id: PersonID) -> Result<Person, Error>;
-    async fn get_people(&self, limit: Option<i32>, offset: i32) -> Result<Vec<Person>, Error>;
-    async fn update_person(&self, person: Person) -> Result<Person, Error>;
-    async fn delete_person(&self, person_id: PersonID) -> Result<bool, Error>;
-    async fn add_pet(&self, new_pet: Pet) -> Result<Pet, Error>;
+    async fn add_person(&self, new_person: Person, audit: &AuditContext) -> Result<Person, Error>;
+    /// add_people adds all the people or none of them, they are returned in
+    /// the given order.
+    async fn add_people(
+        &self,
+        new_people: Vec<Person>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error>;
+    /// add_people_unless_duplicates saves, in one transaction, the people
+    /// whose names do not match the name of someone saved before them, earlier
+    /// people of the list included. The others are not saved and get
+    /// PossibleDuplicates with the people they match. The names are checked
+    /// and saved together, so the same person added twice at once is saved
+    /// once.
+    async fn add_people_unless_duplicates(
+        &self,
+        new_people: Vec<Person>,
+        detection: &DuplicateDetection,
+        audit: &AuditContext,
+    ) -> Result<Vec<Result<Person, Error>>, Error>;
+    async fn get_person(&self, person_id: PersonID, include_deleted: bool)
+        -> Result<Person, Error>;
+    /// get_people returns up to limit people matching the query, in the query
+    /// order with the id breaking ties, starting right after the given cursor.
+    async fn get_people(
+        &self,
+        query: &PeopleQuery,
+        limit: i32,
+        after: Option<Cursor>,
+    ) -> Result<Vec<Person>, Error>;
+    /// stream_people returns every person who is not deleted ordered by id,
+    /// with their pets when asked, without holding them all in memory.
+    async fn stream_people(&self, include_pets: bool) -> Result<PeopleStream, Error>;
+    /// search_people returns the people whose names best match the terms,
+    /// typos and partial names included.
+    async fn search_people(&self, terms: &str, limit: i32) -> Result<Vec<PersonMatch>, Error>;
+    async fn update_person(&self, person: Person, audit: &AuditContext) -> Result<Person, Error>;
+    /// delete_person only marks the person as deleted, see purge_people. Their
+    /// pets are deleted, left without an owner or, with the refuse policy,
+    /// returned in a PersonHasPets error without deleting the person.
+    async fn delete_person(
+        &self,
+        person_id: PersonID,
+        policy: DeletePolicy,
+        audit: &AuditContext,
+    ) -> Result<bool, Error>;
+    /// restore_person undoes the deletion of a person, PersonNotDeleted is
+    /// returned when they are not deleted and PersonMerged when they were
+    /// merged into someone else.
+    async fn restore_person(
+        &self,
+        person_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error>;
+    /// merge_people saves the merged person, who must still have the version
+    /// they were read with, gives them the pets and relationships of the
+    /// source, deletes the source and records the merge. Both people must
+    /// exist and not be deleted.
+    async fn merge_people(
+        &self,
+        person: Person,
+        source_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error>;
+    /// get_merge returns the merge of the given person into another one, if
+    /// they were merged.
+    async fn get_merge(&self, source_id: PersonID) -> Result<Option<Merge>, Error>;
+    /// get_person_history returns every version of the person, oldest first,
+    /// including the current one. Every change of a person saves a version.
+    async fn get_person_history(&self, person_id: PersonID) -> Result<Vec<PersonVersion>, Error>;
+    /// get_people_as_of returns every person as they were last saved at or
+    /// before the given time, deleted people included.
+    async fn get_people_as_of(&self, at: DateTime<Utc>) -> Result<Vec<Person>, Error>;
+    /// purge_people removes for good the people deleted before the given time,
+    /// together with their history. They are returned as they were.
+    async fn purge_people(
+        &self,
+        deleted_before: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error>;
+    /// add_pet saves the pet, its owner must exist and not be deleted or
+    /// PersonNotFound is returned.
+    async fn add_pet(&self, new_pet: Pet, audit: &AuditContext) -> Result<Pet, Error>;
+    async fn get_pet(&self, pet_id: PetID) -> Result<Pet, Error>;
+    /// get_pets returns the pets of the given people ordered by owner and id.
+    async fn get_pets(&self, owners: &[PersonID]) -> Result<Vec<Pet>, Error>;
+    /// update_pet saves the name and owner of the pet, the owner must exist
+    /// and not be deleted or PersonNotFound is returned. An orphaned pet can
+    /// be saved without owner.
+    async fn update_pet(&self, pet: Pet, audit: &AuditContext) -> Result<Pet, Error>;
+    async fn delete_pet(&self, pet_id: PetID, audit: &AuditContext) -> Result<bool, Error>;
+    /// add_relationship saves the relationship with its reciprocal, both
+    /// people must exist and not be deleted nor be related already.
+    async fn add_relationship(&self, relationship: Relationship) -> Result<Relationship, Error>;
+    /// get_relationships returns the relationships of the given people with
+    /// people who are not deleted, ordered by person and related person.
+    async fn get_relationships(&self, people: &[PersonID]) -> Result<Vec<Relationship>, Error>;
+    /// delete_relationship removes the relationship between both people in
+    /// both directions.
+    async fn delete_relationship(
+        &self,
+        person_id: PersonID,
+        related_id: PersonID,
+    ) -> Result<bool, Error>;
+    /// add_audit_entries appends the entries to the audit log, all of them or
+    /// none. The changes of the store save their own entries.
+    async fn add_audit_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Error>;
+    /// get_audit_entries returns up to the limit of the query of the entries
+    /// matching it, the newest first.
+    async fn get_audit_entries(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, Error>;
+    /// get_pending_events returns up to limit events of the outbox which were
+    /// not sent yet, in the order the changes were saved. Every change of a
+    /// person or a pet writes its event together with the change.
+    async fn get_pending_events(&self, limit: i32) -> Result<Vec<DomainEvent>, Error>;
+    /// mark_events_sent marks the events as sent, so they are not pending
+    /// anymore.
+    async fn mark_events_sent(&self, ids: &[i64]) -> Result<(), Error>;
+    /// get_events_after returns up to limit events of the outbox saved after
+    /// the given one, sent or not, in the order the changes were saved.
+    async fn get_events_after(&self, after: i64, limit: i32) -> Result<Vec<DomainEvent>, Error>;
+    /// get_last_event_id returns the id of the newest event of the outbox, 0
+    /// when it is empty.
+    async fn get_last_event_id(&self) -> Result<i64, Error>;
+    /// live_events is what the store notifies every time it commits events to
+    /// the outbox, it wakes up the streams of live events.
+    fn live_events(&self) -> &LiveEvents;
+    async fn add_webhook(&self, webhook: Webhook) -> Result<Webhook, Error>;
+    /// get_webhooks returns every webhook, the oldest first.
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, Error>;
+    async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, Error>;
+    /// delete_webhook removes the webhook together with its deliveries.
+    async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, Error>;
+    async fn add_webhook_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error>;
+    /// get_webhook_deliveries returns up to limit deliveries of the webhook,
+    /// the newest first.
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookID,
+        limit: i32,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+    async fn get_webhook_delivery(&self, delivery_id: String) -> Result<WebhookDelivery, Error>;
 }
 
 impl Debug for dyn Storer {
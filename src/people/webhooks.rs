@@ -0,0 +1,175 @@
+use crate::errors::error::Error;
+use crate::people::outbox::Publisher;
+use crate::people::storage;
+use crate::types::events::{DomainEvent, EventKind};
+use crate::types::webhooks::{self, DeliveryStatus, Webhook, WebhookDelivery, WebhookPayload};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future;
+use reqwest::{header::CONTENT_TYPE, Client};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// The header with the HMAC-SHA256 signature of the body
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// The header with the kind of the event
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+/// The header with the id of the delivery, it changes on redelivery
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// The most times a transient failure of a delivery is retried
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// How long a receiver has to answer a single attempt
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// WebhookClient posts signed payloads to webhooks, retrying transient
+/// failures with an exponential backoff.
+#[derive(Debug, Clone)]
+pub struct WebhookClient {
+    api_client_mdw: ClientWithMiddleware,
+}
+
+impl WebhookClient {
+    pub fn new(client: Client, retry_policy: ExponentialBackoff) -> Self {
+        let client_mdw = ClientBuilder::new(client)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        WebhookClient {
+            api_client_mdw: client_mdw,
+        }
+    }
+
+    /// deliver posts the payload of the event to the webhook and returns how
+    /// it went, a failure is part of the delivery and not an error.
+    pub async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event_id: i64,
+        kind: EventKind,
+        payload: serde_json::Value,
+    ) -> WebhookDelivery {
+        let id = uuid::Uuid::new_v4().to_string();
+        let body = payload.to_string();
+        debug!(
+            "delivering event {} to webhook {} as {}",
+            event_id, webhook.id, id
+        );
+
+        let res = self
+            .api_client_mdw
+            .post(webhook.url.as_str())
+            .header(CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                webhooks::sign(&webhook.secret, body.as_bytes()),
+            )
+            .header(EVENT_HEADER, kind.as_str())
+            .header(DELIVERY_HEADER, id.as_str())
+            .body(body)
+            .send()
+            .await;
+
+        let (status, response_status, error) = match res {
+            Ok(res) if res.status().is_success() => {
+                (DeliveryStatus::Delivered, Some(res.status().as_u16()), None)
+            }
+            Ok(res) => (
+                DeliveryStatus::Failed,
+                Some(res.status().as_u16()),
+                Some(format!("receiver answered {}", res.status())),
+            ),
+            Err(e) => (DeliveryStatus::Failed, None, Some(e.to_string())),
+        };
+        if let Some(error) = &error {
+            warn!(
+                "delivering event {} to webhook {}: {}",
+                event_id, webhook.id, error
+            );
+        }
+
+        WebhookDelivery {
+            id,
+            webhook_id: webhook.id.clone(),
+            event_id,
+            kind,
+            payload,
+            status,
+            response_status,
+            error,
+            delivered_at: Utc::now(),
+        }
+    }
+}
+
+impl Default for WebhookClient {
+    fn default() -> Self {
+        let client = Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let retry_policy =
+            ExponentialBackoff::builder().build_with_max_retries(DEFAULT_MAX_RETRIES);
+
+        WebhookClient::new(client, retry_policy)
+    }
+}
+
+/// WebhookPublisher posts every event of the outbox to the webhooks
+/// subscribed to its kind, and keeps the delivery log.
+#[derive(Debug, Clone)]
+pub struct WebhookPublisher<T: storage::Storer> {
+    store: T,
+    client: WebhookClient,
+}
+
+impl<T: storage::Storer> WebhookPublisher<T> {
+    pub fn new(store: T, client: WebhookClient) -> Self {
+        WebhookPublisher { store, client }
+    }
+}
+
+#[async_trait]
+impl<T: storage::Storer + Send + Sync> Publisher for WebhookPublisher<T> {
+    /// publish delivers the event to its webhooks at the same time. A webhook
+    /// which keeps failing does not hold back the next events, its failed
+    /// delivery is logged so an operator can redeliver it.
+    async fn publish(&self, event: &DomainEvent) -> Result<(), Error> {
+        let subscribed: Vec<Webhook> = self
+            .store
+            .get_webhooks()
+            .await
+            .map_err(|e| Error::PublishEventError(format!("getting webhooks: {e}")))?
+            .into_iter()
+            .filter(|webhook| webhook.accepts(event.kind))
+            .collect();
+        if subscribed.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::to_value(WebhookPayload::from(event))
+            .map_err(|e| Error::PublishEventError(format!("encoding payload: {e}")))?;
+        let deliveries = future::join_all(subscribed.iter().map(|webhook| {
+            self.client
+                .deliver(webhook, event.id, event.kind, payload.clone())
+        }))
+        .await;
+
+        for delivery in deliveries {
+            match self.store.add_webhook_delivery(delivery).await {
+                Ok(()) => {}
+                // the webhook was deleted while it was being delivered to.
+                Err(Error::WebhookNotFound) => {}
+                Err(e) => {
+                    return Err(Error::PublishEventError(format!(
+                        "saving webhook delivery: {e}"
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
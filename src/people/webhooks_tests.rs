@@ -0,0 +1,229 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod webhooks_tests {
+    use crate::errors::error;
+    use crate::people::censor::Censorious;
+    use crate::people::outbox::Dispatcher;
+    use crate::people::service::Service;
+    use crate::people::storage::Storer;
+    use crate::people::webhooks::{
+        WebhookClient, WebhookPublisher, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER,
+    };
+    use crate::storage::memory::Store;
+    use crate::types::audit::AuditContext;
+    use crate::types::events::EventKind;
+    use crate::types::people::{Person, PersonID};
+    use crate::types::webhooks::{sign, DeliveryStatus, NewWebhook, Webhook};
+    use async_trait::async_trait;
+    use hyper::body::Bytes;
+    use reqwest_retry::policies::ExponentialBackoff;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::runtime::Runtime;
+    use warp::http::{HeaderMap, StatusCode};
+    use warp::Filter;
+
+    const SECRET: &str = "a-long-enough-secret";
+
+    #[test]
+    fn test_publish_signed_payload() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test webhooks");
+        let store = Store::new();
+        let people_receiver = runtime.block_on(Receiver::start(vec![200]));
+        let pets_receiver = runtime.block_on(Receiver::start(vec![200]));
+        let people_webhook = runtime.block_on(add_webhook(
+            &store,
+            &people_receiver,
+            vec![EventKind::PersonCreated],
+        ));
+        runtime.block_on(add_webhook(
+            &store,
+            &pets_receiver,
+            vec![EventKind::PetAdded],
+        ));
+        runtime.block_on(add_person(&store, "7"));
+        let dispatcher = Dispatcher::new(
+            store.clone(),
+            WebhookPublisher::new(store.clone(), new_client(0)),
+        );
+        // When
+        let got = runtime.block_on(dispatcher.dispatch());
+        // Then
+        assert_eq!(got, Ok(1));
+        assert!(pets_receiver.requests().is_empty());
+        let requests = people_receiver.requests();
+        assert_eq!(requests.len(), 1);
+        let (headers, body) = &requests[0];
+        assert_eq!(headers[SIGNATURE_HEADER], sign(SECRET, body).as_str());
+        assert_eq!(headers[EVENT_HEADER], "PersonCreated");
+        let payload: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["kind"], "PersonCreated");
+        assert_eq!(payload["aggregate_id"], "7");
+        assert_eq!(payload["data"]["first_name"], "Esme");
+        let deliveries = runtime
+            .block_on(store.get_webhook_deliveries(people_webhook.id, 10))
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(headers[DELIVERY_HEADER], deliveries[0].id.as_str());
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+        assert_eq!(deliveries[0].response_status, Some(200));
+        assert_eq!(deliveries[0].payload, payload);
+    }
+
+    #[test]
+    fn test_publish_retries_transient_failures() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test webhooks");
+        let store = Store::new();
+        let receiver = runtime.block_on(Receiver::start(vec![503, 503, 200]));
+        let webhook = runtime.block_on(add_webhook(&store, &receiver, vec![]));
+        runtime.block_on(add_person(&store, "7"));
+        let dispatcher = Dispatcher::new(
+            store.clone(),
+            WebhookPublisher::new(store.clone(), new_client(3)),
+        );
+        // When
+        let got = runtime.block_on(dispatcher.dispatch());
+        // Then
+        assert_eq!(got, Ok(1));
+        assert_eq!(receiver.requests().len(), 3);
+        let deliveries = runtime
+            .block_on(store.get_webhook_deliveries(webhook.id, 10))
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, DeliveryStatus::Delivered);
+    }
+
+    #[test]
+    fn test_failed_delivery_is_logged_and_redelivered() {
+        // Given
+        let runtime = Runtime::new().expect("unable to create runtime to test webhooks");
+        let store = Store::new();
+        let receiver = runtime.block_on(Receiver::start(vec![500, 500, 200]));
+        let webhook = runtime.block_on(add_webhook(&store, &receiver, vec![]));
+        runtime.block_on(add_person(&store, "7"));
+        let dispatcher = Dispatcher::new(
+            store.clone(),
+            WebhookPublisher::new(store.clone(), new_client(1)),
+        );
+        let service = Service::new(store.clone(), DummyCensor).with_webhook_client(new_client(1));
+        // When
+        let got = runtime.block_on(dispatcher.dispatch());
+        let failed = runtime
+            .block_on(store.get_webhook_deliveries(webhook.id.clone(), 10))
+            .unwrap();
+        let got_redelivery =
+            runtime.block_on(service.redeliver_webhook(webhook.id.clone(), failed[0].id.clone()));
+        let got_unknown =
+            runtime.block_on(service.redeliver_webhook(webhook.id.clone(), "unknown".to_string()));
+        // Then
+        assert_eq!(got, Ok(1));
+        assert!(runtime
+            .block_on(store.get_pending_events(10))
+            .unwrap()
+            .is_empty());
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].status, DeliveryStatus::Failed);
+        assert_eq!(failed[0].response_status, Some(500));
+        let got_redelivery = got_redelivery.unwrap();
+        assert_eq!(got_redelivery.status, DeliveryStatus::Delivered);
+        assert_ne!(got_redelivery.id, failed[0].id);
+        assert_eq!(got_redelivery.payload, failed[0].payload);
+        let requests = receiver.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].1, requests[0].1);
+        assert_eq!(
+            requests[2].0[SIGNATURE_HEADER],
+            sign(SECRET, &requests[2].1).as_str()
+        );
+        let deliveries = runtime
+            .block_on(store.get_webhook_deliveries(webhook.id, 10))
+            .unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(got_unknown, Err(error::Error::WebhookDeliveryNotFound));
+    }
+
+    async fn add_webhook(store: &Store, receiver: &Receiver, events: Vec<EventKind>) -> Webhook {
+        let new_webhook = NewWebhook {
+            url: receiver.url.clone(),
+            secret: SECRET.to_string(),
+            events,
+        };
+        store.add_webhook(new_webhook.to_webhook()).await.unwrap()
+    }
+
+    async fn add_person(store: &Store, id: &str) {
+        store
+            .add_person(
+                Person::new(
+                    PersonID(id.to_string()),
+                    "Esme".to_string(),
+                    "Weatherwax".to_string(),
+                ),
+                &AuditContext::default(),
+            )
+            .await
+            .unwrap();
+    }
+
+    /// new_client retries a few milliseconds apart, so the tests are quick.
+    fn new_client(max_retries: u32) -> WebhookClient {
+        let retry_policy = ExponentialBackoff::builder()
+            .retry_bounds(Duration::from_millis(1), Duration::from_millis(10))
+            .build_with_max_retries(max_retries);
+        WebhookClient::new(reqwest::Client::new(), retry_policy)
+    }
+
+    /// Receiver is a local HTTP server which records the requests it gets and
+    /// answers them with the given statuses, the last one over and over.
+    struct Receiver {
+        url: String,
+        requests: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    }
+
+    impl Receiver {
+        async fn start(statuses: Vec<u16>) -> Self {
+            let requests = Arc::new(Mutex::new(vec![]));
+            let recorded = requests.clone();
+            let route = warp::post()
+                .and(warp::header::headers_cloned())
+                .and(warp::body::bytes())
+                .map(move |headers: HeaderMap, body: Bytes| {
+                    let mut recorded = recorded.lock().unwrap();
+                    recorded.push((headers, body));
+                    let status = statuses
+                        .get(recorded.len() - 1)
+                        .or(statuses.last())
+                        .copied()
+                        .unwrap_or(200);
+                    warp::reply::with_status("", StatusCode::from_u16(status).unwrap())
+                });
+            let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+            tokio::spawn(server);
+
+            Receiver {
+                url: format!("http://{addr}/hooks"),
+                requests,
+            }
+        }
+
+        fn requests(&self) -> Vec<(HeaderMap, Bytes)> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct DummyCensor;
+
+    #[async_trait]
+    impl Censorious for DummyCensor {
+        async fn censor(&self, word: String) -> Result<String, error::Error> {
+            Ok(word)
+        }
+
+        async fn censor_with_backoff(&self, word: String) -> Result<String, error::Error> {
+            Ok(word)
+        }
+    }
+}
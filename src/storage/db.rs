@@ -1,59 +1,1272 @@
+use std::collections::HashMap;
+
 use crate::errors::error::Error;
-use crate::people::storage::Storer as people_storage;
+use crate::people::duplicates::{self, DuplicateCheck, DuplicateDetection, Shortlist};
+use crate::people::live::LiveEvents;
+use crate::people::storage::{PeopleStream, Storer as people_storage};
+use crate::storage::stream;
 use crate::users::storage::Storer as users_storage;
 use async_trait::async_trait;
-use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
-use sqlx::Row;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::{PgConnection, PgPool, PgPoolOptions, PgRow, Postgres};
+use sqlx::{QueryBuilder, Row};
 use tracing::debug;
 
-use crate::types::{
-    accounts::{Account, AccountID},
-    people::{Person, PersonID},
-    pets::{Pet, PetID},
-};
+use crate::types::{
+    accounts::{Account, AccountID},
+    audit::{AuditAction, AuditContext, AuditEntry, AuditQuery},
+    events::{DomainEvent, EventKind, NewEvent},
+    history::PersonVersion,
+    merges::Merge,
+    pagination::Cursor,
+    people::{Person, PersonID, PersonMatch, PersonWithPets},
+    pets::{DeletePolicy, Pet, PetID},
+    profile::{Address, Profile},
+    query::{NameMatch, PeopleQuery, SortOrder, SortValue},
+    relationships::Relationship,
+    webhooks::{Webhook, WebhookDelivery, WebhookID},
+};
+
+const DUPLICATE_KEY: i32 = 23505;
+const FOREIGN_KEY_VIOLATION: i32 = 23503;
+/// The advisory lock taken by the transactions writing to the outbox
+const OUTBOX_LOCK: i64 = 0x6f7574626f78;
+/// The advisory lock taken by the transactions looking for duplicates
+const DUPLICATES_LOCK: i64 = 0x6475706c6963;
+/// The most people read at the same time by a stream of people
+const STREAM_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone)]
+pub struct Store {
+    pub connection: PgPool,
+    live_events: LiveEvents,
+}
+
+impl Store {
+    pub async fn new(db_url: &str) -> Result<Self, Error> {
+        match PgPoolOptions::new()
+            .max_connections(5)
+            .connect(db_url)
+            .await
+        {
+            Ok(pool) => {
+                backfill_name_keys(&pool).await?;
+                Ok(Store {
+                    connection: pool,
+                    live_events: LiveEvents::new(),
+                })
+            }
+            Err(e) => {
+                tracing::event!(
+                    tracing::Level::ERROR,
+                    "couldn't establish DB connection! {e}"
+                );
+                Err(Error::DatabaseConnectionError)
+            }
+        }
+    }
+
+    async fn person_exists(&self, person_id: PersonID) -> Result<bool, Error> {
+        match sqlx::query("SELECT ID FROM people WHERE ID = $1 AND DELETED_AT IS NULL")
+            .bind(person_id.to_string())
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(row) => Ok(row.is_some()),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    /// stream_page reads the people who are not deleted, ordered by id, after
+    /// the given one.
+    async fn stream_page(&self, after: Option<&PersonID>) -> Result<Vec<Person>, Error> {
+        match sqlx::query(
+            "SELECT * FROM people WHERE DELETED_AT IS NULL AND ($1::VARCHAR IS NULL OR ID > $1) ORDER BY ID LIMIT $2",
+        )
+        .bind(after.map(|person_id| person_id.to_string()))
+        .bind(STREAM_PAGE_SIZE)
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(people) => Ok(people),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl people_storage for Store {
+    async fn get_people(
+        &self,
+        query: &PeopleQuery,
+        limit: i32,
+        after: Option<Cursor>,
+    ) -> Result<Vec<Person>, Error> {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("SELECT * FROM people WHERE (");
+        builder.push_bind(query.include_deleted);
+        builder.push(" OR deleted_at IS NULL)");
+
+        for filter in query.filters.iter() {
+            let column = filter.field.column();
+            match filter.matching {
+                NameMatch::Exact => {
+                    builder.push(format!(" AND {column} = "));
+                    builder.push_bind(filter.value.clone());
+                }
+                NameMatch::Prefix => {
+                    builder.push(format!(" AND {column} LIKE "));
+                    builder.push_bind(filter.like_pattern());
+                    builder.push(" ESCAPE '\\'");
+                }
+                NameMatch::Contains => {
+                    builder.push(format!(" AND LOWER({column}) LIKE "));
+                    builder.push_bind(filter.like_pattern());
+                    builder.push(" ESCAPE '\\'");
+                }
+            }
+        }
+
+        let column = query.sort.field.column();
+        let (direction, comparison) = match query.sort.order {
+            SortOrder::Asc => ("ASC", ">"),
+            SortOrder::Desc => ("DESC", "<"),
+        };
+
+        if let Some(cursor) = after {
+            builder.push(format!(" AND ({column} {comparison} "));
+            push_sort_value(&mut builder, cursor.value.clone());
+            builder.push(format!(" OR ({column} = "));
+            push_sort_value(&mut builder, cursor.value);
+            builder.push(format!(" AND id {comparison} "));
+            builder.push_bind(cursor.id.to_string());
+            builder.push("))");
+        }
+
+        builder.push(format!(
+            " ORDER BY {column} {direction}, id {direction} LIMIT "
+        ));
+        builder.push_bind(limit);
+
+        match builder
+            .build()
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(people) => {
+                debug!("found some people: {:?}", people);
+                Ok(people)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_person(
+        &self,
+        person_id: PersonID,
+        include_deleted: bool,
+    ) -> Result<Person, Error> {
+        match sqlx::query("SELECT * FROM people WHERE ID = $1 AND ($2 OR DELETED_AT IS NULL)")
+            .bind(person_id.to_string())
+            .bind(include_deleted)
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(person) => Ok(person),
+            Err(sqlx::Error::RowNotFound) => Err(Error::PersonNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn stream_people(&self, include_pets: bool) -> Result<PeopleStream, Error> {
+        debug!("streaming people from postgres database");
+        let store = self.clone();
+
+        // the people are read in pages after the last one sent, so the
+        // connection goes back to the pool between pages and a slow client
+        // never holds one while it reads.
+        Ok(stream::people_stream(move |tx| async move {
+            let mut after: Option<PersonID> = None;
+            loop {
+                let page = match store.stream_page(after.as_ref()).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+
+                let mut pets: HashMap<PersonID, Vec<Pet>> = HashMap::new();
+                if include_pets {
+                    let owners: Vec<PersonID> =
+                        page.iter().map(|person| person.id.clone()).collect();
+                    match store.get_pets(&owners).await {
+                        Ok(owned) => {
+                            for pet in owned {
+                                if let Some(owner) = &pet.person_id {
+                                    pets.entry(owner.clone()).or_default().push(pet);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            let _ = tx.send(Err(e)).await;
+                            return;
+                        }
+                    }
+                }
+
+                let last_page = page.len() < STREAM_PAGE_SIZE as usize;
+                after = page.last().map(|person| person.id.clone());
+                for person in page {
+                    let pets = include_pets.then(|| pets.remove(&person.id).unwrap_or_default());
+                    if tx.send(Ok(PersonWithPets { person, pets })).await.is_err() {
+                        debug!("people stream was dropped by the client");
+                        return;
+                    }
+                }
+                if last_page {
+                    return;
+                }
+            }
+        }))
+    }
+
+    async fn search_people(&self, terms: &str, limit: i32) -> Result<Vec<PersonMatch>, Error> {
+        // the full name expression has to match the one of the search indexes.
+        match sqlx::query(
+            "SELECT *, GREATEST(similarity(first_name || ' ' || last_name, $1), word_similarity($1, first_name || ' ' || last_name)) + ts_rank(to_tsvector('simple', first_name || ' ' || last_name), plainto_tsquery('simple', $1)) AS score FROM people WHERE DELETED_AT IS NULL AND ((first_name || ' ' || last_name) % $1 OR $1 <% (first_name || ' ' || last_name) OR to_tsvector('simple', first_name || ' ' || last_name) @@ plainto_tsquery('simple', $1)) ORDER BY score DESC, ID LIMIT $2",
+        )
+        .bind(terms)
+        .bind(limit)
+        .try_map(|row: PgRow| {
+            Ok(PersonMatch {
+                person: person_from_row(&row)?,
+                score: row.try_get("score")?,
+            })
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(matches) => {
+                debug!("found some people: {:?}", matches);
+                Ok(matches)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn add_person(&self, new_person: Person, audit: &AuditContext) -> Result<Person, Error> {
+        debug!("adding person to postgres database: {:?}", new_person);
+
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let person = insert_person(&mut tx, new_person).await?;
+        record_events(
+            &mut tx,
+            vec![NewEvent::person(EventKind::PersonCreated, &person)],
+        )
+        .await?;
+        record_audit(
+            &mut tx,
+            vec![audit.person_entry(AuditAction::Create, None, Some(&person))],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                debug!("person was added to postgres database: {:?}", person);
+                Ok(person)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn add_people(
+        &self,
+        new_people: Vec<Person>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error> {
+        debug!("adding {} people to postgres database", new_people.len());
+
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let mut people = Vec::with_capacity(new_people.len());
+        for new_person in new_people {
+            let person = insert_person(&mut tx, new_person).await?;
+            record_events(
+                &mut tx,
+                vec![NewEvent::person(EventKind::PersonCreated, &person)],
+            )
+            .await?;
+            record_audit(
+                &mut tx,
+                vec![audit.person_entry(AuditAction::Create, None, Some(&person))],
+            )
+            .await?;
+            people.push(person);
+        }
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                debug!("{} people were added to postgres database", people.len());
+                Ok(people)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn add_people_unless_duplicates(
+        &self,
+        new_people: Vec<Person>,
+        detection: &DuplicateDetection,
+        audit: &AuditContext,
+    ) -> Result<Vec<Result<Person, Error>>, Error> {
+        debug!(
+            "adding {} people unless duplicates to postgres database",
+            new_people.len()
+        );
+
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+        let mut check = DuplicateCheck::new(detection, new_people);
+        let shortlist = check.shortlist();
+        lock_duplicates(&mut tx, &shortlist, &check.lock_keys()).await?;
+
+        let mut after = None;
+        loop {
+            let candidates = duplicate_candidates(&mut tx, &shortlist, after.as_ref()).await?;
+            check.score(&candidates);
+            match candidates.last() {
+                Some(last) if candidates.len() == duplicates::CANDIDATES_PAGE as usize => {
+                    after = Some(last.id.clone())
+                }
+                _ => break,
+            }
+        }
+
+        let checked = check.finish();
+        let mut results = Vec::with_capacity(checked.len());
+        let mut events = vec![];
+        let mut entries = vec![];
+        for result in checked {
+            let new_person = match result {
+                Ok(new_person) => new_person,
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
+                }
+            };
+
+            let person = insert_person(&mut tx, new_person).await?;
+            events.push(NewEvent::person(EventKind::PersonCreated, &person));
+            entries.push(audit.person_entry(AuditAction::Create, None, Some(&person)));
+            results.push(Ok(person));
+        }
+        record_events(&mut tx, events).await?;
+        record_audit(&mut tx, entries).await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(results)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn update_person(&self, person: Person, audit: &AuditContext) -> Result<Person, Error> {
+        let person_id = person.id.clone();
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let before = lock_person(&mut tx, &person_id).await?;
+        let name_key = duplicates::name_key(&person);
+        let person = match sqlx::query("UPDATE people SET FIRST_NAME=$1, LAST_NAME=$2, EMAIL=$5, PHONE=$6, BIRTH_DATE=$7, ADDRESSES=$8, NAME_KEY=$9, VERSION=VERSION+1 WHERE ID=$3 AND VERSION=$4 AND DELETED_AT IS NULL RETURNING *")
+            .bind(person.first_name)
+            .bind(person.last_name)
+            .bind(person.id.to_string())
+            .bind(person.version)
+            .bind(person.profile.email)
+            .bind(person.profile.phone)
+            .bind(person.profile.birth_date)
+            .bind(addresses_json(&person.profile.addresses))
+            .bind(name_key)
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(person) => person,
+            Err(sqlx::Error::RowNotFound) => {
+                // the check needs a connection of its own.
+                drop(tx);
+                return match self.person_exists(person_id).await? {
+                    // the person is there, so somebody else updated it first.
+                    true => Err(Error::PersonVersionConflict),
+                    false => Err(Error::PersonNotFound),
+                }
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        save_version(&mut tx, &person.id, Utc::now()).await?;
+        record_events(
+            &mut tx,
+            vec![NewEvent::person(EventKind::PersonUpdated, &person)],
+        )
+        .await?;
+        record_audit(
+            &mut tx,
+            vec![audit.person_entry(AuditAction::Update, before.as_ref(), Some(&person))],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(person)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn delete_person(
+        &self,
+        person_id: PersonID,
+        policy: DeletePolicy,
+        audit: &AuditContext,
+    ) -> Result<bool, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let before = lock_person(&mut tx, &person_id).await?;
+        let deleted_at = Utc::now();
+        let person = match sqlx::query(
+            "UPDATE people SET DELETED_AT = $2 WHERE ID = $1 AND DELETED_AT IS NULL RETURNING *",
+        )
+        .bind(person_id.to_string())
+        .bind(deleted_at)
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(Some(person)) => person,
+            Ok(None) => return Err(Error::PersonNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        save_version(&mut tx, &person_id, deleted_at).await?;
+
+        // the person row is written already, so no pet is added meanwhile.
+        let pets_query = match policy {
+            DeletePolicy::Refuse => "SELECT id, name, person_id FROM pets WHERE PERSON_ID = $1 ORDER BY ID",
+            DeletePolicy::Cascade => "DELETE FROM pets WHERE PERSON_ID = $1 RETURNING id, name, person_id",
+            DeletePolicy::Orphan => "UPDATE pets SET PERSON_ID = NULL WHERE PERSON_ID = $1 RETURNING id, name, person_id",
+        };
+        let pets = match sqlx::query(pets_query)
+            .bind(person_id.to_string())
+            .map(|row: PgRow| Pet {
+                id: PetID(row.get("id")),
+                name: row.get("name"),
+                person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+            })
+            .fetch_all(&mut *tx)
+            .await
+        {
+            Ok(pets) => pets,
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+
+        // dropping the transaction rolls the delete back.
+        if policy == DeletePolicy::Refuse && !pets.is_empty() {
+            return Err(Error::PersonHasPets(pets));
+        }
+
+        let pet_event = match policy {
+            DeletePolicy::Cascade => EventKind::PetDeleted,
+            _ => EventKind::PetUpdated,
+        };
+        let mut events: Vec<NewEvent> = pets
+            .iter()
+            .map(|pet| NewEvent::pet(pet_event, pet))
+            .collect();
+        events.push(NewEvent::person(EventKind::PersonDeleted, &person));
+        record_events(&mut tx, events).await?;
+
+        let mut entries = vec![audit.person_entry(AuditAction::Delete, before.as_ref(), None)];
+        entries.extend(pets.iter().map(|pet| match policy {
+            DeletePolicy::Orphan => {
+                let owned = Pet {
+                    person_id: Some(person_id.clone()),
+                    ..pet.clone()
+                };
+                audit.pet_entry(AuditAction::Update, Some(&owned), Some(pet))
+            }
+            _ => audit.pet_entry(AuditAction::Delete, Some(pet), None),
+        }));
+        record_audit(&mut tx, entries).await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                debug!(
+                    "person {} deleted, {:?} applied to {} pets",
+                    person_id,
+                    policy,
+                    pets.len()
+                );
+                Ok(true)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn restore_person(
+        &self,
+        person_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let before = lock_person(&mut tx, &person_id).await?;
+        let person = match sqlx::query(
+            "UPDATE people SET DELETED_AT = NULL WHERE ID = $1 AND DELETED_AT IS NOT NULL AND NOT EXISTS (SELECT 1 FROM merges WHERE SOURCE_ID = $1) RETURNING *",
+        )
+        .bind(person_id.to_string())
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(Some(person)) => person,
+            Ok(None) => {
+                // the person does not exist, is not deleted or was merged
+                // into someone else, who has their pets and relationships.
+                drop(tx);
+                self.get_person(person_id.clone(), true).await?;
+                return Err(match self.get_merge(person_id).await? {
+                    Some(merge) => Error::PersonMerged(merge.target_id),
+                    None => Error::PersonNotDeleted,
+                });
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        save_version(&mut tx, &person.id, Utc::now()).await?;
+        record_events(
+            &mut tx,
+            vec![NewEvent::person(EventKind::PersonUpdated, &person)],
+        )
+        .await?;
+        record_audit(
+            &mut tx,
+            vec![audit.person_entry(AuditAction::Restore, before.as_ref(), Some(&person))],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(person)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn merge_people(
+        &self,
+        person: Person,
+        source_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        // lock both people, so neither is deleted nor changed while they are merged.
+        let (target, source) = match sqlx::query(
+            "SELECT * FROM people WHERE ID IN ($1, $2) AND DELETED_AT IS NULL ORDER BY ID FOR UPDATE",
+        )
+        .bind(person.id.to_string())
+        .bind(source_id.to_string())
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(people) if people.len() == 2 => match people[0].id == person.id {
+                true => (people[0].clone(), people[1].clone()),
+                false => (people[1].clone(), people[0].clone()),
+            },
+            Ok(_) => return Err(Error::PersonNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+
+        let name_key = duplicates::name_key(&person);
+        let merged = match sqlx::query("UPDATE people SET FIRST_NAME=$1, LAST_NAME=$2, EMAIL=$5, PHONE=$6, BIRTH_DATE=$7, ADDRESSES=$8, NAME_KEY=$9, VERSION=VERSION+1 WHERE ID=$3 AND VERSION=$4 RETURNING *")
+            .bind(person.first_name)
+            .bind(person.last_name)
+            .bind(person.id.to_string())
+            .bind(person.version)
+            .bind(person.profile.email)
+            .bind(person.profile.phone)
+            .bind(person.profile.birth_date)
+            .bind(addresses_json(&person.profile.addresses))
+            .bind(name_key)
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(merged) => merged,
+            Err(sqlx::Error::RowNotFound) => return Err(Error::PersonVersionConflict),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+
+        // the relatives of the source become relatives of the survivor, unless
+        // they were related already, then the source is left without any.
+        let moved_pets = match sqlx::query(
+            "UPDATE pets SET PERSON_ID = $2 WHERE PERSON_ID = $1 RETURNING id, name, person_id",
+        )
+        .bind(source_id.to_string())
+        .bind(merged.id.to_string())
+        .map(|row: PgRow| Pet {
+            id: PetID(row.get("id")),
+            name: row.get("name"),
+            person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+        })
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(pets) => pets,
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+
+        let statements = [
+            "DELETE FROM relationships WHERE (PERSON_ID = $1 AND RELATED_ID = $2) OR (PERSON_ID = $2 AND RELATED_ID = $1)",
+            "INSERT INTO relationships (PERSON_ID, RELATED_ID, KIND) SELECT $2, RELATED_ID, KIND FROM relationships WHERE PERSON_ID = $1 ON CONFLICT DO NOTHING",
+            "INSERT INTO relationships (PERSON_ID, RELATED_ID, KIND) SELECT PERSON_ID, $2, KIND FROM relationships WHERE RELATED_ID = $1 ON CONFLICT DO NOTHING",
+            "DELETE FROM relationships WHERE PERSON_ID = $1 OR RELATED_ID = $1",
+            "UPDATE people SET DELETED_AT = $3 WHERE ID = $1",
+            "INSERT INTO merges (SOURCE_ID, TARGET_ID, MERGED_AT) VALUES ($1, $2, $3) ON CONFLICT (SOURCE_ID) DO UPDATE SET TARGET_ID = $2, MERGED_AT = $3",
+            "INSERT INTO person_history (ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, SAVED_AT) SELECT ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, $3 FROM people WHERE ID IN ($1, $2)",
+        ];
+        let merged_at = Utc::now();
+        for statement in statements {
+            let mut query = sqlx::query(statement)
+                .bind(source_id.to_string())
+                .bind(merged.id.to_string());
+            if statement.contains("$3") {
+                query = query.bind(merged_at);
+            }
+            if let Err(e) = query.execute(&mut *tx).await {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        }
+
+        let deleted_source = match sqlx::query("SELECT * FROM people WHERE ID = $1")
+            .bind(source_id.to_string())
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        let mut events = vec![NewEvent::person(EventKind::PersonUpdated, &merged)];
+        events.extend(
+            moved_pets
+                .iter()
+                .map(|pet| NewEvent::pet(EventKind::PetUpdated, pet)),
+        );
+        events.push(NewEvent::person(EventKind::PersonDeleted, &deleted_source));
+        record_events(&mut tx, events).await?;
+
+        let mut entries = vec![
+            audit.person_entry(AuditAction::Update, Some(&target), Some(&merged)),
+            audit.person_entry(AuditAction::Merge, Some(&source), None),
+        ];
+        entries.extend(moved_pets.iter().map(|pet| {
+            let owned = Pet {
+                person_id: Some(source_id.clone()),
+                ..pet.clone()
+            };
+            audit.pet_entry(AuditAction::Update, Some(&owned), Some(pet))
+        }));
+        record_audit(&mut tx, entries).await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                debug!("person {} merged into {}", source_id, merged.id);
+                Ok(merged)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_merge(&self, source_id: PersonID) -> Result<Option<Merge>, Error> {
+        match sqlx::query("SELECT source_id, target_id, merged_at FROM merges WHERE SOURCE_ID = $1")
+            .bind(source_id.to_string())
+            .map(|row: PgRow| Merge {
+                source_id: PersonID(row.get("source_id")),
+                target_id: PersonID(row.get("target_id")),
+                merged_at: row.get("merged_at"),
+            })
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(merge) => Ok(merge),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_person_history(&self, person_id: PersonID) -> Result<Vec<PersonVersion>, Error> {
+        match sqlx::query("SELECT * FROM person_history WHERE ID = $1 ORDER BY REVISION")
+            .bind(person_id.to_string())
+            .try_map(|row: PgRow| {
+                Ok(PersonVersion {
+                    saved_at: row.try_get("saved_at")?,
+                    person: person_from_row(&row)?,
+                })
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(versions) => Ok(versions),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_people_as_of(&self, at: DateTime<Utc>) -> Result<Vec<Person>, Error> {
+        match sqlx::query("SELECT DISTINCT ON (ID) * FROM person_history WHERE SAVED_AT <= $1 ORDER BY ID, REVISION DESC")
+            .bind(at)
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(people) => Ok(people),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn purge_people(
+        &self,
+        deleted_before: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        // pets would break the foreign key, so they go away with their owner.
+        let pets = match sqlx::query(
+            "DELETE FROM pets WHERE PERSON_ID IN (SELECT ID FROM people WHERE DELETED_AT < $1) RETURNING id, name, person_id",
+        )
+        .bind(deleted_before)
+        .map(|row: PgRow| Pet {
+            id: PetID(row.get("id")),
+            name: row.get("name"),
+            person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+        })
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(pets) => pets,
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        let events = pets
+            .iter()
+            .map(|pet| NewEvent::pet(EventKind::PetDeleted, pet))
+            .collect();
+        record_events(&mut tx, events).await?;
+
+        if let Err(e) = sqlx::query(
+            "DELETE FROM person_history WHERE ID IN (SELECT ID FROM people WHERE DELETED_AT < $1)",
+        )
+        .bind(deleted_before)
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            return Err(Error::DatabaseQueryError);
+        }
+
+        if let Err(e) = sqlx::query(
+            "DELETE FROM relationships WHERE PERSON_ID IN (SELECT ID FROM people WHERE DELETED_AT < $1) OR RELATED_ID IN (SELECT ID FROM people WHERE DELETED_AT < $1)",
+        )
+        .bind(deleted_before)
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            return Err(Error::DatabaseQueryError);
+        }
+
+        let purged = match sqlx::query("DELETE FROM people WHERE DELETED_AT < $1 RETURNING *")
+            .bind(deleted_before)
+            .try_map(|row: PgRow| person_from_row(&row))
+            .fetch_all(&mut *tx)
+            .await
+        {
+            Ok(mut purged) => {
+                purged.sort_by(|a, b| a.id.cmp(&b.id));
+                purged
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        let entries = purged
+            .iter()
+            .map(|person| audit.person_entry(AuditAction::Purge, Some(person), None))
+            .collect();
+        record_audit(&mut tx, entries).await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                debug!("{} people were purged", purged.len());
+                Ok(purged)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn add_pet(&self, new_pet: Pet, audit: &AuditContext) -> Result<Pet, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        // a soft deleted owner still satisfies the foreign key, so check it here.
+        let pet = match sqlx::query("INSERT INTO pets (ID, NAME, PERSON_ID) SELECT $1, $2, $3 WHERE EXISTS (SELECT 1 FROM people WHERE ID = $3 AND DELETED_AT IS NULL) RETURNING ID, NAME, PERSON_ID")
+            .bind(new_pet.id.to_string())
+            .bind(new_pet.name)
+            .bind(new_pet.person_id.as_ref().map(PersonID::to_string))
+            .map(|row: PgRow| Pet {
+                id: PetID(row.get("id")),
+                name: row.get("name"),
+                person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+            })
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(pet)) => pet,
+            Ok(None) => return Err(Error::PersonNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        record_events(&mut tx, vec![NewEvent::pet(EventKind::PetAdded, &pet)]).await?;
+        record_audit(
+            &mut tx,
+            vec![audit.pet_entry(AuditAction::Create, None, Some(&pet))],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(pet)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_pet(&self, pet_id: PetID) -> Result<Pet, Error> {
+        match sqlx::query("SELECT id, name, person_id FROM pets WHERE ID = $1")
+            .bind(pet_id.to_string())
+            .map(|row: PgRow| Pet {
+                id: PetID(row.get("id")),
+                name: row.get("name"),
+                person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+            })
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(pet)) => Ok(pet),
+            Ok(None) => Err(Error::PetNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_pets(&self, owners: &[PersonID]) -> Result<Vec<Pet>, Error> {
+        let owners: Vec<String> = owners.iter().map(|owner| owner.to_string()).collect();
+
+        match sqlx::query(
+            "SELECT ID, NAME, PERSON_ID FROM pets WHERE PERSON_ID = ANY($1) ORDER BY PERSON_ID, ID",
+        )
+        .bind(owners)
+        .map(|row: PgRow| Pet {
+            id: PetID(row.get("id")),
+            name: row.get("name"),
+            person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(pets) => Ok(pets),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn update_pet(&self, pet: Pet, audit: &AuditContext) -> Result<Pet, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let before =
+            match sqlx::query("SELECT id, name, person_id FROM pets WHERE ID = $1 FOR UPDATE")
+                .bind(pet.id.to_string())
+                .map(|row: PgRow| Pet {
+                    id: PetID(row.get("id")),
+                    name: row.get("name"),
+                    person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+                })
+                .fetch_optional(&mut *tx)
+                .await
+            {
+                Ok(Some(before)) => before,
+                Ok(None) => return Err(Error::PetNotFound),
+                Err(e) => {
+                    tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                    return Err(Error::DatabaseQueryError);
+                }
+            };
+
+        // a soft deleted owner still satisfies the foreign key, so check it here,
+        // an orphaned pet which stays without owner has none to check.
+        let pet = match sqlx::query("UPDATE pets SET NAME = $1, PERSON_ID = $2 WHERE ID = $3 AND (($2 IS NULL AND PERSON_ID IS NULL) OR EXISTS (SELECT 1 FROM people WHERE ID = $2 AND DELETED_AT IS NULL)) RETURNING id, name, person_id")
+            .bind(pet.name)
+            .bind(pet.person_id.as_ref().map(PersonID::to_string))
+            .bind(pet.id.to_string())
+            .map(|row: PgRow| Pet {
+                id: PetID(row.get("id")),
+                name: row.get("name"),
+                person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+            })
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(pet)) => pet,
+            // the pet is locked, so its new owner does not exist.
+            Ok(None) => return Err(Error::PersonNotFound),
+            // the owner was purged after the check.
+            Err(e) if e.as_database_error().is_some() && get_sql_code(&e) == FOREIGN_KEY_VIOLATION => {
+                return Err(Error::PersonNotFound);
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        record_events(&mut tx, vec![NewEvent::pet(EventKind::PetUpdated, &pet)]).await?;
+        record_audit(
+            &mut tx,
+            vec![audit.pet_entry(AuditAction::Update, Some(&before), Some(&pet))],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(pet)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn delete_pet(&self, pet_id: PetID, audit: &AuditContext) -> Result<bool, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+
+        let pet = match sqlx::query("DELETE FROM pets WHERE ID = $1 RETURNING id, name, person_id")
+            .bind(pet_id.to_string())
+            .map(|row: PgRow| Pet {
+                id: PetID(row.get("id")),
+                name: row.get("name"),
+                person_id: row.get::<Option<String>, _>("person_id").map(PersonID),
+            })
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(pet)) => pet,
+            Ok(None) => return Err(Error::PetNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        };
+        record_events(&mut tx, vec![NewEvent::pet(EventKind::PetDeleted, &pet)]).await?;
+        record_audit(
+            &mut tx,
+            vec![audit.pet_entry(AuditAction::Delete, Some(&pet), None)],
+        )
+        .await?;
+
+        match tx.commit().await {
+            Ok(_) => {
+                self.live_events.notify();
+                Ok(true)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn add_relationship(&self, relationship: Relationship) -> Result<Relationship, Error> {
+        let mut tx = self.connection.begin().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
 
-const DUPLICATE_KEY: i32 = 23505;
+        // lock both people, so neither is deleted before the relationship is saved.
+        match sqlx::query(
+            "SELECT ID FROM people WHERE ID IN ($1, $2) AND DELETED_AT IS NULL FOR UPDATE",
+        )
+        .bind(relationship.person_id.to_string())
+        .bind(relationship.related_id.to_string())
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(people) if people.len() == 2 => {}
+            Ok(_) => return Err(Error::PersonNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        }
 
-#[derive(Debug, Clone)]
-pub struct Store {
-    pub connection: PgPool,
-}
+        let reciprocal = relationship.reciprocal();
+        match sqlx::query("INSERT INTO relationships (PERSON_ID, RELATED_ID, KIND) VALUES ($1, $2, $3), ($2, $1, $4)")
+            .bind(relationship.person_id.to_string())
+            .bind(relationship.related_id.to_string())
+            .bind(relationship.kind.as_str())
+            .bind(reciprocal.kind.as_str())
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => {}
+            Err(e)
+                if e
+                    .as_database_error()
+                    .is_some_and(|db_err| db_err.is_unique_violation()) =>
+            {
+                return Err(Error::RelationshipExists);
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                return Err(Error::DatabaseQueryError);
+            }
+        }
 
-impl Store {
-    pub async fn new(db_url: &str) -> Self {
-        let db_pool = match PgPoolOptions::new()
-            .max_connections(5)
-            .connect(db_url)
+        match tx.commit().await {
+            Ok(_) => Ok(relationship),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_relationships(&self, people: &[PersonID]) -> Result<Vec<Relationship>, Error> {
+        let people: Vec<String> = people.iter().map(|person| person.to_string()).collect();
+
+        match sqlx::query("SELECT r.person_id, r.related_id, r.kind FROM relationships r JOIN people p ON p.ID = r.RELATED_ID WHERE r.PERSON_ID = ANY($1) AND p.DELETED_AT IS NULL ORDER BY r.PERSON_ID, r.RELATED_ID")
+            .bind(people)
+            .try_map(|row: PgRow| relationship_from_row(&row))
+            .fetch_all(&self.connection)
             .await
         {
-            Ok(pool) => pool,
-            Err(e) => panic!("couldn't establish DB connection! {e}"),
-        };
+            Ok(relationships) => Ok(relationships),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
 
-        Store {
-            connection: db_pool,
+    async fn delete_relationship(
+        &self,
+        person_id: PersonID,
+        related_id: PersonID,
+    ) -> Result<bool, Error> {
+        match sqlx::query("DELETE FROM relationships WHERE (PERSON_ID = $1 AND RELATED_ID = $2) OR (PERSON_ID = $2 AND RELATED_ID = $1)")
+            .bind(person_id.to_string())
+            .bind(related_id.to_string())
+            .execute(&self.connection)
+            .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Err(Error::RelationshipNotFound),
+            Ok(_) => Ok(true),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
         }
     }
-}
 
-#[async_trait]
-impl people_storage for Store {
-    async fn get_people(&self, limit: Option<i32>, offset: i32) -> Result<Vec<Person>, Error> {
-        match sqlx::query("SELECT * FROM people LIMIT $1 OFFSET $2")
-            .bind(limit)
-            .bind(offset)
-            .map(|row: PgRow| Person {
-                id: PersonID(row.get("id")),
-                first_name: row.get("first_name"),
-                last_name: row.get("last_name"),
-            })
+    async fn add_audit_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Error> {
+        let mut connection = self.connection.acquire().await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Error::DatabaseQueryError
+        })?;
+        record_audit(&mut connection, entries).await
+    }
+
+    async fn get_audit_entries(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, Error> {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("SELECT * FROM audit_log WHERE 1 = 1");
+        if let Some(entity_id) = &query.entity_id {
+            builder
+                .push(" AND ENTITY_ID = ")
+                .push_bind(entity_id.clone());
+        }
+        if let Some(actor) = &query.actor {
+            builder.push(" AND ACTOR = ").push_bind(actor.0.clone());
+        }
+        if let Some(since) = query.since {
+            builder.push(" AND AT >= ").push_bind(since);
+        }
+        if let Some(until) = query.until {
+            builder.push(" AND AT < ").push_bind(until);
+        }
+        builder
+            .push(" ORDER BY AT DESC, ID DESC LIMIT ")
+            .push_bind(query.limit);
+
+        match builder
+            .build()
+            .try_map(|row: PgRow| audit_entry_from_row(&row))
             .fetch_all(&self.connection)
             .await
         {
-            Ok(people) => {
-                debug!("found some people: {:?}", people);
-                Ok(people)
+            Ok(entries) => Ok(entries),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
             }
+        }
+    }
+
+    async fn get_pending_events(&self, limit: i32) -> Result<Vec<DomainEvent>, Error> {
+        match sqlx::query("SELECT * FROM outbox WHERE SENT_AT IS NULL ORDER BY ID LIMIT $1")
+            .bind(limit)
+            .try_map(|row: PgRow| event_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(events) => Ok(events),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
                 Err(Error::DatabaseQueryError)
@@ -61,23 +1274,18 @@ impl people_storage for Store {
         }
     }
 
-    async fn get_person(&self, person_id: PersonID) -> Result<Person, Error> {
-        match sqlx::query("SELECT * FROM people WHERE ID = $1")
-            .bind(person_id.to_string())
-            .map(|row: PgRow| Person {
-                id: PersonID(row.get("id")),
-                first_name: row.get("first_name"),
-                last_name: row.get("last_name"),
-            })
-            .fetch_one(&self.connection)
+    async fn mark_events_sent(&self, ids: &[i64]) -> Result<(), Error> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        match sqlx::query("UPDATE outbox SET SENT_AT = $1 WHERE ID = ANY($2)")
+            .bind(Utc::now())
+            .bind(ids)
+            .execute(&self.connection)
             .await
         {
-            Ok(person) => Ok(person),
-            Err(sqlx::Error::RowNotFound) => Ok(Person::new(
-                PersonID(String::from("")),
-                String::from(""),
-                String::from(""),
-            )),
+            Ok(_) => Ok(()),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
                 Err(Error::DatabaseQueryError)
@@ -85,25 +1293,52 @@ impl people_storage for Store {
         }
     }
 
-    async fn add_person(&self, new_person: Person) -> Result<Person, Error> {
-        debug!("adding person to postgres database: {:?}", new_person);
+    async fn get_events_after(&self, after: i64, limit: i32) -> Result<Vec<DomainEvent>, Error> {
+        match sqlx::query("SELECT * FROM outbox WHERE ID > $1 ORDER BY ID LIMIT $2")
+            .bind(after)
+            .bind(limit)
+            .try_map(|row: PgRow| event_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(events) => Ok(events),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
 
-        match sqlx::query("INSERT INTO people (ID, FIRST_NAME, LAST_NAME) VALUES ($1, $2, $3) RETURNING ID, FIRST_NAME, LAST_NAME")
-            .bind(new_person.id.to_string())
-            .bind(new_person.first_name)
-            .bind(new_person.last_name)
-            .map(|row: PgRow| Person {
-                id: PersonID(row.get("id")),
-                first_name: row.get("first_name"),
-                last_name: row.get("last_name"),
-            })
+    async fn get_last_event_id(&self) -> Result<i64, Error> {
+        match sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(ID) FROM outbox")
             .fetch_one(&self.connection)
             .await
         {
-            Ok(person) => {
-                debug!("person was added to postgres database: {:?}", person);
-                Ok(person)
+            Ok(id) => Ok(id.unwrap_or(0)),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
             }
+        }
+    }
+
+    fn live_events(&self) -> &LiveEvents {
+        &self.live_events
+    }
+
+    async fn add_webhook(&self, webhook: Webhook) -> Result<Webhook, Error> {
+        match sqlx::query(
+            "INSERT INTO webhooks (ID, URL, SECRET, EVENTS, CREATED_AT) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(webhook.id.to_string())
+        .bind(webhook.url.clone())
+        .bind(webhook.secret.clone())
+        .bind(events_json(&webhook.events))
+        .bind(webhook.created_at)
+        .execute(&self.connection)
+        .await
+        {
+            Ok(_) => Ok(webhook),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
                 Err(Error::DatabaseQueryError)
@@ -111,20 +1346,13 @@ impl people_storage for Store {
         }
     }
 
-    async fn update_person(&self, person: Person) -> Result<Person, Error> {
-        match sqlx::query("UPDATE people SET FIRST_NAME=$1, LAST_NAME=$2 WHERE ID=$3 RETURNING ID, FIRST_NAME, LAST_NAME")
-            .bind(person.first_name)
-            .bind(person.last_name)
-            .bind(person.id.to_string())
-            .map(|row: PgRow| Person {
-                id: PersonID(row.get("id")),
-                first_name: row.get("first_name"),
-                last_name: row.get("last_name"),
-            })
-            .fetch_one(&self.connection)
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        match sqlx::query("SELECT * FROM webhooks ORDER BY CREATED_AT, ID")
+            .try_map(|row: PgRow| webhook_from_row(&row))
+            .fetch_all(&self.connection)
             .await
         {
-            Ok(person) => Ok(person),
+            Ok(webhooks) => Ok(webhooks),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
                 Err(Error::DatabaseQueryError)
@@ -132,12 +1360,30 @@ impl people_storage for Store {
         }
     }
 
-    async fn delete_person(&self, person_id: PersonID) -> Result<bool, Error> {
-        match sqlx::query("DELETE FROM people WHERE id = $1")
-            .bind(person_id.to_string())
+    async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, Error> {
+        match sqlx::query("SELECT * FROM webhooks WHERE ID = $1")
+            .bind(webhook_id.to_string())
+            .try_map(|row: PgRow| webhook_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(webhook)) => Ok(webhook),
+            Ok(None) => Err(Error::WebhookNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, Error> {
+        // the deliveries of the webhook are deleted in cascade.
+        match sqlx::query("DELETE FROM webhooks WHERE ID = $1")
+            .bind(webhook_id.to_string())
             .execute(&self.connection)
             .await
         {
+            Ok(done) if done.rows_affected() == 0 => Err(Error::WebhookNotFound),
             Ok(_) => Ok(true),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
@@ -146,24 +1392,69 @@ impl people_storage for Store {
         }
     }
 
-    async fn add_pet(&self, new_pet: Pet) -> Result<Pet, Error> {
-        match sqlx::query("INSERT INTO pets (ID, NAME, PERSON_ID) VALUES ($1, $2, $3) RETURNING ID, NAME, PERSON_ID")
-            .bind(new_pet.id.to_string())
-            .bind(new_pet.name)
-            .bind(new_pet.person_id.to_string())
-            .map(|row: PgRow| Pet{
-                id: PetID(row.get("id")),
-                name: row.get("name"),
-                person_id: PersonID(row.get("PERSON_ID")),
-            })
-            .fetch_one(&self.connection)
-            .await{
-                Ok(pet) => Ok(pet),
-                Err(e) => {
-                    tracing::event!(tracing::Level::ERROR, "{:?}", e);
-                    Err(Error::DatabaseQueryError)
-                }
+    async fn add_webhook_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error> {
+        match sqlx::query("INSERT INTO webhook_deliveries (ID, WEBHOOK_ID, EVENT_ID, KIND, PAYLOAD, STATUS, RESPONSE_STATUS, ERROR, DELIVERED_AT) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)")
+            .bind(delivery.id)
+            .bind(delivery.webhook_id.to_string())
+            .bind(delivery.event_id)
+            .bind(delivery.kind.as_str())
+            .bind(delivery.payload.to_string())
+            .bind(delivery.status.as_str())
+            .bind(delivery.response_status.map(i32::from))
+            .bind(delivery.error)
+            .bind(delivery.delivered_at)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e
+                    .as_database_error()
+                    .is_some_and(|db_err| db_err.is_foreign_key_violation()) =>
+            {
+                Err(Error::WebhookNotFound)
+            }
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookID,
+        limit: i32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        match sqlx::query("SELECT * FROM webhook_deliveries WHERE WEBHOOK_ID = $1 ORDER BY DELIVERED_AT DESC, ID DESC LIMIT $2")
+            .bind(webhook_id.to_string())
+            .bind(limit)
+            .try_map(|row: PgRow| delivery_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(deliveries) => Ok(deliveries),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
+            }
+        }
+    }
+
+    async fn get_webhook_delivery(&self, delivery_id: String) -> Result<WebhookDelivery, Error> {
+        match sqlx::query("SELECT * FROM webhook_deliveries WHERE ID = $1")
+            .bind(delivery_id)
+            .try_map(|row: PgRow| delivery_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(delivery)) => Ok(delivery),
+            Ok(None) => Err(Error::WebhookDeliveryNotFound),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError)
             }
+        }
     }
 }
 
@@ -247,3 +1538,407 @@ fn get_sql_code(err: &sqlx::error::Error) -> i32 {
 //         Store::new()
 //     }
 // }
+
+fn push_sort_value(builder: &mut QueryBuilder<Postgres>, value: SortValue) {
+    match value {
+        SortValue::Text(text) => builder.push_bind(text),
+        SortValue::Int(number) => builder.push_bind(number),
+        SortValue::Time(time) => builder.push_bind(time),
+    };
+}
+
+/// insert_person saves the new person and starts their history.
+async fn insert_person(tx: &mut PgConnection, new_person: Person) -> Result<Person, Error> {
+    let name_key = duplicates::name_key(&new_person);
+    let person = match sqlx::query("INSERT INTO people (ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, NAME_KEY) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *")
+        .bind(new_person.id.to_string())
+        .bind(new_person.first_name)
+        .bind(new_person.last_name)
+        .bind(new_person.created_on)
+        .bind(new_person.version)
+        .bind(new_person.profile.email)
+        .bind(new_person.profile.phone)
+        .bind(new_person.profile.birth_date)
+        .bind(addresses_json(&new_person.profile.addresses))
+        .bind(name_key)
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+    {
+        Ok(person) => person,
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            return Err(Error::DatabaseQueryError);
+        }
+    };
+    save_version(tx, &person.id, Utc::now()).await?;
+
+    Ok(person)
+}
+
+/// lock_duplicates makes the transactions looking for duplicates wait for the
+/// ones which could save a duplicate of their people until they end. The lock
+/// keys are locked in order so two transactions never wait on each other, only
+/// when everybody is a duplicate does every other check wait.
+async fn lock_duplicates(
+    tx: &mut PgConnection,
+    shortlist: &Shortlist,
+    lock_keys: &[String],
+) -> Result<(), Error> {
+    let lock = match shortlist {
+        Shortlist::Everybody => "SELECT pg_advisory_xact_lock($1)",
+        Shortlist::NameKeys(_) | Shortlist::Similar { .. } => {
+            "SELECT pg_advisory_xact_lock_shared($1)"
+        }
+    };
+    if let Err(e) = sqlx::query(lock)
+        .bind(DUPLICATES_LOCK)
+        .execute(&mut *tx)
+        .await
+    {
+        tracing::event!(tracing::Level::ERROR, "{:?}", e);
+        return Err(Error::DatabaseQueryError);
+    }
+
+    if *shortlist == Shortlist::Everybody {
+        return Ok(());
+    }
+    match sqlx::query("SELECT pg_advisory_xact_lock(key_lock) FROM (SELECT DISTINCT hashtext(lock_key)::BIGINT AS key_lock FROM UNNEST($1::TEXT[]) AS lock_keys (lock_key) ORDER BY key_lock) AS key_locks")
+        .bind(lock_keys)
+        .execute(tx)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// duplicate_candidates returns, ordered by id, the next page of the people
+/// who are not deleted and on the shortlist, starting right after the given
+/// person. Similar names are the ones pg_trgm finds similar with its default
+/// threshold, using the trigram index of the full names.
+async fn duplicate_candidates(
+    tx: &mut PgConnection,
+    shortlist: &Shortlist,
+    after: Option<&PersonID>,
+) -> Result<Vec<Person>, Error> {
+    let mut builder: QueryBuilder<Postgres> =
+        QueryBuilder::new("SELECT * FROM people WHERE DELETED_AT IS NULL");
+    match shortlist {
+        Shortlist::Everybody => {}
+        Shortlist::NameKeys(name_keys) | Shortlist::Similar { name_keys, .. }
+            if name_keys.is_empty() =>
+        {
+            return Ok(vec![]);
+        }
+        Shortlist::NameKeys(name_keys) => {
+            builder.push(" AND NAME_KEY IN (");
+            let mut separated = builder.separated(", ");
+            for name_key in name_keys {
+                separated.push_bind(name_key.clone());
+            }
+            builder.push(")");
+        }
+        Shortlist::Similar { name_keys, names } => {
+            builder.push(" AND (NAME_KEY IN (");
+            let mut separated = builder.separated(", ");
+            for name_key in name_keys {
+                separated.push_bind(name_key.clone());
+            }
+            builder.push(")");
+            for name in names {
+                builder
+                    .push(" OR (first_name || ' ' || last_name) % ")
+                    .push_bind(name.clone());
+            }
+            builder.push(")");
+        }
+    }
+    if let Some(after) = after {
+        builder.push(" AND ID > ").push_bind(after.to_string());
+    }
+    builder
+        .push(" ORDER BY ID LIMIT ")
+        .push_bind(duplicates::CANDIDATES_PAGE);
+
+    match builder
+        .build()
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_all(tx)
+        .await
+    {
+        Ok(people) => Ok(people),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// backfill_name_keys gives a name key to the people saved before there was
+/// one.
+async fn backfill_name_keys(pool: &PgPool) -> Result<(), Error> {
+    let people = match sqlx::query("SELECT * FROM people WHERE NAME_KEY IS NULL")
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_all(pool)
+        .await
+    {
+        Ok(people) => people,
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            return Err(Error::DatabaseQueryError);
+        }
+    };
+
+    for person in people {
+        if let Err(e) = sqlx::query("UPDATE people SET NAME_KEY = $1 WHERE ID = $2")
+            .bind(duplicates::name_key(&person))
+            .bind(person.id.to_string())
+            .execute(pool)
+            .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            return Err(Error::DatabaseQueryError);
+        }
+    }
+
+    Ok(())
+}
+
+/// save_version keeps the person, as they are now, in their history.
+async fn save_version(
+    tx: &mut PgConnection,
+    person_id: &PersonID,
+    saved_at: DateTime<Utc>,
+) -> Result<(), Error> {
+    match sqlx::query("INSERT INTO person_history (ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, SAVED_AT) SELECT ID, FIRST_NAME, LAST_NAME, CREATED_ON, VERSION, DELETED_AT, EMAIL, PHONE, BIRTH_DATE, ADDRESSES, $2 FROM people WHERE ID = $1")
+        .bind(person_id.to_string())
+        .bind(saved_at)
+        .execute(tx)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// record_events writes the events to the outbox, in the transaction of the
+/// change they are about.
+async fn record_events(tx: &mut PgConnection, events: Vec<NewEvent>) -> Result<(), Error> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    // the ids are taken at insert, so the transactions writing events commit
+    // one at a time and the ids are never seen out of order.
+    if let Err(e) = sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(OUTBOX_LOCK)
+        .execute(&mut *tx)
+        .await
+    {
+        tracing::event!(tracing::Level::ERROR, "{:?}", e);
+        return Err(Error::DatabaseQueryError);
+    }
+
+    let mut builder: QueryBuilder<Postgres> =
+        QueryBuilder::new("INSERT INTO outbox (KIND, AGGREGATE_ID, PAYLOAD, OCCURRED_AT) ");
+    builder.push_values(events, |mut values, event| {
+        values
+            .push_bind(event.kind.as_str())
+            .push_bind(event.aggregate_id)
+            .push_bind(event.payload.to_string())
+            .push_bind(event.occurred_at);
+    });
+
+    match builder.build().execute(tx).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// record_audit appends the audit entries of a change to the audit log.
+async fn record_audit(tx: &mut PgConnection, entries: Vec<AuditEntry>) -> Result<(), Error> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+        "INSERT INTO audit_log (ID, ENTITY, ENTITY_ID, ACTION, ACTOR, REQUEST_ID, AT, BEFORE, AFTER) ",
+    );
+    builder.push_values(entries, |mut values, entry| {
+        values
+            .push_bind(entry.id)
+            .push_bind(entry.entity.as_str())
+            .push_bind(entry.entity_id)
+            .push_bind(entry.action.as_str())
+            .push_bind(entry.actor.map(|actor| actor.0))
+            .push_bind(entry.request_id)
+            .push_bind(entry.at)
+            .push_bind(entry.before.map(|before| before.to_string()))
+            .push_bind(entry.after.map(|after| after.to_string()));
+    });
+
+    match builder.build().execute(tx).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// lock_person reads the person and locks them until the transaction ends,
+/// so they are audited as they were right before the change.
+async fn lock_person(tx: &mut PgConnection, person_id: &PersonID) -> Result<Option<Person>, Error> {
+    match sqlx::query("SELECT * FROM people WHERE ID = $1 FOR UPDATE")
+        .bind(person_id.to_string())
+        .try_map(|row: PgRow| person_from_row(&row))
+        .fetch_optional(tx)
+        .await
+    {
+        Ok(person) => Ok(person),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            Err(Error::DatabaseQueryError)
+        }
+    }
+}
+
+/// person_from_row reads a person from a row with every column of people.
+fn person_from_row(row: &PgRow) -> Result<Person, sqlx::Error> {
+    let addresses: String = row.try_get("addresses")?;
+    Ok(Person {
+        id: PersonID(row.try_get("id")?),
+        first_name: row.try_get("first_name")?,
+        last_name: row.try_get("last_name")?,
+        created_on: row.try_get("created_on")?,
+        version: row.try_get("version")?,
+        deleted_at: row.try_get("deleted_at")?,
+        profile: Profile {
+            email: row.try_get("email")?,
+            phone: row.try_get("phone")?,
+            birth_date: row.try_get("birth_date")?,
+            addresses: serde_json::from_str(&addresses)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        },
+    })
+}
+
+/// addresses_json is how the addresses of a person are saved in its column.
+fn addresses_json(addresses: &[Address]) -> String {
+    serde_json::to_string(addresses).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// relationship_from_row reads a relationship from a row with the person,
+/// related person and kind columns.
+fn relationship_from_row(row: &PgRow) -> Result<Relationship, sqlx::Error> {
+    let kind: String = row.try_get("kind")?;
+    Ok(Relationship {
+        person_id: PersonID(row.try_get("person_id")?),
+        related_id: PersonID(row.try_get("related_id")?),
+        kind: kind
+            .parse()
+            .map_err(|e: Error| sqlx::Error::Decode(e.to_string().into()))?,
+    })
+}
+
+fn audit_entry_from_row(row: &PgRow) -> Result<AuditEntry, sqlx::Error> {
+    let decode = |e: Error| sqlx::Error::Decode(e.to_string().into());
+    let snapshot = |index: &str| -> Result<Option<serde_json::Value>, sqlx::Error> {
+        row.try_get::<Option<String>, _>(index)?
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|e| sqlx::Error::ColumnDecode {
+                index: index.to_string(),
+                source: Box::new(e),
+            })
+    };
+
+    Ok(AuditEntry {
+        id: row.try_get("id")?,
+        entity: row
+            .try_get::<String, _>("entity")?
+            .parse()
+            .map_err(decode)?,
+        entity_id: row.try_get("entity_id")?,
+        action: row
+            .try_get::<String, _>("action")?
+            .parse()
+            .map_err(decode)?,
+        actor: row.try_get::<Option<String>, _>("actor")?.map(AccountID),
+        request_id: row.try_get("request_id")?,
+        at: row.try_get("at")?,
+        before: snapshot("before")?,
+        after: snapshot("after")?,
+    })
+}
+
+fn event_from_row(row: &PgRow) -> Result<DomainEvent, sqlx::Error> {
+    let payload: String = row.try_get("payload")?;
+    Ok(DomainEvent {
+        id: row.try_get("id")?,
+        kind: row
+            .try_get::<String, _>("kind")?
+            .parse()
+            .map_err(|e: Error| sqlx::Error::Decode(e.to_string().into()))?,
+        aggregate_id: row.try_get("aggregate_id")?,
+        payload: serde_json::from_str(&payload).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "payload".to_string(),
+            source: Box::new(e),
+        })?,
+        occurred_at: row.try_get("occurred_at")?,
+        sent_at: row.try_get("sent_at")?,
+    })
+}
+
+/// events_json is how the kinds of events of a webhook are saved in its column.
+fn events_json(events: &[EventKind]) -> String {
+    serde_json::to_string(events).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn webhook_from_row(row: &PgRow) -> Result<Webhook, sqlx::Error> {
+    let events: String = row.try_get("events")?;
+    Ok(Webhook {
+        id: WebhookID(row.try_get("id")?),
+        url: row.try_get("url")?,
+        secret: row.try_get("secret")?,
+        events: serde_json::from_str(&events).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "events".to_string(),
+            source: Box::new(e),
+        })?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn delivery_from_row(row: &PgRow) -> Result<WebhookDelivery, sqlx::Error> {
+    let decode = |e: Error| sqlx::Error::Decode(e.to_string().into());
+    let payload: String = row.try_get("payload")?;
+    Ok(WebhookDelivery {
+        id: row.try_get("id")?,
+        webhook_id: WebhookID(row.try_get("webhook_id")?),
+        event_id: row.try_get("event_id")?,
+        kind: row.try_get::<String, _>("kind")?.parse().map_err(decode)?,
+        payload: serde_json::from_str(&payload).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "payload".to_string(),
+            source: Box::new(e),
+        })?,
+        status: row
+            .try_get::<String, _>("status")?
+            .parse()
+            .map_err(decode)?,
+        response_status: row
+            .try_get::<Option<i32>, _>("response_status")?
+            .and_then(|status| u16::try_from(status).ok()),
+        error: row.try_get("error")?,
+        delivered_at: row.try_get("delivered_at")?,
+    })
+}
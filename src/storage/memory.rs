@@ -1,26 +1,245 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
 
+use crate::errors::error::Error;
+use crate::people::duplicates::{self, DuplicateCheck, DuplicateDetection, Shortlist};
+use crate::people::live::LiveEvents;
+use crate::people::storage::{PeopleStream, Storer as people_storage};
+use crate::users::storage::Storer as users_storage;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::{debug, error};
+
+use crate::storage::search;
+use crate::storage::stream;
+use crate::storage::wal::{Mutation, Snapshot, Wal};
 use crate::types::{
-    people::{Person, PersonID},
-    pets::{Pet, PetID},
+    accounts::{Account, AccountID},
+    audit::{AuditAction, AuditContext, AuditEntry, AuditQuery},
+    events::{DomainEvent, EventKind, NewEvent},
+    history::PersonVersion,
+    merges::Merge,
+    pagination::Cursor,
+    people::{Person, PersonID, PersonMatch, PersonWithPets},
+    pets::{DeletePolicy, Pet, PetID},
+    query::PeopleQuery,
+    relationships::Relationship,
+    webhooks::{Webhook, WebhookDelivery, WebhookID},
 };
 
 #[derive(Debug, Clone)]
 pub struct Store {
     pub people: Arc<RwLock<HashMap<PersonID, Person>>>,
     pub pets: Arc<RwLock<HashMap<PetID, Pet>>>,
+    pub relationships: Arc<RwLock<HashMap<PersonID, Vec<Relationship>>>>,
+    pub merges: Arc<RwLock<HashMap<PersonID, Merge>>>,
+    pub audit: Arc<RwLock<Vec<AuditEntry>>>,
+    pub accounts: Arc<RwLock<HashMap<AccountID, Account>>>,
+    /// The versions of every person, oldest first
+    pub history: Arc<RwLock<HashMap<PersonID, Vec<PersonVersion>>>>,
+    /// The domain events of every change, oldest first
+    pub outbox: Arc<RwLock<Vec<DomainEvent>>>,
+    pub webhooks: Arc<RwLock<HashMap<WebhookID, Webhook>>>,
+    /// The deliveries of every webhook, oldest first
+    pub webhook_deliveries: Arc<RwLock<Vec<WebhookDelivery>>>,
+    wal: Option<Arc<Mutex<Wal>>>,
+    live_events: LiveEvents,
 }
 
 impl Store {
     pub fn new() -> Self {
+        let people = Self::init();
+        let mut history = HashMap::new();
+        Self::start_history(&people, &mut history);
+
         Store {
-            people: Arc::new(RwLock::new(Self::init())),
+            people: Arc::new(RwLock::new(people)),
             pets: Arc::new(RwLock::new(HashMap::new())),
+            relationships: Arc::new(RwLock::new(HashMap::new())),
+            merges: Arc::new(RwLock::new(HashMap::new())),
+            audit: Arc::new(RwLock::new(Vec::new())),
+            accounts: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(history)),
+            outbox: Arc::new(RwLock::new(Vec::new())),
+            webhooks: Arc::new(RwLock::new(HashMap::new())),
+            webhook_deliveries: Arc::new(RwLock::new(Vec::new())),
+            wal: None,
+            live_events: LiveEvents::new(),
         }
     }
 
+    /// open creates a durable store backed by the files in the given directory,
+    /// every mutation is appended to a write-ahead log before it is applied.
+    pub async fn open(data_dir: &Path) -> Result<Self, Error> {
+        let (wal, mut snapshot) = Wal::open(data_dir).await?;
+        debug!(
+            "memory store recovered {} people, {} pets, {} relationships, {} merges, {} audit entries and {} accounts",
+            snapshot.people.len(),
+            snapshot.pets.len(),
+            snapshot.relationships.values().map(Vec::len).sum::<usize>(),
+            snapshot.merges.len(),
+            snapshot.audit.len(),
+            snapshot.accounts.len()
+        );
+        Self::start_history(&snapshot.people, &mut snapshot.history);
+
+        Ok(Store {
+            people: Arc::new(RwLock::new(snapshot.people)),
+            pets: Arc::new(RwLock::new(snapshot.pets)),
+            relationships: Arc::new(RwLock::new(snapshot.relationships)),
+            merges: Arc::new(RwLock::new(snapshot.merges)),
+            audit: Arc::new(RwLock::new(snapshot.audit)),
+            accounts: Arc::new(RwLock::new(snapshot.accounts)),
+            history: Arc::new(RwLock::new(snapshot.history)),
+            outbox: Arc::new(RwLock::new(snapshot.outbox)),
+            webhooks: Arc::new(RwLock::new(snapshot.webhooks)),
+            webhook_deliveries: Arc::new(RwLock::new(snapshot.webhook_deliveries)),
+            wal: Some(Arc::new(Mutex::new(wal))),
+            live_events: LiveEvents::new(),
+        })
+    }
+
+    /// snapshot writes the current data to disk and empties the write-ahead log.
+    pub async fn snapshot(&self) -> Result<(), Error> {
+        let wal = match &self.wal {
+            Some(wal) => wal,
+            None => return Ok(()),
+        };
+
+        // take the locks in the same order the mutations do.
+        let people = self.people.read().await;
+        let pets = self.pets.read().await;
+        let relationships = self.relationships.read().await;
+        let merges = self.merges.read().await;
+        let audit = self.audit.read().await;
+        let accounts = self.accounts.read().await;
+        let history = self.history.read().await;
+        let outbox = self.outbox.read().await;
+        let webhooks = self.webhooks.read().await;
+        let webhook_deliveries = self.webhook_deliveries.read().await;
+        let snapshot = Snapshot {
+            people: people.clone(),
+            pets: pets.clone(),
+            relationships: relationships.clone(),
+            merges: merges.clone(),
+            audit: audit.clone(),
+            accounts: accounts.clone(),
+            history: history.clone(),
+            outbox: outbox.clone(),
+            webhooks: webhooks.clone(),
+            webhook_deliveries: webhook_deliveries.clone(),
+        };
+
+        wal.lock().await.compact(&snapshot).await
+    }
+
+    /// spawn_snapshots takes a snapshot of the store every given period.
+    pub fn spawn_snapshots(&self, period: Duration) {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            // the first tick completes immediately and the store was just compacted.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.snapshot().await {
+                    error!("taking memory store snapshot: {:?}", e);
+                }
+            }
+        });
+    }
+
+    /// write_ahead writes the mutations of one change to the write-ahead log
+    /// as a single record.
+    async fn write_ahead(&self, mutations: &[Mutation]) -> Result<(), Error> {
+        match &self.wal {
+            Some(wal) => wal.lock().await.append(mutations).await,
+            None => Ok(()),
+        }
+    }
+
+    /// commit writes the change to the write-ahead log and then adds its
+    /// versions to the history, its events to the outbox, numbered after the
+    /// last one, and its entries to the audit log. The caller applies the rest
+    /// of the change once it is committed, so a failed write changes nothing.
+    async fn commit(&self, change: Change) -> Result<(), Error> {
+        // take the locks in the same order the snapshots do.
+        let mut audit = self.audit.write().await;
+        let mut history = self.history.write().await;
+        let mut outbox = self.outbox.write().await;
+
+        let first_id = outbox.last().map_or(1, |last| last.id + 1);
+        let any_events = !change.events.is_empty();
+        let mut mutations = change.mutations;
+        mutations.extend(
+            change
+                .events
+                .into_iter()
+                .zip(first_id..)
+                .map(|(event, id)| Mutation::EventRecorded(event.numbered(id))),
+        );
+        self.write_ahead(&mutations).await?;
+
+        for mutation in mutations {
+            match mutation {
+                Mutation::PersonVersionSaved(version) => history
+                    .entry(version.person.id.clone())
+                    .or_default()
+                    .push(version),
+                Mutation::EventRecorded(event) => outbox.push(event),
+                Mutation::AuditEntryAdded(entry) => audit.push(entry),
+                _ => {}
+            }
+        }
+        if any_events {
+            self.live_events.notify();
+        }
+        Ok(())
+    }
+
+    /// start_history gives the people saved before there was a history a
+    /// first version, as they are now, from the moment they were added.
+    fn start_history(
+        people: &HashMap<PersonID, Person>,
+        history: &mut HashMap<PersonID, Vec<PersonVersion>>,
+    ) {
+        for person in people.values() {
+            history.entry(person.id.clone()).or_insert_with(|| {
+                vec![PersonVersion {
+                    saved_at: person.created_on,
+                    person: person.clone(),
+                }]
+            });
+        }
+    }
+
+    /// owner_exists tells whether the pet has an owner who is not deleted.
+    fn owner_exists(people: &HashMap<PersonID, Person>, pet: &Pet) -> bool {
+        let owner_exists = pet
+            .person_id
+            .as_ref()
+            .and_then(|owner| people.get(owner))
+            .is_some_and(|person| person.deleted_at.is_none());
+        if !owner_exists {
+            tracing::event!(
+                tracing::Level::ERROR,
+                "pet owner {:?} does not exist",
+                pet.person_id
+            );
+        }
+        owner_exists
+    }
+
+    /// person_exists tells whether the person exists and is not deleted.
+    fn person_exists(people: &HashMap<PersonID, Person>, person_id: &PersonID) -> bool {
+        people
+            .get(person_id)
+            .is_some_and(|person| person.deleted_at.is_none())
+    }
+
     fn init() -> HashMap<PersonID, Person> {
         let file = include_str!("../../people.json");
         serde_json::from_str(file).expect("can't read people.json")
@@ -32,3 +251,925 @@ impl Default for Store {
         Store::new()
     }
 }
+
+/// Change gathers the mutations of one store operation, they are written to
+/// the write-ahead log as one record so a crash never leaves half of them.
+#[derive(Debug, Default)]
+struct Change {
+    mutations: Vec<Mutation>,
+    events: Vec<NewEvent>,
+}
+
+impl Change {
+    fn push(&mut self, mutation: Mutation) {
+        self.mutations.push(mutation);
+    }
+
+    /// save_person saves the person and keeps them, as they are now, in their
+    /// history.
+    fn save_person(&mut self, person: &Person) {
+        self.push(Mutation::PersonSaved(person.clone()));
+        self.push(Mutation::PersonVersionSaved(PersonVersion::new(
+            person.clone(),
+        )));
+    }
+
+    fn record_events(&mut self, events: impl IntoIterator<Item = NewEvent>) {
+        self.events.extend(events);
+    }
+
+    fn record_audit(&mut self, entries: impl IntoIterator<Item = AuditEntry>) {
+        self.mutations
+            .extend(entries.into_iter().map(Mutation::AuditEntryAdded));
+    }
+}
+
+#[async_trait]
+impl people_storage for Store {
+    async fn get_people(
+        &self,
+        query: &PeopleQuery,
+        limit: i32,
+        after: Option<Cursor>,
+    ) -> Result<Vec<Person>, Error> {
+        let people = query.select(self.people.read().await.values(), limit, after);
+
+        debug!("found some people: {:?}", people);
+        Ok(people)
+    }
+
+    async fn get_person(
+        &self,
+        person_id: PersonID,
+        include_deleted: bool,
+    ) -> Result<Person, Error> {
+        match self.people.read().await.get(&person_id) {
+            Some(person) if include_deleted || person.deleted_at.is_none() => Ok(person.clone()),
+            _ => Err(Error::PersonNotFound),
+        }
+    }
+
+    async fn stream_people(&self, include_pets: bool) -> Result<PeopleStream, Error> {
+        // everyone is in memory already, so a copy of them is streamed and
+        // the locks are not held while the client reads.
+        let mut people: Vec<Person> = self
+            .people
+            .read()
+            .await
+            .values()
+            .filter(|person| person.deleted_at.is_none())
+            .cloned()
+            .collect();
+        people.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut pets: HashMap<PersonID, Vec<Pet>> = HashMap::new();
+        if include_pets {
+            for pet in self.pets.read().await.values() {
+                if let Some(owner) = &pet.person_id {
+                    pets.entry(owner.clone()).or_default().push(pet.clone());
+                }
+            }
+            pets.values_mut()
+                .for_each(|owned| owned.sort_by(|a, b| a.id.0.cmp(&b.id.0)));
+        }
+
+        Ok(stream::people_stream(move |tx| async move {
+            for person in people {
+                let pets = include_pets.then(|| pets.remove(&person.id).unwrap_or_default());
+                if tx.send(Ok(PersonWithPets { person, pets })).await.is_err() {
+                    return;
+                }
+            }
+        }))
+    }
+
+    async fn search_people(&self, terms: &str, limit: i32) -> Result<Vec<PersonMatch>, Error> {
+        Ok(search::rank(
+            self.people.read().await.values(),
+            terms,
+            limit,
+        ))
+    }
+
+    async fn add_person(&self, new_person: Person, audit: &AuditContext) -> Result<Person, Error> {
+        debug!("adding person to memory database: {:?}", new_person);
+
+        let mut people = self.people.write().await;
+        if people.contains_key(&new_person.id) {
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        let mut change = Change::default();
+        change.save_person(&new_person);
+        change.record_events([NewEvent::person(EventKind::PersonCreated, &new_person)]);
+        change.record_audit([audit.person_entry(AuditAction::Create, None, Some(&new_person))]);
+        self.commit(change).await?;
+        people.insert(new_person.id.clone(), new_person.clone());
+
+        Ok(new_person)
+    }
+
+    async fn add_people(
+        &self,
+        new_people: Vec<Person>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error> {
+        debug!("adding {} people to memory database", new_people.len());
+
+        let mut people = self.people.write().await;
+        let mut new_ids = std::collections::HashSet::new();
+        let any_duplicate = new_people
+            .iter()
+            .any(|person| people.contains_key(&person.id) || !new_ids.insert(&person.id));
+        if any_duplicate {
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        let mut change = Change::default();
+        for new_person in new_people.iter() {
+            change.save_person(new_person);
+            change.record_events([NewEvent::person(EventKind::PersonCreated, new_person)]);
+            change.record_audit([audit.person_entry(AuditAction::Create, None, Some(new_person))]);
+        }
+        self.commit(change).await?;
+        for new_person in new_people.iter() {
+            people.insert(new_person.id.clone(), new_person.clone());
+        }
+
+        Ok(new_people)
+    }
+
+    async fn add_people_unless_duplicates(
+        &self,
+        new_people: Vec<Person>,
+        detection: &DuplicateDetection,
+        audit: &AuditContext,
+    ) -> Result<Vec<Result<Person, Error>>, Error> {
+        debug!(
+            "adding {} people unless duplicates to memory database",
+            new_people.len()
+        );
+
+        // the people are locked from the check to the insert, so nobody with
+        // the same name can be added in between.
+        let mut people = self.people.write().await;
+        let mut new_ids = std::collections::HashSet::new();
+        let any_duplicate = new_people
+            .iter()
+            .any(|person| people.contains_key(&person.id) || !new_ids.insert(&person.id));
+        if any_duplicate {
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        let mut check = DuplicateCheck::new(detection, new_people);
+        // similar names cannot be told apart without trigrams, so everybody
+        // is scored unless only the exact names are duplicates.
+        let name_keys = match check.shortlist() {
+            Shortlist::NameKeys(name_keys) => Some(name_keys),
+            Shortlist::Everybody | Shortlist::Similar { .. } => None,
+        };
+        check.score(
+            people
+                .values()
+                .filter(|person| person.deleted_at.is_none())
+                .filter(|person| match &name_keys {
+                    Some(keys) => keys.contains(&duplicates::name_key(person)),
+                    None => true,
+                }),
+        );
+
+        let results = check.finish();
+        let mut change = Change::default();
+        for new_person in results.iter().flatten() {
+            change.save_person(new_person);
+            change.record_events([NewEvent::person(EventKind::PersonCreated, new_person)]);
+            change.record_audit([audit.person_entry(AuditAction::Create, None, Some(new_person))]);
+        }
+        self.commit(change).await?;
+        for new_person in results.iter().flatten() {
+            people.insert(new_person.id.clone(), new_person.clone());
+        }
+
+        Ok(results)
+    }
+
+    async fn update_person(&self, person: Person, audit: &AuditContext) -> Result<Person, Error> {
+        match self.people.write().await.get_mut(&person.id) {
+            Some(stored_person) if stored_person.deleted_at.is_some() => Err(Error::PersonNotFound),
+            Some(stored_person) if stored_person.version != person.version => {
+                Err(Error::PersonVersionConflict)
+            }
+            Some(stored_person) => {
+                let mut person = person;
+                person.version += 1;
+                person.created_on = stored_person.created_on;
+                person.deleted_at = None;
+                let mut change = Change::default();
+                change.save_person(&person);
+                change.record_events([NewEvent::person(EventKind::PersonUpdated, &person)]);
+                change.record_audit([audit.person_entry(
+                    AuditAction::Update,
+                    Some(stored_person),
+                    Some(&person),
+                )]);
+                self.commit(change).await?;
+                *stored_person = person.clone();
+                Ok(person)
+            }
+            None => Err(Error::PersonNotFound),
+        }
+    }
+
+    async fn delete_person(
+        &self,
+        person_id: PersonID,
+        policy: DeletePolicy,
+        audit: &AuditContext,
+    ) -> Result<bool, Error> {
+        let mut people = self.people.write().await;
+        let mut pets = self.pets.write().await;
+        let stored_person = match people.get_mut(&person_id) {
+            Some(stored_person) if stored_person.deleted_at.is_none() => stored_person,
+            _ => return Err(Error::PersonNotFound),
+        };
+
+        let mut owned: Vec<Pet> = pets
+            .values()
+            .filter(|pet| pet.person_id.as_ref() == Some(&person_id))
+            .cloned()
+            .collect();
+        owned.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut change = Change::default();
+        let mut events = Vec::with_capacity(owned.len() + 1);
+        let mut entries = Vec::with_capacity(owned.len() + 1);
+        let mut deleted_pets = Vec::with_capacity(owned.len());
+        let mut saved_pets = Vec::with_capacity(owned.len());
+        match policy {
+            DeletePolicy::Refuse if !owned.is_empty() => return Err(Error::PersonHasPets(owned)),
+            DeletePolicy::Refuse => {}
+            DeletePolicy::Cascade => {
+                for pet in owned {
+                    change.push(Mutation::PetDeleted(pet.id.clone()));
+                    events.push(NewEvent::pet(EventKind::PetDeleted, &pet));
+                    entries.push(audit.pet_entry(AuditAction::Delete, Some(&pet), None));
+                    deleted_pets.push(pet.id);
+                }
+            }
+            DeletePolicy::Orphan => {
+                for before in owned {
+                    let mut pet = before.clone();
+                    pet.person_id = None;
+                    change.push(Mutation::PetSaved(pet.clone()));
+                    events.push(NewEvent::pet(EventKind::PetUpdated, &pet));
+                    entries.push(audit.pet_entry(AuditAction::Update, Some(&before), Some(&pet)));
+                    saved_pets.push(pet);
+                }
+            }
+        }
+
+        let mut person = stored_person.clone();
+        person.deleted_at = Some(Utc::now());
+        change.save_person(&person);
+        events.push(NewEvent::person(EventKind::PersonDeleted, &person));
+        change.record_events(events);
+        entries.insert(
+            0,
+            audit.person_entry(AuditAction::Delete, Some(stored_person), None),
+        );
+        change.record_audit(entries);
+        self.commit(change).await?;
+
+        for pet_id in deleted_pets {
+            pets.remove(&pet_id);
+        }
+        for pet in saved_pets {
+            pets.insert(pet.id.clone(), pet);
+        }
+        *stored_person = person;
+        Ok(true)
+    }
+
+    async fn restore_person(
+        &self,
+        person_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error> {
+        let mut people = self.people.write().await;
+        // a merged person was replaced by whom they were merged into, who has
+        // their pets and relationships.
+        if let Some(merge) = self.merges.read().await.get(&person_id) {
+            return Err(Error::PersonMerged(merge.target_id.clone()));
+        }
+
+        match people.get_mut(&person_id) {
+            Some(stored_person) if stored_person.deleted_at.is_none() => {
+                Err(Error::PersonNotDeleted)
+            }
+            Some(stored_person) => {
+                let mut person = stored_person.clone();
+                person.deleted_at = None;
+                let mut change = Change::default();
+                change.save_person(&person);
+                change.record_events([NewEvent::person(EventKind::PersonUpdated, &person)]);
+                change.record_audit([audit.person_entry(
+                    AuditAction::Restore,
+                    Some(stored_person),
+                    Some(&person),
+                )]);
+                self.commit(change).await?;
+                *stored_person = person.clone();
+                Ok(person)
+            }
+            None => Err(Error::PersonNotFound),
+        }
+    }
+
+    async fn merge_people(
+        &self,
+        person: Person,
+        source_id: PersonID,
+        audit: &AuditContext,
+    ) -> Result<Person, Error> {
+        let mut people = self.people.write().await;
+        let mut pets = self.pets.write().await;
+        let mut relationships = self.relationships.write().await;
+        let mut merges = self.merges.write().await;
+
+        if !Self::person_exists(&people, &person.id) || !Self::person_exists(&people, &source_id) {
+            return Err(Error::PersonNotFound);
+        }
+        let stored_person = &people[&person.id];
+        if stored_person.version != person.version {
+            return Err(Error::PersonVersionConflict);
+        }
+
+        let mut person = person;
+        person.version += 1;
+        person.created_on = stored_person.created_on;
+        let mut change = Change::default();
+        change.save_person(&person);
+        let mut events = vec![NewEvent::person(EventKind::PersonUpdated, &person)];
+        let mut entries =
+            vec![audit.person_entry(AuditAction::Update, Some(stored_person), Some(&person))];
+
+        let mut owned: Vec<Pet> = pets
+            .values()
+            .filter(|pet| pet.person_id.as_ref() == Some(&source_id))
+            .cloned()
+            .collect();
+        owned.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut saved_pets = Vec::with_capacity(owned.len());
+        for before in owned {
+            let mut pet = before.clone();
+            pet.person_id = Some(person.id.clone());
+            change.push(Mutation::PetSaved(pet.clone()));
+            events.push(NewEvent::pet(EventKind::PetUpdated, &pet));
+            entries.push(audit.pet_entry(AuditAction::Update, Some(&before), Some(&pet)));
+            saved_pets.push(pet);
+        }
+
+        // the relatives of the source become relatives of the survivor,
+        // unless they were related already.
+        let mut deleted_relationships = Vec::new();
+        let mut saved_relationships = Vec::new();
+        for relationship in relationships.get(&source_id).into_iter().flatten() {
+            deleted_relationships.extend([relationship.reciprocal(), relationship.clone()]);
+
+            let already_related = relationships.get(&person.id).is_some_and(|saved| {
+                saved
+                    .iter()
+                    .any(|saved| saved.related_id == relationship.related_id)
+            });
+            if relationship.related_id == person.id || already_related {
+                continue;
+            }
+
+            let moved = Relationship {
+                person_id: person.id.clone(),
+                ..relationship.clone()
+            };
+            saved_relationships.extend([moved.clone(), moved.reciprocal()]);
+        }
+        for deleted in deleted_relationships.iter() {
+            change.push(Mutation::RelationshipDeleted(deleted.clone()));
+        }
+        for saved in saved_relationships.iter() {
+            change.push(Mutation::RelationshipSaved(saved.clone()));
+        }
+
+        let stored_source = &people[&source_id];
+        let mut source = stored_source.clone();
+        source.deleted_at = Some(Utc::now());
+        change.save_person(&source);
+        events.push(NewEvent::person(EventKind::PersonDeleted, &source));
+        entries.insert(
+            1,
+            audit.person_entry(AuditAction::Merge, Some(stored_source), None),
+        );
+        change.record_events(events);
+        change.record_audit(entries);
+
+        let merge = Merge::new(source_id, person.id.clone());
+        change.push(Mutation::PeopleMerged(merge.clone()));
+        self.commit(change).await?;
+
+        people.insert(person.id.clone(), person.clone());
+        people.insert(source.id.clone(), source);
+        for pet in saved_pets {
+            pets.insert(pet.id.clone(), pet);
+        }
+        relationships.remove(&merge.source_id);
+        for deleted in deleted_relationships {
+            if let Some(saved) = relationships.get_mut(&deleted.person_id) {
+                saved.retain(|saved| saved.related_id != deleted.related_id);
+            }
+        }
+        for saved in saved_relationships {
+            relationships
+                .entry(saved.person_id.clone())
+                .or_default()
+                .push(saved);
+        }
+        relationships.retain(|_, saved| !saved.is_empty());
+        merges.insert(merge.source_id.clone(), merge);
+
+        Ok(person)
+    }
+
+    async fn get_merge(&self, source_id: PersonID) -> Result<Option<Merge>, Error> {
+        Ok(self.merges.read().await.get(&source_id).cloned())
+    }
+
+    async fn get_person_history(&self, person_id: PersonID) -> Result<Vec<PersonVersion>, Error> {
+        Ok(self
+            .history
+            .read()
+            .await
+            .get(&person_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_people_as_of(&self, at: DateTime<Utc>) -> Result<Vec<Person>, Error> {
+        Ok(self
+            .history
+            .read()
+            .await
+            .values()
+            .filter_map(|versions| {
+                versions
+                    .iter()
+                    .rev()
+                    .find(|version| version.saved_at <= at)
+                    .map(|version| version.person.clone())
+            })
+            .collect())
+    }
+
+    async fn purge_people(
+        &self,
+        deleted_before: DateTime<Utc>,
+        audit: &AuditContext,
+    ) -> Result<Vec<Person>, Error> {
+        let mut people = self.people.write().await;
+        let mut pets = self.pets.write().await;
+
+        let purged: Vec<PersonID> = people
+            .values()
+            .filter(|person| matches!(person.deleted_at, Some(at) if at < deleted_before))
+            .map(|person| person.id.clone())
+            .collect();
+
+        // pets would break the foreign key, so they go away with their owner.
+        let orphan_pets: Vec<PetID> = pets
+            .values()
+            .filter(|pet| {
+                pet.person_id
+                    .as_ref()
+                    .is_some_and(|owner| purged.contains(owner))
+            })
+            .map(|pet| pet.id.clone())
+            .collect();
+
+        let mut change = Change::default();
+        for pet_id in orphan_pets.iter() {
+            change.push(Mutation::PetDeleted(pet_id.clone()));
+        }
+        change.record_events(
+            orphan_pets
+                .iter()
+                .filter_map(|pet_id| pets.get(pet_id))
+                .map(|pet| NewEvent::pet(EventKind::PetDeleted, pet)),
+        );
+
+        let mut relationships = self.relationships.write().await;
+        let purged_relationships: Vec<Relationship> = relationships
+            .values()
+            .flatten()
+            .filter(|relationship| {
+                purged.contains(&relationship.person_id)
+                    || purged.contains(&relationship.related_id)
+            })
+            .cloned()
+            .collect();
+        for relationship in purged_relationships.iter() {
+            change.push(Mutation::RelationshipDeleted(relationship.clone()));
+        }
+
+        let mut purged_people: Vec<Person> = purged
+            .iter()
+            .filter_map(|person_id| people.get(person_id))
+            .cloned()
+            .collect();
+        purged_people.sort_by(|a, b| a.id.cmp(&b.id));
+        for person in purged_people.iter() {
+            change.push(Mutation::PersonDeleted(person.id.clone()));
+        }
+        change.record_audit(
+            purged_people
+                .iter()
+                .map(|person| audit.person_entry(AuditAction::Purge, Some(person), None)),
+        );
+        self.commit(change).await?;
+
+        for pet_id in orphan_pets {
+            pets.remove(&pet_id);
+        }
+        for relationship in purged_relationships {
+            if let Some(saved) = relationships.get_mut(&relationship.person_id) {
+                saved.retain(|saved| saved.related_id != relationship.related_id);
+            }
+        }
+        relationships.retain(|_, saved| !saved.is_empty());
+        let mut history = self.history.write().await;
+        for person in purged_people.iter() {
+            people.remove(&person.id);
+            history.remove(&person.id);
+        }
+
+        debug!("{} people were purged", purged_people.len());
+        Ok(purged_people)
+    }
+
+    async fn add_pet(&self, new_pet: Pet, audit: &AuditContext) -> Result<Pet, Error> {
+        let people = self.people.read().await;
+        if !Self::owner_exists(&people, &new_pet) {
+            return Err(Error::PersonNotFound);
+        }
+
+        let mut pets = self.pets.write().await;
+        if pets.contains_key(&new_pet.id) {
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        let mut change = Change::default();
+        change.push(Mutation::PetSaved(new_pet.clone()));
+        change.record_events([NewEvent::pet(EventKind::PetAdded, &new_pet)]);
+        change.record_audit([audit.pet_entry(AuditAction::Create, None, Some(&new_pet))]);
+        self.commit(change).await?;
+        pets.insert(new_pet.id.clone(), new_pet.clone());
+
+        Ok(new_pet)
+    }
+
+    async fn get_pet(&self, pet_id: PetID) -> Result<Pet, Error> {
+        match self.pets.read().await.get(&pet_id) {
+            Some(pet) => Ok(pet.clone()),
+            None => Err(Error::PetNotFound),
+        }
+    }
+
+    async fn get_pets(&self, owners: &[PersonID]) -> Result<Vec<Pet>, Error> {
+        let mut pets: Vec<Pet> = self
+            .pets
+            .read()
+            .await
+            .values()
+            .filter(|pet| {
+                pet.person_id
+                    .as_ref()
+                    .is_some_and(|owner| owners.contains(owner))
+            })
+            .cloned()
+            .collect();
+        pets.sort_by(|a, b| (&a.person_id, &a.id).cmp(&(&b.person_id, &b.id)));
+
+        Ok(pets)
+    }
+
+    async fn update_pet(&self, pet: Pet, audit: &AuditContext) -> Result<Pet, Error> {
+        let people = self.people.read().await;
+        let mut pets = self.pets.write().await;
+        let stored_pet = match pets.get_mut(&pet.id) {
+            Some(stored_pet) => stored_pet,
+            None => return Err(Error::PetNotFound),
+        };
+
+        // an orphaned pet which stays without owner has none to check.
+        let stays_orphan = pet.person_id.is_none() && stored_pet.person_id.is_none();
+        if !stays_orphan && !Self::owner_exists(&people, &pet) {
+            return Err(Error::PersonNotFound);
+        }
+
+        let mut change = Change::default();
+        change.push(Mutation::PetSaved(pet.clone()));
+        change.record_events([NewEvent::pet(EventKind::PetUpdated, &pet)]);
+        change.record_audit([audit.pet_entry(AuditAction::Update, Some(stored_pet), Some(&pet))]);
+        self.commit(change).await?;
+        *stored_pet = pet.clone();
+
+        Ok(pet)
+    }
+
+    async fn delete_pet(&self, pet_id: PetID, audit: &AuditContext) -> Result<bool, Error> {
+        let mut pets = self.pets.write().await;
+        let pet = match pets.get(&pet_id) {
+            Some(pet) => pet.clone(),
+            None => return Err(Error::PetNotFound),
+        };
+
+        let mut change = Change::default();
+        change.push(Mutation::PetDeleted(pet_id.clone()));
+        change.record_events([NewEvent::pet(EventKind::PetDeleted, &pet)]);
+        change.record_audit([audit.pet_entry(AuditAction::Delete, Some(&pet), None)]);
+        self.commit(change).await?;
+        pets.remove(&pet_id);
+
+        Ok(true)
+    }
+
+    async fn add_relationship(&self, relationship: Relationship) -> Result<Relationship, Error> {
+        let people = self.people.read().await;
+        if !Self::person_exists(&people, &relationship.person_id)
+            || !Self::person_exists(&people, &relationship.related_id)
+        {
+            return Err(Error::PersonNotFound);
+        }
+
+        let mut relationships = self.relationships.write().await;
+        let already_related = relationships
+            .get(&relationship.person_id)
+            .is_some_and(|saved| {
+                saved
+                    .iter()
+                    .any(|saved| saved.related_id == relationship.related_id)
+            });
+        if already_related {
+            return Err(Error::RelationshipExists);
+        }
+
+        let saved_relationships = [relationship.clone(), relationship.reciprocal()];
+        self.write_ahead(&saved_relationships.clone().map(Mutation::RelationshipSaved))
+            .await?;
+        for saved in saved_relationships {
+            relationships
+                .entry(saved.person_id.clone())
+                .or_default()
+                .push(saved);
+        }
+
+        Ok(relationship)
+    }
+
+    async fn get_relationships(&self, people: &[PersonID]) -> Result<Vec<Relationship>, Error> {
+        let stored_people = self.people.read().await;
+        let relationships = self.relationships.read().await;
+
+        let mut found: Vec<Relationship> = people
+            .iter()
+            .filter_map(|person_id| relationships.get(person_id))
+            .flatten()
+            .filter(|relationship| Self::person_exists(&stored_people, &relationship.related_id))
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| (&a.person_id, &a.related_id).cmp(&(&b.person_id, &b.related_id)));
+        found.dedup();
+
+        Ok(found)
+    }
+
+    async fn delete_relationship(
+        &self,
+        person_id: PersonID,
+        related_id: PersonID,
+    ) -> Result<bool, Error> {
+        let mut relationships = self.relationships.write().await;
+        let relationship = match relationships
+            .get(&person_id)
+            .and_then(|saved| saved.iter().find(|saved| saved.related_id == related_id))
+        {
+            Some(relationship) => relationship.clone(),
+            None => return Err(Error::RelationshipNotFound),
+        };
+
+        let deleted_relationships = [relationship.reciprocal(), relationship];
+        self.write_ahead(
+            &deleted_relationships
+                .clone()
+                .map(Mutation::RelationshipDeleted),
+        )
+        .await?;
+        for deleted in deleted_relationships {
+            if let Some(saved) = relationships.get_mut(&deleted.person_id) {
+                saved.retain(|saved| saved.related_id != deleted.related_id);
+            }
+        }
+
+        Ok(true)
+    }
+    async fn add_audit_entries(&self, entries: Vec<AuditEntry>) -> Result<(), Error> {
+        let mut change = Change::default();
+        change.record_audit(entries);
+        self.commit(change).await
+    }
+
+    async fn get_audit_entries(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>, Error> {
+        let mut entries: Vec<AuditEntry> = self
+            .audit
+            .read()
+            .await
+            .iter()
+            .filter(|entry| query.matches(entry))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| (b.at, &b.id).cmp(&(a.at, &a.id)));
+        entries.truncate(query.limit.max(0) as usize);
+
+        Ok(entries)
+    }
+
+    async fn get_pending_events(&self, limit: i32) -> Result<Vec<DomainEvent>, Error> {
+        Ok(self
+            .outbox
+            .read()
+            .await
+            .iter()
+            .filter(|event| event.sent_at.is_none())
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_events_sent(&self, ids: &[i64]) -> Result<(), Error> {
+        let mut outbox = self.outbox.write().await;
+        let sent_at = Utc::now();
+        self.write_ahead(&[Mutation::EventsSent {
+            ids: ids.to_vec(),
+            sent_at,
+        }])
+        .await?;
+        for event in outbox.iter_mut().filter(|event| ids.contains(&event.id)) {
+            event.sent_at = Some(sent_at);
+        }
+
+        Ok(())
+    }
+
+    async fn get_events_after(&self, after: i64, limit: i32) -> Result<Vec<DomainEvent>, Error> {
+        Ok(self
+            .outbox
+            .read()
+            .await
+            .iter()
+            .filter(|event| event.id > after)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_last_event_id(&self) -> Result<i64, Error> {
+        Ok(self.outbox.read().await.last().map_or(0, |event| event.id))
+    }
+
+    fn live_events(&self) -> &LiveEvents {
+        &self.live_events
+    }
+
+    async fn add_webhook(&self, webhook: Webhook) -> Result<Webhook, Error> {
+        let mut webhooks = self.webhooks.write().await;
+        if webhooks.contains_key(&webhook.id) {
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        self.write_ahead(&[Mutation::WebhookSaved(webhook.clone())])
+            .await?;
+        webhooks.insert(webhook.id.clone(), webhook.clone());
+
+        Ok(webhook)
+    }
+
+    async fn get_webhooks(&self) -> Result<Vec<Webhook>, Error> {
+        let mut webhooks: Vec<Webhook> = self.webhooks.read().await.values().cloned().collect();
+        webhooks.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+
+        Ok(webhooks)
+    }
+
+    async fn get_webhook(&self, webhook_id: WebhookID) -> Result<Webhook, Error> {
+        match self.webhooks.read().await.get(&webhook_id) {
+            Some(webhook) => Ok(webhook.clone()),
+            None => Err(Error::WebhookNotFound),
+        }
+    }
+
+    async fn delete_webhook(&self, webhook_id: WebhookID) -> Result<bool, Error> {
+        let mut webhooks = self.webhooks.write().await;
+        let mut webhook_deliveries = self.webhook_deliveries.write().await;
+        if !webhooks.contains_key(&webhook_id) {
+            return Err(Error::WebhookNotFound);
+        }
+
+        self.write_ahead(&[Mutation::WebhookDeleted(webhook_id.clone())])
+            .await?;
+        webhooks.remove(&webhook_id);
+        webhook_deliveries.retain(|delivery| delivery.webhook_id != webhook_id);
+
+        Ok(true)
+    }
+
+    async fn add_webhook_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error> {
+        let webhooks = self.webhooks.read().await;
+        let mut webhook_deliveries = self.webhook_deliveries.write().await;
+        if !webhooks.contains_key(&delivery.webhook_id) {
+            return Err(Error::WebhookNotFound);
+        }
+
+        self.write_ahead(&[Mutation::WebhookDeliveryAdded(delivery.clone())])
+            .await?;
+        webhook_deliveries.push(delivery);
+
+        Ok(())
+    }
+
+    async fn get_webhook_deliveries(
+        &self,
+        webhook_id: WebhookID,
+        limit: i32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let mut deliveries: Vec<WebhookDelivery> = self
+            .webhook_deliveries
+            .read()
+            .await
+            .iter()
+            .filter(|delivery| delivery.webhook_id == webhook_id)
+            .cloned()
+            .collect();
+        deliveries.sort_by(|a, b| (b.delivered_at, &b.id).cmp(&(a.delivered_at, &a.id)));
+        deliveries.truncate(limit.max(0) as usize);
+
+        Ok(deliveries)
+    }
+
+    async fn get_webhook_delivery(&self, delivery_id: String) -> Result<WebhookDelivery, Error> {
+        match self
+            .webhook_deliveries
+            .read()
+            .await
+            .iter()
+            .find(|delivery| delivery.id == delivery_id)
+        {
+            Some(delivery) => Ok(delivery.clone()),
+            None => Err(Error::WebhookDeliveryNotFound),
+        }
+    }
+}
+
+#[async_trait]
+impl users_storage for Store {
+    async fn add_account(&self, new_account: Account) -> Result<AccountID, Error> {
+        debug!("adding account to memory database: {}", new_account.email);
+
+        let mut accounts = self.accounts.write().await;
+        let email_exists = accounts
+            .values()
+            .any(|account| account.email == new_account.email);
+        if email_exists || accounts.contains_key(&new_account.id) {
+            tracing::event!(tracing::Level::INFO, message = "account already exists");
+            return Err(Error::DatabaseUniqueError);
+        }
+
+        self.write_ahead(&[Mutation::AccountSaved(new_account.clone())])
+            .await?;
+        let account_id = new_account.id.clone();
+        accounts.insert(account_id.clone(), new_account);
+
+        debug!("new account was added to memory database: {}", account_id);
+        Ok(account_id)
+    }
+
+    async fn get_account(&self, email: String) -> Result<Account, Error> {
+        debug!("getting account from memory database: {}", email);
+
+        match self
+            .accounts
+            .read()
+            .await
+            .values()
+            .find(|account| account.email == email)
+        {
+            Some(account) => Ok(account.clone()),
+            None => Ok(Account::default()),
+        }
+    }
+}
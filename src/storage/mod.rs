@@ -1,3 +1,10 @@
 pub mod db;
 pub mod memory;
 mod memory_tests;
+pub mod search;
+mod search_tests;
+pub mod sqlite;
+mod sqlite_tests;
+pub mod stream;
+mod stream_tests;
+pub mod wal;
@@ -0,0 +1,115 @@
+use crate::types::people::{Person, PersonMatch};
+use std::collections::HashSet;
+
+/// The lowest score a person needs to be part of the results, the same
+/// default pg_trgm uses for its similarity operator.
+pub const MIN_SCORE: f32 = 0.3;
+/// The lowest similarity a term needs with a name word to count, the same
+/// default pg_trgm uses for its word similarity operator.
+pub const MIN_WORD_SCORE: f32 = 0.6;
+
+/// rank scores every person against the terms the same way postgres does with
+/// pg_trgm, the best matches come first.
+pub fn rank<'a>(
+    people: impl Iterator<Item = &'a Person>,
+    terms: &str,
+    limit: i32,
+) -> Vec<PersonMatch> {
+    let mut matches: Vec<PersonMatch> = people
+        .filter(|person| person.deleted_at.is_none())
+        .map(|person| PersonMatch {
+            score: score(terms, &full_name(person)),
+            person: person.clone(),
+        })
+        .filter(|found| found.score > MIN_SCORE)
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.person.id.cmp(&b.person.id))
+    });
+    matches.truncate(limit.max(0) as usize);
+    matches
+}
+
+pub fn full_name(person: &Person) -> String {
+    format!("{} {}", person.first_name, person.last_name)
+}
+
+/// score is the best of the trigram similarity of the whole name and the
+/// similarity of each term to the name word closest to it, plus a bonus for
+/// the terms found as whole words like a full-text search would do.
+pub fn score(terms: &str, name: &str) -> f32 {
+    let name_words = words(name);
+    let term_words = words(terms);
+    if term_words.is_empty() {
+        return 0.0;
+    }
+
+    let whole = similarity(&trigrams(terms), &trigrams(name));
+
+    let mut per_word: f32 = term_words
+        .iter()
+        .map(|term| {
+            let term_trigrams = word_trigrams(term);
+            name_words
+                .iter()
+                .map(|word| word_similarity(&term_trigrams, &word_trigrams(word)))
+                .fold(0.0, f32::max)
+        })
+        .sum::<f32>()
+        / term_words.len() as f32;
+    if per_word < MIN_WORD_SCORE {
+        per_word = 0.0;
+    }
+
+    let found = term_words
+        .iter()
+        .filter(|term| name_words.contains(term))
+        .count() as f32
+        / term_words.len() as f32;
+
+    whole.max(per_word) + found * 0.1
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// trigrams returns the trigrams of every word, padded the way pg_trgm does.
+fn trigrams(text: &str) -> HashSet<String> {
+    words(text)
+        .iter()
+        .flat_map(|word| word_trigrams(word))
+        .collect()
+}
+
+fn word_trigrams(word: &str) -> HashSet<String> {
+    let padded: Vec<char> = format!("  {} ", word).chars().collect();
+    padded
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// similarity is the share of trigrams both sets have.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// word_similarity is the share of the term trigrams found in the word, so a
+/// partial name still scores high.
+fn word_similarity(term: &HashSet<String>, word: &HashSet<String>) -> f32 {
+    if term.is_empty() {
+        return 0.0;
+    }
+    term.intersection(word).count() as f32 / term.len() as f32
+}
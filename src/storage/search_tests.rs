@@ -0,0 +1,63 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod search_tests {
+    use crate::storage::search;
+    use crate::types::people::{Person, PersonID};
+
+    #[test]
+    fn test_score_typos_and_partial_names() {
+        // Given
+        let name = "Fernando Luis";
+        // When
+        let exact = search::score("fernando luis", name);
+        let typo = search::score("Fernado", name);
+        let partial = search::score("Fer", name);
+        let unrelated = search::score("Esme", name);
+        let too_short = search::score("do", name);
+        // Then
+        assert!(exact > typo);
+        assert!(typo > search::MIN_SCORE);
+        assert!(partial > search::MIN_SCORE);
+        assert!(unrelated < search::MIN_SCORE);
+        assert!(too_short < search::MIN_SCORE);
+    }
+
+    #[test]
+    fn test_rank_people() {
+        // Given
+        let mut deleted = Person::new(
+            PersonID("4".to_string()),
+            "Fernando".to_string(),
+            "Fernando".to_string(),
+        );
+        deleted.deleted_at = Some(chrono::Utc::now());
+        let people = [
+            Person::new(
+                PersonID("1".to_string()),
+                "Fernanda".to_string(),
+                "Gomez".to_string(),
+            ),
+            Person::new(
+                PersonID("2".to_string()),
+                "Esme".to_string(),
+                "Esme".to_string(),
+            ),
+            Person::new(
+                PersonID("3".to_string()),
+                "Fernando".to_string(),
+                "Luis".to_string(),
+            ),
+            deleted,
+        ];
+        // When
+        let got = search::rank(people.iter(), "fernando", 10);
+        let got_one = search::rank(people.iter(), "fernando", 1);
+        // Then
+        let ids: Vec<String> = got
+            .iter()
+            .map(|found| found.person.id.to_string())
+            .collect();
+        assert_eq!(ids, vec!["3", "1"]);
+        assert_eq!(got_one.len(), 1);
+    }
+}
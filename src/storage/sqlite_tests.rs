@@ -0,0 +1,1370 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod sqlite_tests {
+    use crate::errors::error::Error;
+    use crate::people::duplicates::{self, DuplicateDetection};
+    use crate::people::storage::Storer as people_storage;
+    use crate::storage::sqlite::Store;
+    use crate::types::accounts::{Account, AccountID};
+    use crate::types::audit::{AuditAction, AuditContext, AuditEntity, AuditQuery};
+    use crate::types::events::EventKind;
+    use crate::types::pagination::Cursor;
+    use crate::types::people::{Person, PersonID, PersonWithPets};
+    use crate::types::pets::{DeletePolicy, Pet, PetID};
+    use crate::types::profile::{Address, Profile};
+    use crate::types::query::{NameField, NameFilter, NameMatch, PeopleQuery};
+    use crate::types::query::{Sort, SortField, SortOrder};
+    use crate::types::relationships::{Relationship, RelationshipKind};
+    use crate::types::webhooks::{DeliveryStatus, NewWebhook, Webhook, WebhookDelivery};
+    use crate::users::storage::Storer as users_storage;
+    use chrono::{NaiveDate, Utc};
+    use futures::StreamExt;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_add_and_get_people() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let people = vec![
+            Person::new(
+                PersonID("1".to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            ),
+            Person::new(
+                PersonID("2".to_string()),
+                "Fernando".to_string(),
+                "Fernando".to_string(),
+            ),
+        ];
+        for person in people.clone() {
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        // When
+        let all = runtime.block_on(store.get_people(&PeopleQuery::default(), 10, None));
+        let first_page = runtime
+            .block_on(store.get_people(&PeopleQuery::default(), 1, None))
+            .unwrap();
+        let after = first_page
+            .last()
+            .map(|last| Cursor::new(Sort::default(), last));
+        let second_page =
+            runtime.block_on(store.get_people(&PeopleQuery::default(), 1, after.clone()));
+        let got = runtime.block_on(store.get_person(PersonID("2".to_string()), false));
+        // Then
+        assert_eq!(all.unwrap(), people);
+        assert_eq!(first_page, vec![people[0].clone()]);
+        assert_eq!(second_page, Ok(vec![people[1].clone()]));
+        assert_eq!(
+            after
+                .map(|cursor| cursor.encode())
+                .map(|token| Cursor::decode(&token)),
+            Some(Ok(Cursor::new(Sort::default(), &people[0])))
+        );
+        assert_eq!(got, Ok(people[1].clone()));
+    }
+
+    #[test]
+    fn test_get_people_sorted_and_filtered() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, first_name, last_name) in [
+            ("10", "Ana", "Zapata"),
+            ("11", "Andres", "Alba"),
+            ("12", "Mariana", "Luna"),
+        ] {
+            let person = Person::new(
+                PersonID(id.to_string()),
+                first_name.to_string(),
+                last_name.to_string(),
+            );
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        let by_last_name_desc = PeopleQuery {
+            sort: Sort {
+                field: SortField::LastName,
+                order: SortOrder::Desc,
+            },
+            filters: vec![NameFilter {
+                field: NameField::FirstName,
+                matching: NameMatch::Prefix,
+                value: "An".to_string(),
+            }],
+            ..Default::default()
+        };
+        let containing_ana = PeopleQuery {
+            filters: vec![NameFilter {
+                field: NameField::FirstName,
+                matching: NameMatch::Contains,
+                value: "ANA".to_string(),
+            }],
+            ..Default::default()
+        };
+        // When
+        let first_page = runtime
+            .block_on(store.get_people(&by_last_name_desc, 1, None))
+            .unwrap();
+        let after = first_page
+            .last()
+            .map(|last| Cursor::new(by_last_name_desc.sort, last));
+        let second_page = runtime
+            .block_on(store.get_people(&by_last_name_desc, 10, after))
+            .unwrap();
+        let containing = runtime
+            .block_on(store.get_people(&containing_ana, 10, None))
+            .unwrap();
+        // Then
+        let ids = |people: &Vec<Person>| -> Vec<String> {
+            people.iter().map(|person| person.id.to_string()).collect()
+        };
+        assert_eq!(ids(&first_page), vec!["10"]);
+        assert_eq!(ids(&second_page), vec!["11"]);
+        assert_eq!(ids(&containing), vec!["10", "12"]);
+    }
+
+    #[test]
+    fn test_add_people() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let new_people = vec![
+            Person::new(
+                PersonID("10".to_string()),
+                "Esme".to_string(),
+                "Esme".to_string(),
+            ),
+            Person::new(
+                PersonID("11".to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            ),
+        ];
+        let with_duplicate = vec![
+            Person::new(
+                PersonID("12".to_string()),
+                "Ana".to_string(),
+                "Ana".to_string(),
+            ),
+            Person::new(
+                PersonID("10".to_string()),
+                "Esme".to_string(),
+                "Esme".to_string(),
+            ),
+        ];
+        // When
+        let got = runtime
+            .block_on(store.add_people(new_people.clone(), &AuditContext::default()))
+            .unwrap();
+        let got_duplicate =
+            runtime.block_on(store.add_people(with_duplicate, &AuditContext::default()));
+        // Then
+        let ids: Vec<String> = got.iter().map(|person| person.id.to_string()).collect();
+        assert_eq!(ids, vec!["10", "11"]);
+        assert!(got_duplicate.is_err());
+        let got_partial = runtime.block_on(store.get_person(PersonID("12".to_string()), true));
+        assert_eq!(got_partial, Err(Error::PersonNotFound));
+    }
+
+    #[test]
+    fn test_add_people_unless_duplicates() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let existing = Person::new(
+            PersonID("7".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        runtime
+            .block_on(store.add_person(existing.clone(), &AuditContext::default()))
+            .unwrap();
+        let new_people = vec![
+            Person::new(
+                PersonID("8".to_string()),
+                "Esmé".to_string(),
+                "Weatherwax".to_string(),
+            ),
+            Person::new(
+                PersonID("9".to_string()),
+                "Gytha".to_string(),
+                "Ogg".to_string(),
+            ),
+            Person::new(
+                PersonID("10".to_string()),
+                "Gytha".to_string(),
+                "Ogg".to_string(),
+            ),
+        ];
+        // When
+        let got = runtime
+            .block_on(store.add_people_unless_duplicates(
+                new_people.clone(),
+                &DuplicateDetection::default(),
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        // Then
+        assert_eq!(got.len(), 3);
+        match &got[0] {
+            Err(Error::PossibleDuplicates(matches)) => {
+                assert_eq!(matches.len(), 1);
+                assert_eq!(matches[0].person, existing);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(got[1], Ok(new_people[1].clone()));
+        match &got[2] {
+            Err(Error::PossibleDuplicates(matches)) => {
+                assert_eq!(matches[0].person, new_people[1]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            runtime.block_on(store.get_person(PersonID("8".to_string()), true)),
+            Err(Error::PersonNotFound)
+        );
+        let events = runtime.block_on(store.get_pending_events(10)).unwrap();
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn test_add_people_unless_duplicates_scores_everybody() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let mut existing: Vec<Person> = (0..duplicates::CANDIDATES_PAGE)
+            .map(|i| {
+                Person::new(
+                    PersonID(format!("a{i:04}")),
+                    format!("Someone {i}"),
+                    "Else".to_string(),
+                )
+            })
+            .collect();
+        let katherine = Person::new(
+            PersonID("b".to_string()),
+            "Katherine".to_string(),
+            "Smith".to_string(),
+        );
+        existing.push(katherine.clone());
+        runtime
+            .block_on(store.add_people(existing, &AuditContext::default()))
+            .unwrap();
+        let catherine = Person::new(
+            PersonID("c".to_string()),
+            "Catherine".to_string(),
+            "Smith".to_string(),
+        );
+        // When
+        let got = runtime
+            .block_on(store.add_people_unless_duplicates(
+                vec![catherine],
+                &DuplicateDetection::default(),
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        // Then
+        match &got[0] {
+            Err(Error::PossibleDuplicates(matches)) => {
+                assert_eq!(matches.len(), 1);
+                assert_eq!(matches[0].person, katherine);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_search_people() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, first_name, last_name) in [
+            ("10", "Esmeralda", "Luna"),
+            ("11", "Esme", "Alba"),
+            ("12", "Esme", "Luna"),
+        ] {
+            let person = Person::new(
+                PersonID(id.to_string()),
+                first_name.to_string(),
+                last_name.to_string(),
+            );
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        runtime
+            .block_on(store.delete_person(
+                PersonID("12".to_string()),
+                DeletePolicy::Refuse,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        // When
+        let got = runtime.block_on(store.search_people("esme", 10)).unwrap();
+        let got_nothing = runtime.block_on(store.search_people("Zapata", 10)).unwrap();
+        // Then
+        let ids: Vec<String> = got
+            .iter()
+            .map(|found| found.person.id.to_string())
+            .collect();
+        assert_eq!(ids, vec!["11", "10"]);
+        assert!(got[0].score > got[1].score);
+        assert!(got_nothing.is_empty());
+    }
+
+    #[test]
+    fn test_get_not_found_person() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        // When
+        let got = runtime.block_on(store.get_person(PersonID("2000".to_string()), false));
+        // Then
+        assert_eq!(got, Err(Error::PersonNotFound));
+    }
+
+    #[test]
+    fn test_update_and_delete_person() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let person = Person::new(
+            PersonID("3".to_string()),
+            "Esme".to_string(),
+            "Esme".to_string(),
+        );
+        let updated_person = Person::new(
+            PersonID("3".to_string()),
+            "Esme".to_string(),
+            "Emse".to_string(),
+        );
+        runtime
+            .block_on(store.add_person(person.clone(), &AuditContext::default()))
+            .unwrap();
+        // When
+        let updated =
+            runtime.block_on(store.update_person(updated_person.clone(), &AuditContext::default()));
+        let stale_update =
+            runtime.block_on(store.update_person(updated_person.clone(), &AuditContext::default()));
+        let deleted = runtime.block_on(store.delete_person(
+            PersonID("3".to_string()),
+            DeletePolicy::Refuse,
+            &AuditContext::default(),
+        ));
+        let deleted_again = runtime.block_on(store.delete_person(
+            PersonID("3".to_string()),
+            DeletePolicy::Refuse,
+            &AuditContext::default(),
+        ));
+        // Then
+        let mut want = updated_person;
+        want.version = 2;
+        want.created_on = person.created_on;
+        assert_eq!(updated, Ok(want));
+        assert_eq!(stale_update, Err(Error::PersonVersionConflict));
+        assert_eq!(deleted, Ok(true));
+        assert_eq!(deleted_again, Err(Error::PersonNotFound));
+    }
+
+    #[test]
+    fn test_add_and_update_person_profile() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let mut person = Person::new(
+            PersonID("4".to_string()),
+            "Luis".to_string(),
+            "Fernando".to_string(),
+        );
+        person.profile = Profile {
+            email: Some("luis@anydomain.com".to_string()),
+            phone: Some("+34600000000".to_string()),
+            birth_date: NaiveDate::from_ymd_opt(1990, 5, 17),
+            addresses: vec![Address {
+                street: "Calle Mayor 1".to_string(),
+                city: "Madrid".to_string(),
+                region: None,
+                postal_code: Some("28013".to_string()),
+                country: "ES".to_string(),
+            }],
+        };
+        let mut updated_person = person.clone();
+        updated_person.profile = Profile {
+            email: Some("fernando@anydomain.com".to_string()),
+            ..Default::default()
+        };
+        // When
+        let added = runtime.block_on(store.add_person(person.clone(), &AuditContext::default()));
+        let got = runtime.block_on(store.get_person(PersonID("4".to_string()), false));
+        let updated =
+            runtime.block_on(store.update_person(updated_person.clone(), &AuditContext::default()));
+        let got_updated = runtime.block_on(store.get_person(PersonID("4".to_string()), false));
+        // Then
+        assert_eq!(added, Ok(person.clone()));
+        assert_eq!(got, Ok(person));
+        updated_person.version = 2;
+        assert_eq!(updated, Ok(updated_person.clone()));
+        assert_eq!(got_updated, Ok(updated_person));
+    }
+
+    #[test]
+    fn test_stream_people() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, name) in [("1", "Luis"), ("2", "Fernando")] {
+            let person = Person::new(PersonID(id.to_string()), name.to_string(), name.to_string());
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        for id in ["11", "10"] {
+            let pet = Pet {
+                id: PetID(id.to_string()),
+                name: "Firulais".to_string(),
+                person_id: Some(PersonID("1".to_string())),
+            };
+            runtime
+                .block_on(store.add_pet(pet, &AuditContext::default()))
+                .unwrap();
+        }
+        runtime
+            .block_on(store.delete_person(
+                PersonID("2".to_string()),
+                DeletePolicy::Refuse,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        // When
+        let got: Vec<PersonWithPets> = runtime.block_on(async {
+            store
+                .stream_people(true)
+                .await
+                .unwrap()
+                .map(|person| person.unwrap())
+                .collect()
+                .await
+        });
+        let got_without_pets: Vec<PersonWithPets> = runtime.block_on(async {
+            store
+                .stream_people(false)
+                .await
+                .unwrap()
+                .map(|person| person.unwrap())
+                .collect()
+                .await
+        });
+        // Then
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].person.id, PersonID("1".to_string()));
+        let pet_ids: Vec<String> = got[0]
+            .pets
+            .iter()
+            .flatten()
+            .map(|pet| pet.id.to_string())
+            .collect();
+        assert_eq!(pet_ids, vec!["10", "11"]);
+        assert_eq!(got_without_pets[0].pets, None);
+    }
+
+    #[test]
+    fn test_get_pets() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, name) in [("1", "Luis"), ("2", "Fernando")] {
+            let person = Person::new(PersonID(id.to_string()), name.to_string(), name.to_string());
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        let pets = [
+            Pet {
+                id: PetID("1".to_string()),
+                name: "Firulais".to_string(),
+                person_id: Some(PersonID("1".to_string())),
+            },
+            Pet {
+                id: PetID("2".to_string()),
+                name: "Michi".to_string(),
+                person_id: Some(PersonID("2".to_string())),
+            },
+        ];
+        for pet in pets.iter() {
+            runtime
+                .block_on(store.add_pet(pet.clone(), &AuditContext::default()))
+                .unwrap();
+        }
+        // When
+        let got = runtime
+            .block_on(store.get_pets(&[PersonID("1".to_string())]))
+            .unwrap();
+        let got_none = runtime.block_on(store.get_pets(&[])).unwrap();
+        // Then
+        assert_eq!(got, vec![pets[0].clone()]);
+        assert!(got_none.is_empty());
+    }
+
+    #[test]
+    fn test_add_pet() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        runtime
+            .block_on(store.add_person(person, &AuditContext::default()))
+            .unwrap();
+        let pet = Pet {
+            id: PetID("1".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        let orphan_pet = Pet {
+            id: PetID("2".to_string()),
+            name: "Michi".to_string(),
+            person_id: Some(PersonID("2000".to_string())),
+        };
+        // When
+        let got = runtime.block_on(store.add_pet(pet.clone(), &AuditContext::default()));
+        let got_orphan = runtime.block_on(store.add_pet(orphan_pet, &AuditContext::default()));
+        let delete_owner = runtime.block_on(store.delete_person(
+            PersonID("1".to_string()),
+            DeletePolicy::Cascade,
+            &AuditContext::default(),
+        ));
+        // Then
+        assert_eq!(got, Ok(pet));
+        assert_eq!(got_orphan, Err(Error::PersonNotFound));
+        assert_eq!(delete_owner, Ok(true));
+    }
+
+    #[test]
+    fn test_get_update_and_delete_pet() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for id in ["1", "2"] {
+            let person = Person::new(
+                PersonID(id.to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            );
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        let pet = Pet {
+            id: PetID("1".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        runtime
+            .block_on(store.add_pet(pet.clone(), &AuditContext::default()))
+            .unwrap();
+        let transferred = Pet {
+            name: "Michi".to_string(),
+            person_id: Some(PersonID("2".to_string())),
+            ..pet.clone()
+        };
+        // When
+        let got = runtime.block_on(store.get_pet(pet.id.clone()));
+        let got_updated =
+            runtime.block_on(store.update_pet(transferred.clone(), &AuditContext::default()));
+        let got_without_owner = runtime.block_on(store.update_pet(
+            Pet {
+                person_id: Some(PersonID("2000".to_string())),
+                ..pet.clone()
+            },
+            &AuditContext::default(),
+        ));
+        let got_not_found = runtime.block_on(store.update_pet(
+            Pet {
+                id: PetID("2000".to_string()),
+                ..pet.clone()
+            },
+            &AuditContext::default(),
+        ));
+        let got_deleted =
+            runtime.block_on(store.delete_pet(pet.id.clone(), &AuditContext::default()));
+        let got_after_delete = runtime.block_on(store.get_pet(pet.id.clone()));
+        // Then
+        assert_eq!(got, Ok(pet));
+        assert_eq!(got_updated, Ok(transferred));
+        assert_eq!(got_without_owner, Err(Error::PersonNotFound));
+        assert_eq!(got_not_found, Err(Error::PetNotFound));
+        assert_eq!(got_deleted, Ok(true));
+        assert_eq!(got_after_delete, Err(Error::PetNotFound));
+    }
+
+    #[test]
+    fn test_delete_person_with_pets() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for id in ["1", "2", "3"] {
+            let person = Person::new(
+                PersonID(id.to_string()),
+                "Luis".to_string(),
+                "Luis".to_string(),
+            );
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        let pet_of = |id: &str, owner: &str| Pet {
+            id: PetID(id.to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID(owner.to_string())),
+        };
+        for pet in [pet_of("1", "1"), pet_of("2", "2"), pet_of("3", "3")] {
+            runtime
+                .block_on(store.add_pet(pet, &AuditContext::default()))
+                .unwrap();
+        }
+        // When
+        let got_refused = runtime.block_on(store.delete_person(
+            PersonID("1".to_string()),
+            DeletePolicy::Refuse,
+            &AuditContext::default(),
+        ));
+        let got_still_there = runtime.block_on(store.get_person(PersonID("1".to_string()), false));
+        let got_cascade = runtime.block_on(store.delete_person(
+            PersonID("2".to_string()),
+            DeletePolicy::Cascade,
+            &AuditContext::default(),
+        ));
+        let got_cascaded_pet = runtime.block_on(store.get_pet(PetID("2".to_string())));
+        let got_orphan = runtime.block_on(store.delete_person(
+            PersonID("3".to_string()),
+            DeletePolicy::Orphan,
+            &AuditContext::default(),
+        ));
+        let got_orphaned_pet = runtime.block_on(store.get_pet(PetID("3".to_string())));
+        // Then
+        assert_eq!(
+            got_refused,
+            Err(Error::PersonHasPets(vec![pet_of("1", "1")]))
+        );
+        assert!(got_still_there.is_ok());
+        assert_eq!(got_cascade, Ok(true));
+        assert_eq!(got_cascaded_pet, Err(Error::PetNotFound));
+        assert_eq!(got_orphan, Ok(true));
+        assert_eq!(
+            got_orphaned_pet,
+            Ok(Pet {
+                person_id: None,
+                ..pet_of("3", "3")
+            })
+        );
+    }
+
+    #[test]
+    fn test_rename_orphaned_pet() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        runtime
+            .block_on(store.add_person(
+                Person::new(
+                    PersonID("3".to_string()),
+                    "Esme".to_string(),
+                    "Esme".to_string(),
+                ),
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        let pet = Pet {
+            id: PetID("3".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("3".to_string())),
+        };
+        runtime
+            .block_on(store.add_pet(pet.clone(), &AuditContext::default()))
+            .unwrap();
+        runtime
+            .block_on(store.delete_person(
+                PersonID("3".to_string()),
+                DeletePolicy::Orphan,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        let renamed = Pet {
+            name: "Michi".to_string(),
+            person_id: None,
+            ..pet.clone()
+        };
+        // When
+        let got = runtime.block_on(store.update_pet(renamed.clone(), &AuditContext::default()));
+        let got_deleted_owner =
+            runtime.block_on(store.update_pet(pet.clone(), &AuditContext::default()));
+        // Then
+        assert_eq!(got, Ok(renamed.clone()));
+        assert_eq!(got_deleted_owner, Err(Error::PersonNotFound));
+        assert_eq!(runtime.block_on(store.get_pet(pet.id)), Ok(renamed));
+    }
+
+    #[test]
+    fn test_soft_delete_restore_and_purge_person() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for id in ["1", "2"] {
+            let person = Person::new(PersonID(id.to_string()), id.to_string(), id.to_string());
+            runtime
+                .block_on(store.add_person(person, &AuditContext::default()))
+                .unwrap();
+        }
+        let pet = Pet {
+            id: PetID("1".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        runtime
+            .block_on(store.add_pet(pet.clone(), &AuditContext::default()))
+            .unwrap();
+        let with_deleted = PeopleQuery {
+            include_deleted: true,
+            ..Default::default()
+        };
+        // When
+        let deleted = runtime.block_on(store.delete_person(
+            PersonID("1".to_string()),
+            DeletePolicy::Cascade,
+            &AuditContext::default(),
+        ));
+        let hidden = runtime.block_on(store.get_person(PersonID("1".to_string()), false));
+        let visible = runtime.block_on(store.get_person(PersonID("1".to_string()), true));
+        let people = runtime
+            .block_on(store.get_people(&PeopleQuery::default(), 10, None))
+            .unwrap();
+        let all_people = runtime
+            .block_on(store.get_people(&with_deleted, 10, None))
+            .unwrap();
+        let got_deleted_owner = runtime.block_on(store.add_pet(
+            Pet {
+                id: PetID("2".to_string()),
+                ..pet
+            },
+            &AuditContext::default(),
+        ));
+        let restored = runtime
+            .block_on(store.restore_person(PersonID("1".to_string()), &AuditContext::default()));
+        let restored_again = runtime
+            .block_on(store.restore_person(PersonID("1".to_string()), &AuditContext::default()));
+        let not_purged = runtime.block_on(store.purge_people(Utc::now(), &AuditContext::default()));
+        runtime
+            .block_on(store.delete_person(
+                PersonID("1".to_string()),
+                DeletePolicy::Cascade,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        let purged = runtime.block_on(store.purge_people(Utc::now(), &AuditContext::default()));
+        let restored_after_purge = runtime
+            .block_on(store.restore_person(PersonID("1".to_string()), &AuditContext::default()));
+        // Then
+        assert_eq!(deleted, Ok(true));
+        assert_eq!(hidden, Err(Error::PersonNotFound));
+        assert!(visible.unwrap().deleted_at.is_some());
+        assert_eq!(people.len(), 1);
+        assert_eq!(all_people.len(), 2);
+        assert_eq!(got_deleted_owner, Err(Error::PersonNotFound));
+        assert_eq!(restored.unwrap().deleted_at, None);
+        assert_eq!(restored_again, Err(Error::PersonNotDeleted));
+        assert_eq!(not_purged, Ok(vec![]));
+        assert_eq!(purged.map(|purged| purged.len()), Ok(1));
+        assert_eq!(restored_after_purge, Err(Error::PersonNotFound));
+    }
+
+    #[test]
+    fn test_add_account_with_duplicate_email() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let account = Account {
+            id: AccountID("1".to_string()),
+            email: "esme@anydomain.com".to_string(),
+            password: "123456".to_string(),
+        };
+        let duplicate_account = Account {
+            id: AccountID("2".to_string()),
+            email: "esme@anydomain.com".to_string(),
+            password: "654321".to_string(),
+        };
+        // When
+        let got_id = runtime.block_on(store.add_account(account));
+        let got_duplicate = runtime.block_on(store.add_account(duplicate_account));
+        let got = runtime.block_on(store.get_account("esme@anydomain.com".to_string()));
+        // Then
+        assert_eq!(got_id, Ok(AccountID("1".to_string())));
+        assert_eq!(got_duplicate, Err(Error::DatabaseUniqueError));
+        assert_eq!(got.unwrap().id, AccountID("1".to_string()));
+    }
+
+    #[test]
+    fn test_add_get_and_delete_relationships() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, name) in [("1", "Luis"), ("2", "Fernando"), ("3", "Esme")] {
+            runtime
+                .block_on(store.add_person(
+                    Person::new(PersonID(id.to_string()), name.to_string(), name.to_string()),
+                    &AuditContext::default(),
+                ))
+                .unwrap();
+        }
+        // When
+        let got_added = runtime.block_on(store.add_relationship(relationship(
+            "1",
+            "2",
+            RelationshipKind::Parent,
+        )));
+        let got_related = runtime.block_on(store.add_relationship(relationship(
+            "2",
+            "1",
+            RelationshipKind::Spouse,
+        )));
+        let got_not_found = runtime.block_on(store.add_relationship(relationship(
+            "1",
+            "404",
+            RelationshipKind::Parent,
+        )));
+        runtime
+            .block_on(store.add_relationship(relationship("2", "3", RelationshipKind::Sibling)))
+            .unwrap();
+        let got_all = runtime.block_on(
+            store.get_relationships(&[PersonID("1".to_string()), PersonID("2".to_string())]),
+        );
+        runtime
+            .block_on(store.delete_person(
+                PersonID("3".to_string()),
+                DeletePolicy::Refuse,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        let got_without_deleted =
+            runtime.block_on(store.get_relationships(&[PersonID("2".to_string())]));
+        let got_purged = runtime.block_on(store.purge_people(Utc::now(), &AuditContext::default()));
+        let got_deleted = runtime.block_on(
+            store.delete_relationship(PersonID("2".to_string()), PersonID("1".to_string())),
+        );
+        let got_deleted_again = runtime.block_on(
+            store.delete_relationship(PersonID("1".to_string()), PersonID("2".to_string())),
+        );
+        let got_none = runtime.block_on(
+            store.get_relationships(&[PersonID("1".to_string()), PersonID("2".to_string())]),
+        );
+        // Then
+        let parent = relationship("1", "2", RelationshipKind::Parent);
+        assert_eq!(got_added, Ok(parent.clone()));
+        assert_eq!(got_related, Err(Error::RelationshipExists));
+        assert_eq!(got_not_found, Err(Error::PersonNotFound));
+        assert_eq!(
+            got_all,
+            Ok(vec![
+                parent.clone(),
+                parent.reciprocal(),
+                relationship("2", "3", RelationshipKind::Sibling),
+            ])
+        );
+        assert_eq!(got_without_deleted, Ok(vec![parent.reciprocal()]));
+        assert_eq!(got_purged.map(|purged| purged.len()), Ok(1));
+        assert_eq!(got_deleted, Ok(true));
+        assert_eq!(got_deleted_again, Err(Error::RelationshipNotFound));
+        assert_eq!(got_none, Ok(vec![]));
+    }
+
+    #[test]
+    fn test_merge_people() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        for (id, name) in [
+            ("1", "Luis"),
+            ("2", "Fernando"),
+            ("3", "Esme"),
+            ("4", "Nanny"),
+        ] {
+            runtime
+                .block_on(store.add_person(
+                    Person::new(PersonID(id.to_string()), name.to_string(), name.to_string()),
+                    &AuditContext::default(),
+                ))
+                .unwrap();
+        }
+        let pet = Pet {
+            id: PetID("1".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("2".to_string())),
+        };
+        runtime
+            .block_on(store.add_pet(pet.clone(), &AuditContext::default()))
+            .unwrap();
+        for related in [
+            relationship("1", "2", RelationshipKind::Spouse),
+            relationship("2", "3", RelationshipKind::Sibling),
+            relationship("1", "4", RelationshipKind::Child),
+            relationship("2", "4", RelationshipKind::Parent),
+        ] {
+            runtime.block_on(store.add_relationship(related)).unwrap();
+        }
+        let mut person = runtime
+            .block_on(store.get_person(PersonID("1".to_string()), false))
+            .unwrap();
+        person.last_name = "Fernando".to_string();
+        // When
+        let got = runtime.block_on(store.merge_people(
+            person.clone(),
+            PersonID("2".to_string()),
+            &AuditContext::default(),
+        ));
+        let got_old_version = runtime.block_on(store.merge_people(
+            person,
+            PersonID("3".to_string()),
+            &AuditContext::default(),
+        ));
+        let got_source = runtime.block_on(store.get_person(PersonID("2".to_string()), false));
+        let got_pets = runtime.block_on(store.get_pets(&[PersonID("1".to_string())]));
+        let got_relationships = runtime.block_on(
+            store.get_relationships(&[PersonID("1".to_string()), PersonID("3".to_string())]),
+        );
+        let got_merge = runtime.block_on(store.get_merge(PersonID("2".to_string())));
+        let got_not_merged = runtime.block_on(store.get_merge(PersonID("1".to_string())));
+        let got_restored_source = runtime
+            .block_on(store.restore_person(PersonID("2".to_string()), &AuditContext::default()));
+        // Then
+        let got = got.unwrap();
+        assert_eq!(got.id, PersonID("1".to_string()));
+        assert_eq!(got.last_name, "Fernando");
+        assert_eq!(got.version, 2);
+        assert_eq!(got_old_version, Err(Error::PersonVersionConflict));
+        assert_eq!(got_source, Err(Error::PersonNotFound));
+        assert_eq!(
+            got_pets,
+            Ok(vec![Pet {
+                person_id: Some(PersonID("1".to_string())),
+                ..pet
+            }])
+        );
+        assert_eq!(
+            got_relationships,
+            Ok(vec![
+                relationship("1", "3", RelationshipKind::Sibling),
+                relationship("1", "4", RelationshipKind::Child),
+                relationship("3", "1", RelationshipKind::Sibling),
+            ])
+        );
+        let got_merge = got_merge.unwrap().unwrap();
+        assert_eq!(got_merge.source_id, PersonID("2".to_string()));
+        assert_eq!(got_merge.target_id, PersonID("1".to_string()));
+        assert_eq!(got_not_merged, Ok(None));
+        assert_eq!(
+            got_restored_source,
+            Err(Error::PersonMerged(PersonID("1".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_add_and_get_audit_entries() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        let context = AuditContext::new(Some(AccountID("42".to_string())), "1".to_string());
+        let mut created = context.entry(
+            AuditEntity::Person,
+            "1",
+            AuditAction::Create,
+            None,
+            Some(&person),
+        );
+        created.at = "2026-10-01T10:00:00Z".parse().unwrap();
+        let mut deleted = AuditContext::default().entry(
+            AuditEntity::Person,
+            "1",
+            AuditAction::Delete,
+            Some(&person),
+            None,
+        );
+        deleted.at = "2026-10-02T10:00:00Z".parse().unwrap();
+        let mut other = context.entry(
+            AuditEntity::Person,
+            "2",
+            AuditAction::Create,
+            None,
+            Some(&person),
+        );
+        other.at = "2026-10-03T10:00:00Z".parse().unwrap();
+        runtime
+            .block_on(store.add_audit_entries(vec![created.clone(), deleted.clone()]))
+            .unwrap();
+        runtime
+            .block_on(store.add_audit_entries(vec![other.clone()]))
+            .unwrap();
+        // When
+        let got_all = runtime.block_on(store.get_audit_entries(&AuditQuery::default()));
+        let got_of_person = runtime.block_on(store.get_audit_entries(&AuditQuery {
+            entity_id: Some("1".to_string()),
+            ..Default::default()
+        }));
+        let got_of_actor = runtime.block_on(store.get_audit_entries(&AuditQuery {
+            actor: Some(AccountID("42".to_string())),
+            since: Some("2026-10-01T10:00:00Z".parse().unwrap()),
+            until: Some("2026-10-03T10:00:00Z".parse().unwrap()),
+            ..Default::default()
+        }));
+        let got_limited = runtime.block_on(store.get_audit_entries(&AuditQuery {
+            limit: 1,
+            ..Default::default()
+        }));
+        // Then
+        assert_eq!(
+            got_all,
+            Ok(vec![other.clone(), deleted.clone(), created.clone()])
+        );
+        assert_eq!(got_of_person, Ok(vec![deleted, created.clone()]));
+        assert_eq!(got_of_actor, Ok(vec![created]));
+        assert_eq!(got_limited, Ok(vec![other]));
+    }
+
+    #[test]
+    fn test_changes_save_audit_entries() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let context = AuditContext::new(Some(AccountID("42".to_string())), "1".to_string());
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Fernando".to_string(),
+        );
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(person.id.clone()),
+        };
+        let added = runtime
+            .block_on(store.add_person(person.clone(), &context))
+            .unwrap();
+        let updated = runtime
+            .block_on(store.update_person(
+                Person {
+                    first_name: "Luisfer".to_string(),
+                    ..added.clone()
+                },
+                &context,
+            ))
+            .unwrap();
+        runtime.block_on(store.add_pet(pet, &context)).unwrap();
+        runtime
+            .block_on(store.delete_person(person.id.clone(), DeletePolicy::Orphan, &context))
+            .unwrap();
+        // When
+        let got_of_person = runtime
+            .block_on(store.get_audit_entries(&AuditQuery {
+                entity_id: Some("1".to_string()),
+                ..Default::default()
+            }))
+            .unwrap();
+        let got_of_pet = runtime
+            .block_on(store.get_audit_entries(&AuditQuery {
+                entity_id: Some("10".to_string()),
+                ..Default::default()
+            }))
+            .unwrap();
+        // Then
+        let mut actions: Vec<&str> = got_of_person
+            .iter()
+            .map(|entry| entry.action.as_str())
+            .collect();
+        actions.sort();
+        assert_eq!(actions, vec!["create", "delete", "update"]);
+        assert!(got_of_person.iter().chain(got_of_pet.iter()).all(
+            |entry| entry.request_id == "1" && entry.actor == Some(AccountID("42".to_string()))
+        ));
+        let update = got_of_person
+            .iter()
+            .find(|entry| entry.action == AuditAction::Update)
+            .unwrap();
+        assert_eq!(update.before, serde_json::to_value(&added).ok());
+        assert_eq!(update.after, serde_json::to_value(&updated).ok());
+        let orphaned = got_of_pet
+            .iter()
+            .find(|entry| entry.action == AuditAction::Update)
+            .unwrap();
+        assert_eq!(
+            orphaned.after.as_ref().map(|pet| pet["person_id"].clone()),
+            Some(serde_json::Value::Null)
+        );
+    }
+
+    fn relationship(person_id: &str, related_id: &str, kind: RelationshipKind) -> Relationship {
+        Relationship {
+            person_id: PersonID(person_id.to_string()),
+            related_id: PersonID(related_id.to_string()),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_person_history_and_people_as_of() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let person = Person::new(
+            PersonID("7".to_string()),
+            "Esme".to_string(),
+            "Ogg".to_string(),
+        );
+        let added = runtime
+            .block_on(store.add_person(person, &AuditContext::default()))
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let added_at = Utc::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        runtime
+            .block_on(store.update_person(
+                Person {
+                    last_name: "Weatherwax".to_string(),
+                    ..added
+                },
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        runtime
+            .block_on(store.delete_person(
+                PersonID("7".to_string()),
+                DeletePolicy::Orphan,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        // When
+        let got_history = runtime
+            .block_on(store.get_person_history(PersonID("7".to_string())))
+            .unwrap();
+        let got_as_of = runtime.block_on(store.get_people_as_of(added_at)).unwrap();
+        let got_now = runtime
+            .block_on(store.get_people_as_of(Utc::now()))
+            .unwrap();
+        // Then
+        let last_names: Vec<&str> = got_history
+            .iter()
+            .map(|version| version.person.last_name.as_str())
+            .collect();
+        assert_eq!(last_names, vec!["Ogg", "Weatherwax", "Weatherwax"]);
+        assert!(got_history[2].person.deleted_at.is_some());
+        let as_of = got_as_of.iter().find(|person| person.id.0 == "7").unwrap();
+        assert_eq!(as_of.last_name, "Ogg");
+        assert_eq!(as_of.deleted_at, None);
+        let now = got_now.iter().find(|person| person.id.0 == "7").unwrap();
+        assert!(now.deleted_at.is_some());
+    }
+
+    #[test]
+    fn test_changes_record_events() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let person = runtime
+            .block_on(store.add_person(
+                Person::new(
+                    PersonID("7".to_string()),
+                    "Esme".to_string(),
+                    "Ogg".to_string(),
+                ),
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        runtime
+            .block_on(store.add_pet(
+                Pet {
+                    id: PetID("10".to_string()),
+                    name: "Greebo".to_string(),
+                    person_id: Some(PersonID("7".to_string())),
+                },
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        runtime
+            .block_on(store.update_person(
+                Person {
+                    last_name: "Weatherwax".to_string(),
+                    ..person
+                },
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        runtime
+            .block_on(store.delete_person(
+                PersonID("7".to_string()),
+                DeletePolicy::Cascade,
+                &AuditContext::default(),
+            ))
+            .unwrap();
+        let recorded = runtime.block_on(store.get_pending_events(10)).unwrap();
+        runtime
+            .block_on(store.mark_events_sent(&[recorded[0].id, recorded[1].id]))
+            .unwrap();
+        // When
+        let got = runtime.block_on(store.get_pending_events(10)).unwrap();
+        // Then
+        let recorded: Vec<(EventKind, &str)> = recorded
+            .iter()
+            .map(|event| (event.kind, event.aggregate_id.as_str()))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (EventKind::PersonCreated, "7"),
+                (EventKind::PetAdded, "10"),
+                (EventKind::PersonUpdated, "7"),
+                (EventKind::PetDeleted, "10"),
+                (EventKind::PersonDeleted, "7"),
+            ]
+        );
+        let got_kinds: Vec<EventKind> = got.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            got_kinds,
+            vec![
+                EventKind::PersonUpdated,
+                EventKind::PetDeleted,
+                EventKind::PersonDeleted
+            ]
+        );
+        assert_eq!(got[0].payload["last_name"], "Weatherwax");
+        assert!(got.iter().all(|event| event.sent_at.is_none()));
+    }
+
+    #[test]
+    fn test_get_events_after() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let got_empty = runtime.block_on(store.get_last_event_id()).unwrap();
+        for id in ["7", "8", "9"] {
+            runtime
+                .block_on(store.add_person(
+                    Person::new(
+                        PersonID(id.to_string()),
+                        "Esme".to_string(),
+                        "Ogg".to_string(),
+                    ),
+                    &AuditContext::default(),
+                ))
+                .unwrap();
+        }
+        let pending = runtime.block_on(store.get_pending_events(1)).unwrap();
+        runtime
+            .block_on(store.mark_events_sent(&[pending[0].id]))
+            .unwrap();
+        let last = runtime.block_on(store.get_last_event_id()).unwrap();
+        // When
+        let got = runtime
+            .block_on(store.get_events_after(pending[0].id - 1, 2))
+            .unwrap();
+        let got_none = runtime.block_on(store.get_events_after(last, 10)).unwrap();
+        // Then
+        assert_eq!(got_empty, 0);
+        let got: Vec<&str> = got
+            .iter()
+            .map(|event| event.aggregate_id.as_str())
+            .collect();
+        assert_eq!(got, vec!["7", "8"]);
+        assert!(got_none.is_empty());
+    }
+
+    #[test]
+    fn test_webhooks_and_deliveries() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let store = runtime.block_on(new_store());
+        let new_webhook = |url: &str| {
+            NewWebhook {
+                url: url.to_string(),
+                secret: "a-long-enough-secret".to_string(),
+                events: vec![EventKind::PetAdded],
+            }
+            .to_webhook()
+        };
+        let delivery =
+            |webhook: &Webhook, id: &str, status: DeliveryStatus, seconds: i64| WebhookDelivery {
+                id: id.to_string(),
+                webhook_id: webhook.id.clone(),
+                event_id: seconds,
+                kind: EventKind::PetAdded,
+                payload: serde_json::json!({"event_id": seconds}),
+                status,
+                response_status: Some(500),
+                error: None,
+                delivered_at: Utc::now() + chrono::Duration::seconds(seconds),
+            };
+        let kept = runtime
+            .block_on(store.add_webhook(new_webhook("https://kept.example.com")))
+            .unwrap();
+        let deleted = runtime
+            .block_on(store.add_webhook(new_webhook("https://deleted.example.com")))
+            .unwrap();
+        for saved in [
+            delivery(&kept, "d1", DeliveryStatus::Failed, 1),
+            delivery(&kept, "d2", DeliveryStatus::Delivered, 2),
+            delivery(&deleted, "d3", DeliveryStatus::Delivered, 3),
+        ] {
+            runtime.block_on(store.add_webhook_delivery(saved)).unwrap();
+        }
+        runtime
+            .block_on(store.delete_webhook(deleted.id.clone()))
+            .unwrap();
+        // When
+        let got_webhooks = runtime.block_on(store.get_webhooks()).unwrap();
+        let got_deliveries = runtime
+            .block_on(store.get_webhook_deliveries(kept.id.clone(), 10))
+            .unwrap();
+        let got_delivery = runtime.block_on(store.get_webhook_delivery("d1".to_string()));
+        let got_deleted_delivery = runtime.block_on(store.get_webhook_delivery("d3".to_string()));
+        let got_deleted_again = runtime.block_on(store.delete_webhook(deleted.id.clone()));
+        let got_orphan = runtime.block_on(store.add_webhook_delivery(delivery(
+            &deleted,
+            "d4",
+            DeliveryStatus::Delivered,
+            4,
+        )));
+        // Then
+        assert_eq!(got_webhooks.len(), 1);
+        assert_eq!(got_webhooks[0].id, kept.id);
+        assert_eq!(got_webhooks[0].secret, "a-long-enough-secret");
+        assert_eq!(got_webhooks[0].events, vec![EventKind::PetAdded]);
+        let got_ids: Vec<&str> = got_deliveries
+            .iter()
+            .map(|delivery| delivery.id.as_str())
+            .collect();
+        assert_eq!(got_ids, vec!["d2", "d1"]);
+        let got_delivery = got_delivery.unwrap();
+        assert_eq!(got_delivery.status, DeliveryStatus::Failed);
+        assert_eq!(got_delivery.response_status, Some(500));
+        assert_eq!(got_delivery.payload["event_id"], 1);
+        assert_eq!(got_deleted_delivery, Err(Error::WebhookDeliveryNotFound));
+        assert_eq!(got_deleted_again, Err(Error::WebhookNotFound));
+        assert_eq!(got_orphan, Err(Error::WebhookNotFound));
+    }
+
+    async fn new_store() -> Store {
+        Store::new("sqlite::memory:")
+            .await
+            .expect("unable to create sqlite store")
+    }
+}
@@ -0,0 +1,85 @@
+use crate::errors::error::Error;
+use crate::people::storage::PeopleStream;
+use crate::types::{
+    people::{Person, PersonWithPets},
+    pets::Pet,
+};
+use futures::stream::{self, Stream, StreamExt};
+use std::future::Future;
+use tokio::sync::mpsc::{self, Sender};
+use tracing::{debug, error};
+
+/// The most people read ahead of the client, a slow client slows down the
+/// reads instead of filling the memory.
+const STREAM_BUFFER: usize = 64;
+
+/// people_stream runs the given producer in its own task and returns the
+/// people it sends.
+pub fn people_stream<F, Fut>(produce: F) -> PeopleStream
+where
+    F: FnOnce(Sender<Result<PersonWithPets, Error>>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    let producer = tokio::spawn(produce(tx));
+
+    stream::unfold((rx, Some(producer)), |(mut rx, producer)| async move {
+        if let Some(person) = rx.recv().await {
+            return Some((person, (rx, producer)));
+        }
+
+        // a producer which panicked must not look like the end of the people.
+        match producer?.await {
+            Ok(_) => None,
+            Err(e) => {
+                error!("people stream producer failed: {:?}", e);
+                Some((Err(Error::DatabaseQueryError), (rx, None)))
+            }
+        }
+    })
+    .boxed()
+}
+
+/// send_grouped sends the people of rows ordered by person, every row has a
+/// person and one of their pets, if any, so the rows of a person are merged.
+pub async fn send_grouped<S>(rows: S, include_pets: bool, tx: Sender<Result<PersonWithPets, Error>>)
+where
+    S: Stream<Item = Result<(Person, Option<Pet>), Error>>,
+{
+    let mut rows = Box::pin(rows);
+    let mut current: Option<PersonWithPets> = None;
+
+    while let Some(row) = rows.next().await {
+        let (person, pet) = match row {
+            Ok(row) => row,
+            Err(e) => {
+                let _ = tx.send(Err(e)).await;
+                return;
+            }
+        };
+
+        match current.as_mut() {
+            Some(last) if last.person.id == person.id => {
+                if let (Some(pets), Some(pet)) = (last.pets.as_mut(), pet) {
+                    pets.push(pet);
+                }
+            }
+            _ => {
+                let next = PersonWithPets {
+                    person,
+                    pets: include_pets.then(|| pet.into_iter().collect()),
+                };
+                if let Some(last) = current.replace(next) {
+                    if tx.send(Ok(last)).await.is_err() {
+                        debug!("people stream was dropped by the client");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(last) = current {
+        let _ = tx.send(Ok(last)).await;
+    }
+}
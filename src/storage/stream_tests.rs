@@ -0,0 +1,70 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod stream_tests {
+    use crate::errors::error::Error;
+    use crate::storage::stream;
+    use crate::types::people::{Person, PersonID, PersonWithPets};
+    use crate::types::pets::{Pet, PetID};
+    use futures::StreamExt;
+    use tokio::runtime::Runtime;
+
+    fn rows() -> Vec<Result<(Person, Option<Pet>), Error>> {
+        let luis = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        let esme = Person::new(
+            PersonID("2".to_string()),
+            "Esme".to_string(),
+            "Esme".to_string(),
+        );
+        let pet = |id: &str| Pet {
+            id: PetID(id.to_string()),
+            name: "Firulais".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        vec![
+            Ok((luis.clone(), Some(pet("10")))),
+            Ok((luis, Some(pet("11")))),
+            Ok((esme, None)),
+        ]
+    }
+
+    #[test]
+    fn test_people_stream_groups_pets() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        // When
+        let got: Vec<Result<PersonWithPets, Error>> = runtime.block_on(async {
+            stream::people_stream(|tx| {
+                stream::send_grouped(futures::stream::iter(rows()), true, tx)
+            })
+            .collect()
+            .await
+        });
+        // Then
+        let got: Vec<(String, usize)> = got
+            .into_iter()
+            .map(|person| person.unwrap())
+            .map(|person| (person.person.id.to_string(), person.pets.unwrap().len()))
+            .collect();
+        assert_eq!(got, vec![("1".to_string(), 2), ("2".to_string(), 0)]);
+    }
+
+    #[test]
+    fn test_people_stream_stops_on_error() {
+        // Given
+        let runtime = Runtime::new().expect("Unable to create a runtime");
+        let mut rows = rows();
+        rows.insert(2, Err(Error::DatabaseQueryError));
+        // When
+        let got: Vec<Result<PersonWithPets, Error>> = runtime.block_on(async {
+            stream::people_stream(|tx| stream::send_grouped(futures::stream::iter(rows), false, tx))
+                .collect()
+                .await
+        });
+        // Then
+        assert_eq!(got, vec![Err(Error::DatabaseQueryError)]);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::errors::error::Error;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+use crate::types::{
+    accounts::{Account, AccountID},
+    audit::AuditEntry,
+    events::DomainEvent,
+    history::PersonVersion,
+    merges::Merge,
+    people::{Person, PersonID},
+    pets::{Pet, PetID},
+    relationships::Relationship,
+    webhooks::{Webhook, WebhookDelivery, WebhookID},
+};
+
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const LOG_FILE: &str = "wal.ndjson";
+
+/// Mutation is a change applied to the memory store, it is written to the log
+/// before it is applied so it can be replayed after a crash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", content = "data")]
+pub enum Mutation {
+    PersonSaved(Person),
+    PersonDeleted(PersonID),
+    PersonVersionSaved(PersonVersion),
+    PetSaved(Pet),
+    PetDeleted(PetID),
+    RelationshipSaved(Relationship),
+    RelationshipDeleted(Relationship),
+    PeopleMerged(Merge),
+    AuditEntryAdded(AuditEntry),
+    EventRecorded(DomainEvent),
+    EventsSent {
+        ids: Vec<i64>,
+        sent_at: DateTime<Utc>,
+    },
+    WebhookSaved(Webhook),
+    WebhookDeleted(WebhookID),
+    WebhookDeliveryAdded(WebhookDelivery),
+    AccountSaved(Account),
+}
+
+/// Snapshot is the whole content of the memory store at a given moment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(default)]
+    pub people: HashMap<PersonID, Person>,
+    #[serde(default)]
+    pub pets: HashMap<PetID, Pet>,
+    /// The relationships of every person, by person
+    #[serde(default)]
+    pub relationships: HashMap<PersonID, Vec<Relationship>>,
+    /// The merges of people into other people, by merged person
+    #[serde(default)]
+    pub merges: HashMap<PersonID, Merge>,
+    /// The audit log, oldest entry first
+    #[serde(default)]
+    pub audit: Vec<AuditEntry>,
+    #[serde(default)]
+    pub accounts: HashMap<AccountID, Account>,
+    /// The versions of every person, oldest first
+    #[serde(default)]
+    pub history: HashMap<PersonID, Vec<PersonVersion>>,
+    /// The domain events of the outbox, oldest first
+    #[serde(default)]
+    pub outbox: Vec<DomainEvent>,
+    #[serde(default)]
+    pub webhooks: HashMap<WebhookID, Webhook>,
+    /// The deliveries of every webhook, oldest first
+    #[serde(default)]
+    pub webhook_deliveries: Vec<WebhookDelivery>,
+}
+
+/// Wal keeps a snapshot file and an append only log of the mutations done
+/// after that snapshot, both inside the same directory. Every line of the log
+/// holds the mutations of one change to the store, so a change is replayed
+/// whole or not at all.
+#[derive(Debug)]
+pub struct Wal {
+    dir: PathBuf,
+    log: File,
+}
+
+impl Snapshot {
+    /// every mutation is an upsert or a delete, so replaying a mutation that
+    /// is already part of the snapshot is harmless.
+    pub fn apply(&mut self, mutation: Mutation) {
+        match mutation {
+            Mutation::PersonSaved(person) => {
+                self.people.insert(person.id.clone(), person);
+            }
+            Mutation::PersonDeleted(person_id) => {
+                self.people.remove(&person_id);
+                self.history.remove(&person_id);
+            }
+            Mutation::PersonVersionSaved(version) => {
+                // deleting and restoring keep the version number, so the
+                // whole version tells whether it was saved already.
+                let versions = self.history.entry(version.person.id.clone()).or_default();
+                if !versions.contains(&version) {
+                    versions.push(version);
+                }
+            }
+            Mutation::PetSaved(pet) => {
+                self.pets.insert(pet.id.clone(), pet);
+            }
+            Mutation::PetDeleted(pet_id) => {
+                self.pets.remove(&pet_id);
+            }
+            Mutation::RelationshipSaved(relationship) => {
+                let relationships = self
+                    .relationships
+                    .entry(relationship.person_id.clone())
+                    .or_default();
+                relationships.retain(|saved| saved.related_id != relationship.related_id);
+                relationships.push(relationship);
+            }
+            Mutation::RelationshipDeleted(relationship) => {
+                if let Some(relationships) = self.relationships.get_mut(&relationship.person_id) {
+                    relationships.retain(|saved| saved.related_id != relationship.related_id);
+                }
+            }
+            Mutation::PeopleMerged(merge) => {
+                self.merges.insert(merge.source_id.clone(), merge);
+            }
+            Mutation::AuditEntryAdded(entry) => {
+                if !self.audit.iter().any(|saved| saved.id == entry.id) {
+                    self.audit.push(entry);
+                }
+            }
+            Mutation::EventRecorded(event) => {
+                if !self.outbox.iter().any(|saved| saved.id == event.id) {
+                    self.outbox.push(event);
+                }
+            }
+            Mutation::EventsSent { ids, sent_at } => {
+                for event in self.outbox.iter_mut() {
+                    if ids.contains(&event.id) {
+                        event.sent_at = Some(sent_at);
+                    }
+                }
+            }
+            Mutation::WebhookSaved(webhook) => {
+                self.webhooks.insert(webhook.id.clone(), webhook);
+            }
+            Mutation::WebhookDeleted(webhook_id) => {
+                self.webhooks.remove(&webhook_id);
+                self.webhook_deliveries
+                    .retain(|delivery| delivery.webhook_id != webhook_id);
+            }
+            Mutation::WebhookDeliveryAdded(delivery) => {
+                if !self
+                    .webhook_deliveries
+                    .iter()
+                    .any(|saved| saved.id == delivery.id)
+                {
+                    self.webhook_deliveries.push(delivery);
+                }
+            }
+            Mutation::AccountSaved(account) => {
+                self.accounts.insert(account.id.clone(), account);
+            }
+        }
+    }
+}
+
+impl Wal {
+    /// open loads the snapshot found in the given directory, replays the log on
+    /// top of it and compacts both, so the store starts from a clean state.
+    pub async fn open(dir: &Path) -> Result<(Wal, Snapshot), Error> {
+        fs::create_dir_all(dir).await.map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "creating data directory: {e}");
+            Error::DatabaseConnectionError
+        })?;
+
+        let mut snapshot = read_snapshot(&dir.join(SNAPSHOT_FILE)).await?;
+
+        let log_path = dir.join(LOG_FILE);
+        let changes = read_log(&log_path).await?;
+        debug!("replaying {} changes from {:?}", changes.len(), log_path);
+        for mutation in changes.into_iter().flatten() {
+            snapshot.apply(mutation);
+        }
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .await
+            .map_err(|e| {
+                tracing::event!(tracing::Level::ERROR, "opening write-ahead log: {e}");
+                Error::DatabaseConnectionError
+            })?;
+
+        let mut wal = Wal {
+            dir: dir.to_path_buf(),
+            log,
+        };
+        wal.compact(&snapshot).await?;
+
+        Ok((wal, snapshot))
+    }
+
+    /// append writes the mutations of one change to the log, as a single line,
+    /// and waits until it is on disk.
+    pub async fn append(&mut self, mutations: &[Mutation]) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(mutations).map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "encoding mutation: {e}");
+            Error::DatabaseQueryError
+        })?;
+        line.push(b'\n');
+
+        self.log.write_all(&line).await.map_err(log_write_error)?;
+        self.log.sync_data().await.map_err(log_write_error)
+    }
+
+    /// compact replaces the snapshot file with the given one and empties the log.
+    pub async fn compact(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        let content = serde_json::to_vec(snapshot).map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "encoding snapshot: {e}");
+            Error::DatabaseQueryError
+        })?;
+
+        // write to a temporary file first, a rename is atomic so a crash never
+        // leaves a half written snapshot behind.
+        let tmp_path = self.dir.join(format!("{SNAPSHOT_FILE}.tmp"));
+        let mut tmp_file = File::create(&tmp_path).await.map_err(log_write_error)?;
+        tmp_file
+            .write_all(&content)
+            .await
+            .map_err(log_write_error)?;
+        tmp_file.sync_all().await.map_err(log_write_error)?;
+        fs::rename(&tmp_path, self.dir.join(SNAPSHOT_FILE))
+            .await
+            .map_err(log_write_error)?;
+
+        self.log.set_len(0).await.map_err(log_write_error)?;
+        self.log.sync_all().await.map_err(log_write_error)
+    }
+}
+
+async fn read_snapshot(path: &Path) -> Result<Snapshot, Error> {
+    match fs::read(path).await {
+        Ok(content) => serde_json::from_slice(&content).map_err(|e| {
+            tracing::event!(tracing::Level::ERROR, "reading snapshot {:?}: {e}", path);
+            Error::DatabaseConnectionError
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Snapshot::default()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "reading snapshot {:?}: {e}", path);
+            Err(Error::DatabaseConnectionError)
+        }
+    }
+}
+
+async fn read_log(path: &Path) -> Result<Vec<Vec<Mutation>>, Error> {
+    let content = match fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "reading write-ahead log: {e}");
+            return Err(Error::DatabaseConnectionError);
+        }
+    };
+
+    let mut changes = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(mutations) => changes.push(mutations),
+            Err(e) => {
+                // a crash in the middle of an append leaves a broken last line,
+                // that change was never acknowledged so it is safe to drop it.
+                warn!("ignoring write-ahead log from line {}: {}", number + 1, e);
+                break;
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+fn log_write_error(e: std::io::Error) -> Error {
+    tracing::event!(tracing::Level::ERROR, "writing memory storage files: {e}");
+    Error::DatabaseQueryError
+}
@@ -8,12 +8,21 @@ use std::{
     str::FromStr,
 };
 
+use crate::errors::error;
+
+/// The key the login tokens are encrypted with
+const TOKEN_KEY: &[u8] = b"RANDOM WORDS WINTER MACINTOSH PC";
+/// The footer every login token carries
+const TOKEN_FOOTER: &str = "key-id:gandalf0";
+/// The claim of the token with the account id
+const ACCOUNT_CLAIM: &str = "go-to";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SaveAccountSuccess {
     pub id: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct AccountID(pub String);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -42,7 +51,24 @@ impl Account {
 
     pub fn issue_token(&self) -> String {
         let state = serde_json::to_string(&self.id).expect("failed to serialize state");
-        local_paseto(state, "RANDOM WORDS WINTER MACINTOSH PC".as_bytes())
+        local_paseto(state, TOKEN_KEY)
+    }
+
+    /// verify_token returns the id of the account a login token was issued
+    /// to, if the token is valid and did not expire.
+    pub fn verify_token(token: &str) -> Result<AccountID, error::Error> {
+        let claims = paseto::tokens::validate_local_token(
+            token,
+            Some(TOKEN_FOOTER),
+            TOKEN_KEY,
+            &paseto::tokens::TimeBackend::Chrono,
+        )
+        .map_err(|_| error::Error::InvalidToken)?;
+
+        claims[ACCOUNT_CLAIM]
+            .as_str()
+            .and_then(|state| serde_json::from_str(state).ok())
+            .ok_or(error::Error::InvalidToken)
     }
 }
 
@@ -132,8 +158,8 @@ fn local_paseto(state: String, encryption_key: &[u8]) -> String {
         .set_jti("gandalf0")
         .set_not_before(&Utc::now())
         .set_subject("gandalf")
-        .set_claim("go-to", json!(state))
-        .set_footer("key-id:gandalf0")
+        .set_claim(ACCOUNT_CLAIM, json!(state))
+        .set_footer(TOKEN_FOOTER)
         .build()
         .expect("Failed to construct paseto token w/ builder!")
 }
@@ -142,6 +168,21 @@ fn local_paseto(state: String, encryption_key: &[u8]) -> String {
 mod account_test {
     use crate::types::accounts;
 
+    #[test]
+    fn test_verify_token() {
+        // Given
+        let account =
+            accounts::NewAccount::new("esme@anydomain.com".to_string(), "123456".to_string())
+                .to_account();
+        let token = account.issue_token();
+        // When
+        let got = accounts::Account::verify_token(&token);
+        let got_invalid = accounts::Account::verify_token("v2.local.not-a-token");
+        // Then
+        assert_eq!(got, Ok(account.id));
+        assert_eq!(got_invalid, Err(crate::errors::error::Error::InvalidToken));
+    }
+
     #[test]
     fn test_hash_password() {
         // Given
@@ -149,6 +190,6 @@ mod account_test {
         // When
         let got = accounts::hash(a_password.as_bytes());
         // Then
-        assert_eq!(false, got == a_password);
+        assert!(got != a_password);
     }
 }
@@ -0,0 +1,264 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::errors::error;
+use crate::types::accounts::AccountID;
+use crate::types::pagination::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::types::people::Person;
+use crate::types::pets::Pet;
+
+/// AuditEntity is the kind of record an audit entry is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEntity {
+    Person,
+    Pet,
+}
+
+/// AuditAction is what was done to the record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    /// The person was merged into another one
+    Merge,
+    /// The deleted person was removed for good
+    Purge,
+}
+
+/// AuditContext is who makes the request and how the request is known, every
+/// audit entry written while serving it carries both.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditContext {
+    /// The account of the bearer token, none for anonymous requests
+    pub actor: Option<AccountID>,
+    pub request_id: String,
+}
+
+/// AuditEntry records a change of a person or a pet, with the record as it
+/// was before and after the change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub entity: AuditEntity,
+    pub entity_id: String,
+    pub action: AuditAction,
+    pub actor: Option<AccountID>,
+    pub request_id: String,
+    pub at: DateTime<Utc>,
+    /// None when the record did not exist before, like when it is created
+    pub before: Option<serde_json::Value>,
+    /// None when the record does not exist after, like when it is deleted
+    pub after: Option<serde_json::Value>,
+}
+
+/// AuditQuery is how the audit log is filtered, every given filter has to
+/// match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditQuery {
+    pub entity_id: Option<String>,
+    pub actor: Option<AccountID>,
+    /// Only the entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only the entries before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: i32,
+}
+
+impl AuditContext {
+    pub fn new(actor: Option<AccountID>, request_id: String) -> Self {
+        AuditContext { actor, request_id }
+    }
+
+    /// entry returns the audit entry of the action done to the record in this
+    /// context.
+    pub fn entry<T: Serialize>(
+        &self,
+        entity: AuditEntity,
+        entity_id: impl ToString,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> AuditEntry {
+        let snapshot = |record: &T| serde_json::to_value(record).ok();
+        AuditEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            entity,
+            entity_id: entity_id.to_string(),
+            action,
+            actor: self.actor.clone(),
+            request_id: self.request_id.clone(),
+            at: Utc::now(),
+            before: before.and_then(snapshot),
+            after: after.and_then(snapshot),
+        }
+    }
+
+    /// person_entry returns the audit entry of the action done to the person,
+    /// who is before or after the action.
+    pub fn person_entry(
+        &self,
+        action: AuditAction,
+        before: Option<&Person>,
+        after: Option<&Person>,
+    ) -> AuditEntry {
+        let person_id = after.or(before).map(|person| person.id.to_string());
+        self.entry(
+            AuditEntity::Person,
+            person_id.unwrap_or_default(),
+            action,
+            before,
+            after,
+        )
+    }
+
+    /// pet_entry returns the audit entry of the action done to the pet, which
+    /// is before or after the action.
+    pub fn pet_entry(
+        &self,
+        action: AuditAction,
+        before: Option<&Pet>,
+        after: Option<&Pet>,
+    ) -> AuditEntry {
+        let pet_id = after.or(before).map(|pet| pet.id.to_string());
+        self.entry(
+            AuditEntity::Pet,
+            pet_id.unwrap_or_default(),
+            action,
+            before,
+            after,
+        )
+    }
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        AuditQuery {
+            entity_id: None,
+            actor: None,
+            since: None,
+            until: None,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl AuditQuery {
+    /// matches tells whether the entry passes every filter of the query.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.entity_id
+            .as_ref()
+            .is_none_or(|entity_id| *entity_id == entry.entity_id)
+            && self
+                .actor
+                .as_ref()
+                .is_none_or(|actor| entry.actor.as_ref() == Some(actor))
+            && self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at < until)
+    }
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Person => "person",
+            AuditEntity::Pet => "pet",
+        }
+    }
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Merge => "merge",
+            AuditAction::Purge => "purge",
+        }
+    }
+}
+
+impl fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = error::Error;
+
+    fn from_str(entity: &str) -> Result<Self, Self::Err> {
+        match entity {
+            "person" => Ok(AuditEntity::Person),
+            "pet" => Ok(AuditEntity::Pet),
+            _ => Err(error::Error::InvalidAuditQuery(format!(
+                "unknown entity {entity}"
+            ))),
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = error::Error;
+
+    fn from_str(action: &str) -> Result<Self, Self::Err> {
+        match action {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            "restore" => Ok(AuditAction::Restore),
+            "merge" => Ok(AuditAction::Merge),
+            "purge" => Ok(AuditAction::Purge),
+            _ => Err(error::Error::InvalidAuditQuery(format!(
+                "unknown action {action}"
+            ))),
+        }
+    }
+}
+
+/// extract_audit_query reads the entity_id, actor, since and until filters
+/// and the limit of the audit log, times are RFC 3339.
+pub fn extract_audit_query(params: &HashMap<String, String>) -> Result<AuditQuery, error::Error> {
+    let time = |name: &str| -> Result<Option<DateTime<Utc>>, error::Error> {
+        params
+            .get(name)
+            .map(|value| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| {
+                        error::Error::InvalidAuditQuery(format!(
+                            "{name} {value} is not an RFC 3339 time"
+                        ))
+                    })
+            })
+            .transpose()
+    };
+
+    let limit = match params.get("limit") {
+        Some(limit) => limit
+            .parse::<i32>()
+            .map_err(error::Error::ParseError)?
+            .clamp(1, MAX_PAGE_SIZE),
+        None => DEFAULT_PAGE_SIZE,
+    };
+
+    Ok(AuditQuery {
+        entity_id: params.get("entity_id").cloned(),
+        actor: params.get("actor").map(|actor| AccountID(actor.clone())),
+        since: time("since")?,
+        until: time("until")?,
+        limit,
+    })
+}
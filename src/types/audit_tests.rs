@@ -0,0 +1,96 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod audit_tests {
+    use crate::errors::error;
+    use crate::types::accounts::AccountID;
+    use crate::types::audit::{
+        extract_audit_query, AuditAction, AuditContext, AuditEntity, AuditQuery,
+    };
+    use crate::types::people::{Person, PersonID};
+    use chrono::Duration;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_audit_entry() {
+        // Given
+        let context = AuditContext::new(Some(AccountID("1".to_string())), "abc".to_string());
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        // When
+        let got = context.entry(
+            AuditEntity::Person,
+            &person.id,
+            AuditAction::Create,
+            None,
+            Some(&person),
+        );
+        // Then
+        assert_eq!(got.entity_id, "1");
+        assert_eq!(got.actor, Some(AccountID("1".to_string())));
+        assert_eq!(got.request_id, "abc");
+        assert_eq!(got.before, None);
+        assert_eq!(got.after.unwrap()["last_name"], "Weatherwax");
+    }
+
+    #[test]
+    fn test_audit_query_matches() {
+        // Given
+        let context = AuditContext::new(Some(AccountID("1".to_string())), "abc".to_string());
+        let entry =
+            context.entry::<Person>(AuditEntity::Person, "7", AuditAction::Delete, None, None);
+        let query = |entity_id: &str, actor: &str| AuditQuery {
+            entity_id: Some(entity_id.to_string()),
+            actor: Some(AccountID(actor.to_string())),
+            since: Some(entry.at),
+            until: Some(entry.at + Duration::seconds(1)),
+            ..Default::default()
+        };
+        // When
+        let got = query("7", "1").matches(&entry);
+        let got_other_entity = query("8", "1").matches(&entry);
+        let got_other_actor = query("7", "2").matches(&entry);
+        let got_until = AuditQuery {
+            until: Some(entry.at),
+            ..Default::default()
+        }
+        .matches(&entry);
+        // Then
+        assert!(got);
+        assert!(!got_other_entity);
+        assert!(!got_other_actor);
+        assert!(!got_until);
+    }
+
+    #[test]
+    fn test_extract_audit_query() {
+        // Given
+        let params = HashMap::from([
+            ("entity_id".to_string(), "7".to_string()),
+            ("actor".to_string(), "1".to_string()),
+            ("since".to_string(), "2026-10-18T10:00:00+02:00".to_string()),
+            ("limit".to_string(), "500".to_string()),
+        ]);
+        let bad_params = HashMap::from([("until".to_string(), "yesterday".to_string())]);
+        // When
+        let got = extract_audit_query(&params).unwrap();
+        let got_bad = extract_audit_query(&bad_params);
+        // Then
+        assert_eq!(got.entity_id, Some("7".to_string()));
+        assert_eq!(got.actor, Some(AccountID("1".to_string())));
+        assert_eq!(
+            got.since.map(|since| since.to_rfc3339()),
+            Some("2026-10-18T08:00:00+00:00".to_string())
+        );
+        assert_eq!(got.until, None);
+        assert_eq!(got.limit, 100);
+        assert_eq!(
+            got_bad,
+            Err(error::Error::InvalidAuditQuery(
+                "until yesterday is not an RFC 3339 time".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::errors::error;
+use crate::types::people::{NewPerson, Person};
+use serde::{Deserialize, Serialize};
+
+/// The most people a single batch can add
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// BatchItem is what happened to one person of a batch, in the position it
+/// had in the request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchItem {
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person: Option<Person>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchResult {
+    pub created: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItem>,
+}
+
+impl BatchResult {
+    pub fn new(results: Vec<Result<Person, String>>) -> Self {
+        let results: Vec<BatchItem> = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| match result {
+                Ok(person) => BatchItem {
+                    index,
+                    person: Some(person),
+                    error: None,
+                },
+                Err(error) => BatchItem {
+                    index,
+                    person: None,
+                    error: Some(error),
+                },
+            })
+            .collect();
+        let created = results.iter().filter(|item| item.person.is_some()).count();
+
+        BatchResult {
+            created,
+            failed: results.len() - created,
+            results,
+        }
+    }
+}
+
+/// parse_new_people reads a JSON array or a stream of newline delimited JSON
+/// people, a person that cannot be read fails alone.
+pub fn parse_new_people(body: &[u8]) -> Result<Vec<Result<NewPerson, String>>, error::Error> {
+    let is_array = body
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| *byte == b'[');
+
+    let items: Vec<Result<NewPerson, String>> = if is_array {
+        serde_json::from_slice::<Vec<serde_json::Value>>(body)
+            .map_err(|_| error::Error::InvalidBatch)?
+            .into_iter()
+            .map(|value| serde_json::from_value(value).map_err(|e| e.to_string()))
+            .collect()
+    } else {
+        std::str::from_utf8(body)
+            .map_err(|_| error::Error::InvalidBatch)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| e.to_string()))
+            .collect()
+    };
+
+    if items.len() > MAX_BATCH_SIZE {
+        return Err(error::Error::BatchTooLarge);
+    }
+
+    Ok(items)
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod batch_tests {
+    use crate::errors::error;
+    use crate::types::batch;
+    use crate::types::people::{NewPerson, Person, PersonID};
+
+    #[test]
+    fn test_parse_new_people_json_array() {
+        // Given
+        let body = br#"[{"first_name":"Luis","last_name":"Luis"},{"first_name":"Esme"}]"#;
+        // When
+        let got = batch::parse_new_people(body).unwrap();
+        // Then
+        assert_eq!(got.len(), 2);
+        assert_eq!(
+            got[0],
+            Ok(NewPerson::new("Luis".to_string(), "Luis".to_string()))
+        );
+        assert!(got[1].is_err());
+    }
+
+    #[test]
+    fn test_parse_new_people_ndjson() {
+        // Given
+        let body = b"{\"first_name\":\"Luis\",\"last_name\":\"Luis\"}\n\nnot a person\n{\"first_name\":\"Esme\",\"last_name\":\"Esme\"}\n";
+        // When
+        let got = batch::parse_new_people(body).unwrap();
+        // Then
+        assert_eq!(got.len(), 3);
+        assert!(got[0].is_ok());
+        assert!(got[1].is_err());
+        assert_eq!(
+            got[2],
+            Ok(NewPerson::new("Esme".to_string(), "Esme".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_new_people_invalid_array() {
+        // Given
+        let body = b"  [{\"first_name\":\"Luis\"";
+        // When
+        let got = batch::parse_new_people(body);
+        // Then
+        assert_eq!(got, Err(error::Error::InvalidBatch));
+    }
+
+    #[test]
+    fn test_parse_new_people_too_many() {
+        // Given
+        let body =
+            "{\"first_name\":\"Luis\",\"last_name\":\"Luis\"}\n".repeat(batch::MAX_BATCH_SIZE + 1);
+        // When
+        let got = batch::parse_new_people(body.as_bytes());
+        // Then
+        assert_eq!(got, Err(error::Error::BatchTooLarge));
+    }
+
+    #[test]
+    fn test_batch_result() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Luis".to_string(),
+            "Luis".to_string(),
+        );
+        // When
+        let got = batch::BatchResult::new(vec![Ok(person.clone()), Err("bad words".to_string())]);
+        // Then
+        assert_eq!(got.created, 1);
+        assert_eq!(got.failed, 1);
+        assert_eq!(got.results[0].person, Some(person));
+        assert_eq!(got.results[1].index, 1);
+        assert_eq!(got.results[1].error, Some("bad words".to_string()));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::errors::error;
+use crate::types::batch::MAX_BATCH_SIZE;
+use crate::types::people::{NewPerson, Person, PersonID};
+use crate::types::pets::Pet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const PEOPLE_HEADER: [&str; 5] = ["id", "first_name", "last_name", "created_on", "version"];
+const PET_HEADER: [&str; 2] = ["pet_id", "pet_name"];
+
+/// Record is a row of a CSV file and the line it starts at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// ImportRow is a person to import with the names of its pets, the rows
+/// sharing the same id column are merged into one person.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    pub line: usize,
+    pub new_person: NewPerson,
+    pub pet_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportedPerson {
+    pub line: usize,
+    pub person: Person,
+    pub pets: Vec<Pet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub failed: usize,
+    pub people: Vec<ImportedPerson>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportResult {
+    pub fn new(mut people: Vec<ImportedPerson>, mut errors: Vec<RowError>) -> Self {
+        people.sort_by_key(|imported| imported.line);
+        errors.sort_by_key(|row_error| row_error.line);
+
+        ImportResult {
+            imported: people.len(),
+            failed: errors.len(),
+            people,
+            errors,
+        }
+    }
+}
+
+/// parse reads the records of a CSV text, quoted fields can hold commas,
+/// quotes and line breaks.
+pub fn parse(text: &str) -> Result<Vec<Record>, error::Error> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (true, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                fields.push(std::mem::take(&mut field));
+                push_record(&mut records, record_line, std::mem::take(&mut fields));
+                line += 1;
+                record_line = line;
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(error::Error::InvalidCsv(format!(
+            "quoted field starting at line {record_line} is not closed"
+        )));
+    }
+
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        push_record(&mut records, record_line, fields);
+    }
+
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<Record>, line: usize, fields: Vec<String>) {
+    // blank lines are not records.
+    if fields.len() == 1 && fields[0].is_empty() {
+        return;
+    }
+    records.push(Record { line, fields });
+}
+
+/// write_row appends a CSV row, quoting the fields which need it. A field a
+/// spreadsheet would run as a formula is prefixed with a quote.
+pub fn write_row(out: &mut String, fields: &[&str]) {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let field = match field.starts_with(['=', '+', '-', '@']) {
+                true => format!("'{field}"),
+                false => field.to_string(),
+            };
+            match field.contains([',', '"', '\n', '\r']) {
+                true => format!("\"{}\"", field.replace('"', "\"\"")),
+                false => field,
+            }
+        })
+        .collect();
+
+    out.push_str(&row.join(","));
+    out.push_str("\r\n");
+}
+
+/// export_people writes people as CSV, with pets there is a row for every pet
+/// of a person and a row with empty pet columns for people without pets.
+pub fn export_people(people: &[Person], pets: Option<&[Pet]>) -> String {
+    let mut out = String::new();
+    let mut header = PEOPLE_HEADER.to_vec();
+    if pets.is_some() {
+        header.extend(PET_HEADER);
+    }
+    write_row(&mut out, &header);
+
+    let mut pets_by_owner: HashMap<&PersonID, Vec<&Pet>> = HashMap::new();
+    for pet in pets.unwrap_or_default() {
+        if let Some(owner) = &pet.person_id {
+            pets_by_owner.entry(owner).or_default().push(pet);
+        }
+    }
+
+    for person in people {
+        let created_on = person.created_on.to_rfc3339();
+        let version = person.version.to_string();
+        let person_fields = [
+            person.id.0.as_str(),
+            person.first_name.as_str(),
+            person.last_name.as_str(),
+            created_on.as_str(),
+            version.as_str(),
+        ];
+
+        if pets.is_none() {
+            write_row(&mut out, &person_fields);
+            continue;
+        }
+
+        let owned = pets_by_owner.remove(&person.id).unwrap_or_default();
+        if owned.is_empty() {
+            write_row(&mut out, &[&person_fields[..], &["", ""]].concat());
+        }
+        for pet in owned {
+            write_row(
+                &mut out,
+                &[&person_fields[..], &[pet.id.0.as_str(), pet.name.as_str()]].concat(),
+            );
+        }
+    }
+
+    out
+}
+
+/// parse_import reads the people to import from a CSV text with a header row,
+/// first_name and last_name are required, pet_name and id are optional and
+/// any other column is ignored. A row which is not valid fails alone.
+pub fn parse_import(text: &str) -> Result<Vec<Result<ImportRow, RowError>>, error::Error> {
+    // spreadsheets often save the file with a byte order mark.
+    let mut records = parse(text.trim_start_matches('\u{feff}'))?.into_iter();
+    let header = match records.next() {
+        Some(header) => header.fields,
+        None => {
+            return Err(error::Error::InvalidCsv(
+                "header row is missing".to_string(),
+            ))
+        }
+    };
+
+    let column = |name: &str| header.iter().position(|column| column.trim() == name);
+    let (first_name, last_name) = match (column("first_name"), column("last_name")) {
+        (Some(first_name), Some(last_name)) => (first_name, last_name),
+        _ => {
+            return Err(error::Error::InvalidCsv(
+                "first_name and last_name columns are required".to_string(),
+            ))
+        }
+    };
+    let id = column("id");
+    let pet_name = column("pet_name");
+
+    let mut rows: Vec<Result<ImportRow, RowError>> = Vec::new();
+    let mut rows_by_id: HashMap<String, usize> = HashMap::new();
+
+    for record in records {
+        let value = |index: usize| {
+            record
+                .fields
+                .get(index)
+                .map(|field| field.trim())
+                .unwrap_or("")
+        };
+        let row_id = id
+            .map(value)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string);
+        let pet = pet_name
+            .map(value)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+
+        if record.fields.len() != header.len() {
+            rows.push(Err(RowError {
+                line: record.line,
+                error: format!(
+                    "expected {} fields but found {}",
+                    header.len(),
+                    record.fields.len()
+                ),
+            }));
+            continue;
+        }
+
+        if value(first_name).is_empty() || value(last_name).is_empty() {
+            rows.push(Err(RowError {
+                line: record.line,
+                error: "first_name and last_name cannot be empty".to_string(),
+            }));
+            continue;
+        }
+
+        // the other rows of an exported person only add a pet.
+        let same_person = row_id.as_ref().and_then(|row_id| rows_by_id.get(row_id));
+        if let Some(Ok(row)) = same_person.map(|index| &mut rows[*index]) {
+            row.pet_names.extend(pet);
+            continue;
+        }
+        if let Some(row_id) = row_id {
+            rows_by_id.insert(row_id, rows.len());
+        }
+
+        rows.push(Ok(ImportRow {
+            line: record.line,
+            new_person: NewPerson::new(value(first_name).to_string(), value(last_name).to_string()),
+            pet_names: pet.into_iter().collect(),
+        }));
+    }
+
+    if rows.len() > MAX_BATCH_SIZE {
+        return Err(error::Error::BatchTooLarge);
+    }
+
+    Ok(rows)
+}
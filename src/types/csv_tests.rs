@@ -0,0 +1,155 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod csv_tests {
+    use crate::errors::error;
+    use crate::types::csv;
+    use crate::types::people::{NewPerson, Person, PersonID};
+    use crate::types::pets::{Pet, PetID};
+
+    #[test]
+    fn test_parse() {
+        // Given
+        let text = "name,notes\r\n\"Luis, Fer\",\"said \"\"hi\"\"\nand left\"\n\nEsme,\n";
+        // When
+        let got = csv::parse(text).unwrap();
+        // Then
+        assert_eq!(
+            got,
+            vec![
+                csv::Record {
+                    line: 1,
+                    fields: vec!["name".to_string(), "notes".to_string()],
+                },
+                csv::Record {
+                    line: 2,
+                    fields: vec!["Luis, Fer".to_string(), "said \"hi\"\nand left".to_string()],
+                },
+                csv::Record {
+                    line: 5,
+                    fields: vec!["Esme".to_string(), "".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed_quote() {
+        // Given
+        let text = "name\n\"Luis\nEsme\n";
+        // When
+        let got = csv::parse(text);
+        // Then
+        assert!(matches!(got, Err(error::Error::InvalidCsv(_))));
+    }
+
+    #[test]
+    fn test_write_row() {
+        // Given
+        let mut out = String::new();
+        // When
+        csv::write_row(&mut out, &["Luis, Fer", "say \"hi\"", "=1+1", "Esme"]);
+        // Then
+        assert_eq!(out, "\"Luis, Fer\",\"say \"\"hi\"\"\",'=1+1,Esme\r\n");
+    }
+
+    #[test]
+    fn test_export_people_with_pets() {
+        // Given
+        let people = vec![
+            Person {
+                created_on: "2023-09-17T17:29:57Z".parse().unwrap(),
+                ..Person::new(
+                    PersonID("1".to_string()),
+                    "Luis".to_string(),
+                    "Luis".to_string(),
+                )
+            },
+            Person {
+                created_on: "2023-09-17T17:31:02Z".parse().unwrap(),
+                ..Person::new(
+                    PersonID("2".to_string()),
+                    "Fernando".to_string(),
+                    "Fernando".to_string(),
+                )
+            },
+        ];
+        let pets = vec![
+            Pet {
+                id: PetID("10".to_string()),
+                name: "Firulais".to_string(),
+                person_id: Some(PersonID("1".to_string())),
+            },
+            Pet {
+                id: PetID("11".to_string()),
+                name: "Michi".to_string(),
+                person_id: Some(PersonID("1".to_string())),
+            },
+        ];
+        // When
+        let got = csv::export_people(&people, Some(&pets));
+        let got_without_pets = csv::export_people(&people, None);
+        // Then
+        assert_eq!(
+            got,
+            "id,first_name,last_name,created_on,version,pet_id,pet_name\r\n\
+             1,Luis,Luis,2023-09-17T17:29:57+00:00,1,10,Firulais\r\n\
+             1,Luis,Luis,2023-09-17T17:29:57+00:00,1,11,Michi\r\n\
+             2,Fernando,Fernando,2023-09-17T17:31:02+00:00,1,,\r\n"
+        );
+        assert_eq!(
+            got_without_pets,
+            "id,first_name,last_name,created_on,version\r\n\
+             1,Luis,Luis,2023-09-17T17:29:57+00:00,1\r\n\
+             2,Fernando,Fernando,2023-09-17T17:31:02+00:00,1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_import() {
+        // Given
+        let text = "\u{feff}id,first_name,last_name,pet_name,notes\n\
+                    1,Luis,Luis,Firulais,\n\
+                    1,Luis,Luis,Michi,\n\
+                    ,Esme,,,\n\
+                    ,Fernando,Fernando\n\
+                    ,Ana,Alba,,vip\n";
+        // When
+        let got = csv::parse_import(text).unwrap();
+        // Then
+        assert_eq!(
+            got,
+            vec![
+                Ok(csv::ImportRow {
+                    line: 2,
+                    new_person: NewPerson::new("Luis".to_string(), "Luis".to_string()),
+                    pet_names: vec!["Firulais".to_string(), "Michi".to_string()],
+                }),
+                Err(csv::RowError {
+                    line: 4,
+                    error: "first_name and last_name cannot be empty".to_string(),
+                }),
+                Err(csv::RowError {
+                    line: 5,
+                    error: "expected 5 fields but found 3".to_string(),
+                }),
+                Ok(csv::ImportRow {
+                    line: 6,
+                    new_person: NewPerson::new("Ana".to_string(), "Alba".to_string()),
+                    pet_names: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_import_without_name_columns() {
+        // Given
+        let text = "id,name\n1,Luis\n";
+        // When
+        let got = csv::parse_import(text);
+        let got_empty = csv::parse_import("");
+        // Then
+        assert!(matches!(got, Err(error::Error::InvalidCsv(_))));
+        assert!(matches!(got_empty, Err(error::Error::InvalidCsv(_))));
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::errors::error;
+use crate::types::people::Person;
+use crate::types::pets::Pet;
+
+/// EventKind is what happened to a person or a pet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventKind {
+    PersonCreated,
+    /// The person was updated, restored or merged into
+    PersonUpdated,
+    /// The person was deleted or merged into another one
+    PersonDeleted,
+    PetAdded,
+    /// The pet was renamed or has a new owner, or none
+    PetUpdated,
+    PetDeleted,
+}
+
+/// NewEvent is a domain event before it is saved in the outbox, it is saved
+/// together with the change it is about.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewEvent {
+    pub kind: EventKind,
+    /// The id of the person or pet the event is about
+    pub aggregate_id: String,
+    /// The person or pet as it was saved
+    pub payload: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// DomainEvent is an event of the outbox, the ids follow the order in which
+/// the changes were saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainEvent {
+    pub id: i64,
+    pub kind: EventKind,
+    pub aggregate_id: String,
+    pub payload: serde_json::Value,
+    pub occurred_at: DateTime<Utc>,
+    /// None while the event is pending
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+impl NewEvent {
+    pub fn person(kind: EventKind, person: &Person) -> Self {
+        Self::new(kind, person.id.to_string(), person)
+    }
+
+    pub fn pet(kind: EventKind, pet: &Pet) -> Self {
+        Self::new(kind, pet.id.to_string(), pet)
+    }
+
+    fn new<T: Serialize>(kind: EventKind, aggregate_id: String, record: &T) -> Self {
+        NewEvent {
+            kind,
+            aggregate_id,
+            payload: serde_json::to_value(record).unwrap_or_default(),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// numbered returns the pending event of the outbox with the given id.
+    pub fn numbered(self, id: i64) -> DomainEvent {
+        DomainEvent {
+            id,
+            kind: self.kind,
+            aggregate_id: self.aggregate_id,
+            payload: self.payload,
+            occurred_at: self.occurred_at,
+            sent_at: None,
+        }
+    }
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::PersonCreated => "PersonCreated",
+            EventKind::PersonUpdated => "PersonUpdated",
+            EventKind::PersonDeleted => "PersonDeleted",
+            EventKind::PetAdded => "PetAdded",
+            EventKind::PetUpdated => "PetUpdated",
+            EventKind::PetDeleted => "PetDeleted",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = error::Error;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "PersonCreated" => Ok(EventKind::PersonCreated),
+            "PersonUpdated" => Ok(EventKind::PersonUpdated),
+            "PersonDeleted" => Ok(EventKind::PersonDeleted),
+            "PetAdded" => Ok(EventKind::PetAdded),
+            "PetUpdated" => Ok(EventKind::PetUpdated),
+            "PetDeleted" => Ok(EventKind::PetDeleted),
+            _ => Err(error::Error::UnknownEventKind(kind.to_string())),
+        }
+    }
+}
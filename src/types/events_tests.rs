@@ -0,0 +1,58 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod events_tests {
+    use crate::errors::error;
+    use crate::types::events::{EventKind, NewEvent};
+    use crate::types::people::{Person, PersonID};
+    use crate::types::pets::{Pet, PetID};
+
+    #[test]
+    fn test_new_events() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let pet = Pet {
+            id: PetID("10".to_string()),
+            name: "You".to_string(),
+            person_id: Some(PersonID("1".to_string())),
+        };
+        // When
+        let got_person = NewEvent::person(EventKind::PersonCreated, &person).numbered(7);
+        let got_pet = NewEvent::pet(EventKind::PetAdded, &pet);
+        // Then
+        assert_eq!(got_person.id, 7);
+        assert_eq!(got_person.aggregate_id, "1");
+        assert_eq!(got_person.payload["last_name"], "Weatherwax");
+        assert_eq!(got_person.sent_at, None);
+        assert_eq!(got_pet.aggregate_id, "10");
+        assert_eq!(got_pet.payload["person_id"], "1");
+    }
+
+    #[test]
+    fn test_event_kind_from_str() {
+        // Given
+        let kinds = [
+            "PersonCreated",
+            "PersonUpdated",
+            "PersonDeleted",
+            "PetAdded",
+            "PetUpdated",
+            "PetDeleted",
+        ];
+        // When
+        let got: Vec<String> = kinds
+            .iter()
+            .map(|kind| kind.parse::<EventKind>().unwrap().to_string())
+            .collect();
+        let got_unknown = "PetRenamed".parse::<EventKind>();
+        // Then
+        assert_eq!(got, kinds);
+        assert_eq!(
+            got_unknown,
+            Err(error::Error::UnknownEventKind("PetRenamed".to_string()))
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::errors::error;
+use crate::types::people::Person;
+
+/// PersonVersion is a person as they were saved at a given moment, every
+/// change of a person saves a new version of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonVersion {
+    pub saved_at: DateTime<Utc>,
+    pub person: Person,
+}
+
+/// FieldChange is a field of a person whose value is not the same as in the
+/// previous version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    /// Null when the previous version did not have the field
+    pub from: serde_json::Value,
+    /// Null when the field was removed
+    pub to: serde_json::Value,
+}
+
+/// PersonRevision is a version of a person together with what changed from the
+/// previous version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonRevision {
+    pub saved_at: DateTime<Utc>,
+    pub person: Person,
+    pub changes: Vec<FieldChange>,
+}
+
+impl PersonVersion {
+    /// new returns the version of the person saved now.
+    pub fn new(person: Person) -> Self {
+        PersonVersion {
+            saved_at: Utc::now(),
+            person,
+        }
+    }
+}
+
+/// seen_at returns the person of a version saved at or before the moment as
+/// they were seen then, a person deleted afterwards was not deleted yet.
+pub fn seen_at(person: Person, at: DateTime<Utc>) -> Person {
+    match person.deleted_at {
+        Some(deleted_at) if deleted_at > at => Person {
+            deleted_at: None,
+            ..person
+        },
+        _ => person,
+    }
+}
+
+/// person_as_of returns the person as they were at the moment, from their
+/// versions oldest first, or None when they were not added yet.
+pub fn person_as_of(versions: &[PersonVersion], at: DateTime<Utc>) -> Option<Person> {
+    versions
+        .iter()
+        .rev()
+        .find(|version| version.saved_at <= at)
+        .map(|version| seen_at(version.person.clone(), at))
+}
+
+/// revisions returns every version, oldest first, with the fields changed
+/// from the previous one. The first version has no changes.
+pub fn revisions(versions: Vec<PersonVersion>) -> Vec<PersonRevision> {
+    let mut previous: Option<Person> = None;
+    versions
+        .into_iter()
+        .map(|version| {
+            let changes = previous
+                .as_ref()
+                .map(|previous| changes(previous, &version.person))
+                .unwrap_or_default();
+            previous = Some(version.person.clone());
+            PersonRevision {
+                saved_at: version.saved_at,
+                person: version.person,
+                changes,
+            }
+        })
+        .collect()
+}
+
+/// changes returns the fields whose values are not the same in both versions
+/// of the person, sorted by name. The version number itself is left out.
+pub fn changes(before: &Person, after: &Person) -> Vec<FieldChange> {
+    let fields = |person: &Person| match serde_json::to_value(person) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let (before, after) = (fields(before), fields(after));
+
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| *name != "version")
+        .filter_map(|name| {
+            let from = before.get(name).cloned().unwrap_or_default();
+            let to = after.get(name).cloned().unwrap_or_default();
+            (from != to).then(|| FieldChange {
+                field: name.clone(),
+                from,
+                to,
+            })
+        })
+        .collect()
+}
+
+/// extract_as_of reads the moment the data is read as of, an RFC 3339 time.
+pub fn extract_as_of(
+    params: &HashMap<String, String>,
+) -> Result<Option<DateTime<Utc>>, error::Error> {
+    params
+        .get("as_of")
+        .map(|as_of| {
+            DateTime::parse_from_rfc3339(as_of)
+                .map(|as_of| as_of.with_timezone(&Utc))
+                .map_err(|_| error::Error::InvalidAsOf(as_of.clone()))
+        })
+        .transpose()
+}
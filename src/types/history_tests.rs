@@ -0,0 +1,152 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod history_tests {
+    use crate::errors::error;
+    use crate::types::history::{
+        changes, extract_as_of, person_as_of, revisions, FieldChange, PersonVersion,
+    };
+    use crate::types::people::{Person, PersonID};
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn versions() -> Vec<PersonVersion> {
+        let added = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Ogg".to_string(),
+        );
+        let renamed = Person {
+            last_name: "Weatherwax".to_string(),
+            version: 2,
+            ..added.clone()
+        };
+        let deleted = Person {
+            deleted_at: Some(at("2026-10-12T00:00:00Z")),
+            ..renamed.clone()
+        };
+        vec![
+            PersonVersion {
+                saved_at: at("2026-10-01T00:00:00Z"),
+                person: added,
+            },
+            PersonVersion {
+                saved_at: at("2026-10-05T00:00:00Z"),
+                person: renamed,
+            },
+            PersonVersion {
+                saved_at: at("2026-10-12T00:00:00Z"),
+                person: deleted,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_person_as_of() {
+        // Given
+        let versions = versions();
+        // When
+        let got_before = person_as_of(&versions, at("2026-09-30T00:00:00Z"));
+        let got_added = person_as_of(&versions, at("2026-10-04T23:59:59Z"));
+        let got_renamed = person_as_of(&versions, at("2026-10-05T00:00:00Z"));
+        let got_deleted = person_as_of(&versions, at("2026-10-18T00:00:00Z"));
+        // Then
+        assert_eq!(got_before, None);
+        assert_eq!(got_added.unwrap().last_name, "Ogg");
+        let got_renamed = got_renamed.unwrap();
+        assert_eq!(got_renamed.last_name, "Weatherwax");
+        assert_eq!(got_renamed.deleted_at, None);
+        assert_eq!(
+            got_deleted.unwrap().deleted_at,
+            Some(at("2026-10-12T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_person_as_of_deleted_later() {
+        // Given
+        // people saved before there was a history start it as they are now.
+        let deleted = Person {
+            deleted_at: Some(at("2026-10-12T00:00:00Z")),
+            ..Person::new(
+                PersonID("1".to_string()),
+                "Esme".to_string(),
+                "Ogg".to_string(),
+            )
+        };
+        let versions = vec![PersonVersion {
+            saved_at: at("2026-10-01T00:00:00Z"),
+            person: deleted,
+        }];
+        // When
+        let got = person_as_of(&versions, at("2026-10-05T00:00:00Z"));
+        // Then
+        assert_eq!(got.unwrap().deleted_at, None);
+    }
+
+    #[test]
+    fn test_revisions() {
+        // When
+        let got = revisions(versions());
+        // Then
+        assert_eq!(got.len(), 3);
+        assert_eq!(got[0].changes, vec![]);
+        assert_eq!(
+            got[1].changes,
+            vec![FieldChange {
+                field: "last_name".to_string(),
+                from: json!("Ogg"),
+                to: json!("Weatherwax"),
+            }]
+        );
+        assert_eq!(
+            got[2].changes,
+            vec![FieldChange {
+                field: "deleted_at".to_string(),
+                from: json!(null),
+                to: json!("2026-10-12T00:00:00Z"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_changes_of_the_same_person() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Ogg".to_string(),
+        );
+        let updated = Person {
+            version: 7,
+            ..person.clone()
+        };
+        // When
+        let got = changes(&person, &updated);
+        // Then
+        assert_eq!(got, vec![]);
+    }
+
+    #[test]
+    fn test_extract_as_of() {
+        // Given
+        let params =
+            HashMap::from([("as_of".to_string(), "2026-10-18T10:00:00+02:00".to_string())]);
+        let bad_params = HashMap::from([("as_of".to_string(), "last week".to_string())]);
+        // When
+        let got = extract_as_of(&params);
+        let got_none = extract_as_of(&HashMap::new());
+        let got_bad = extract_as_of(&bad_params);
+        // Then
+        assert_eq!(got, Ok(Some(at("2026-10-18T08:00:00Z"))));
+        assert_eq!(got_none, Ok(None));
+        assert_eq!(
+            got_bad,
+            Err(error::Error::InvalidAsOf("last week".to_string()))
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::people::{Person, PersonID};
+
+/// MergeField is a field of a person whose value can be taken from the source
+/// of a merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeField {
+    FirstName,
+    LastName,
+    Email,
+    Phone,
+    BirthDate,
+    Addresses,
+}
+
+/// MergeRequest is the body to merge the source person into the person of the
+/// path, who survives the merge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeRequest {
+    pub source_id: PersonID,
+    /// The fields whose values come from the source, the surviving person
+    /// keeps the values of every other field.
+    #[serde(default)]
+    pub from_source: Vec<MergeField>,
+}
+
+/// Merge records that the source person was merged into the target, so the
+/// old id still leads to the surviving person.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Merge {
+    pub source_id: PersonID,
+    pub target_id: PersonID,
+    pub merged_at: DateTime<Utc>,
+}
+
+impl MergeRequest {
+    /// merged returns the target with the fields chosen from the source. The
+    /// contact details the target does not have are taken from the source too,
+    /// and a field the source does not have keeps the value of the target.
+    pub fn merged(&self, target: &Person, source: &Person) -> Person {
+        let mut person = target.clone();
+        let from_source = |field| self.from_source.contains(&field);
+
+        if from_source(MergeField::FirstName) {
+            person.first_name = source.first_name.clone();
+        }
+        if from_source(MergeField::LastName) {
+            person.last_name = source.last_name.clone();
+        }
+
+        let profile = &mut person.profile;
+        if from_source(MergeField::Email) || profile.email.is_none() {
+            profile.email = source.profile.email.clone().or(profile.email.take());
+        }
+        if from_source(MergeField::Phone) || profile.phone.is_none() {
+            profile.phone = source.profile.phone.clone().or(profile.phone.take());
+        }
+        if from_source(MergeField::BirthDate) || profile.birth_date.is_none() {
+            profile.birth_date = source.profile.birth_date.or(profile.birth_date);
+        }
+        if (from_source(MergeField::Addresses) || profile.addresses.is_empty())
+            && !source.profile.addresses.is_empty()
+        {
+            profile.addresses = source.profile.addresses.clone();
+        }
+
+        person
+    }
+}
+
+impl Merge {
+    pub fn new(source_id: PersonID, target_id: PersonID) -> Self {
+        Merge {
+            source_id,
+            target_id,
+            merged_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod merges_tests {
+    use crate::types::merges::{MergeField, MergeRequest};
+    use crate::types::people::{Person, PersonID};
+    use crate::types::profile::{Address, Profile};
+    use chrono::NaiveDate;
+
+    fn person(id: &str, first_name: &str, last_name: &str, profile: Profile) -> Person {
+        Person {
+            profile,
+            ..Person::new(
+                PersonID(id.to_string()),
+                first_name.to_string(),
+                last_name.to_string(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_merged_keeps_target_fields() {
+        // Given
+        let target = person(
+            "1",
+            "Esme",
+            "Weatherwax",
+            Profile {
+                email: Some("esme@anydomain.com".to_string()),
+                ..Default::default()
+            },
+        );
+        let source = person(
+            "2",
+            "Esmé",
+            "Wetherwax",
+            Profile {
+                email: Some("granny@anydomain.com".to_string()),
+                phone: Some("+34600000000".to_string()),
+                ..Default::default()
+            },
+        );
+        let request = MergeRequest {
+            source_id: source.id.clone(),
+            from_source: vec![],
+        };
+        // When
+        let got = request.merged(&target, &source);
+        // Then
+        assert_eq!(got.id, target.id);
+        assert_eq!(got.first_name, "Esme");
+        assert_eq!(got.last_name, "Weatherwax");
+        assert_eq!(got.profile.email, Some("esme@anydomain.com".to_string()));
+        assert_eq!(got.profile.phone, Some("+34600000000".to_string()));
+    }
+
+    #[test]
+    fn test_merged_takes_chosen_fields_from_source() {
+        // Given
+        let address = Address {
+            street: "Calle Mayor 1".to_string(),
+            city: "Madrid".to_string(),
+            region: None,
+            postal_code: None,
+            country: "ES".to_string(),
+        };
+        let target = person(
+            "1",
+            "Esme",
+            "Weatherwax",
+            Profile {
+                email: Some("esme@anydomain.com".to_string()),
+                birth_date: NaiveDate::from_ymd_opt(1990, 5, 17),
+                ..Default::default()
+            },
+        );
+        let source = person(
+            "2",
+            "Esmeralda",
+            "Wetherwax",
+            Profile {
+                email: Some("granny@anydomain.com".to_string()),
+                addresses: vec![address.clone()],
+                ..Default::default()
+            },
+        );
+        let request = MergeRequest {
+            source_id: source.id.clone(),
+            from_source: vec![
+                MergeField::FirstName,
+                MergeField::Email,
+                MergeField::BirthDate,
+            ],
+        };
+        // When
+        let got = request.merged(&target, &source);
+        // Then
+        assert_eq!(got.first_name, "Esmeralda");
+        assert_eq!(got.last_name, "Weatherwax");
+        assert_eq!(got.profile.email, Some("granny@anydomain.com".to_string()));
+        // the source has no birth date, so the one of the target is kept.
+        assert_eq!(got.profile.birth_date, NaiveDate::from_ymd_opt(1990, 5, 17));
+        assert_eq!(got.profile.addresses, vec![address]);
+    }
+}
@@ -1,5 +1,25 @@
 pub mod accounts;
+pub mod audit;
+mod audit_tests;
+pub mod batch;
+mod batch_tests;
+pub mod csv;
+mod csv_tests;
+pub mod events;
+mod events_tests;
+pub mod history;
+mod history_tests;
+pub mod merges;
+mod merges_tests;
 pub mod pagination;
 mod pagination_tests;
 pub mod people;
 pub mod pets;
+pub mod profile;
+mod profile_tests;
+pub mod query;
+mod query_tests;
+pub mod relationships;
+mod relationships_tests;
+pub mod webhooks;
+mod webhooks_tests;
@@ -1,30 +1,84 @@
 use crate::errors::error;
+use crate::types::people::{Person, PersonID};
+use crate::types::query::{Sort, SortValue};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Default, Debug, PartialEq)]
+/// The page size used when the request does not give a limit
+pub const DEFAULT_PAGE_SIZE: i32 = 20;
+/// The biggest page a request can ask for
+pub const MAX_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, PartialEq)]
 pub struct Pagination {
-    /// The index of the last item which has to be returned
-    pub limit: Option<i32>,
-    /// The index of the first item which has to be returned
-    pub offset: i32,
+    /// The number of items which have to be returned
+    pub limit: i32,
+    /// The position of the last item of the previous page
+    pub after: Option<Cursor>,
+}
+
+/// Cursor points to a person in the order people are sorted by, the id breaks
+/// ties, so a page starts at the same place while data changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort: Sort,
+    pub value: SortValue,
+    pub id: PersonID,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination {
+            limit: DEFAULT_PAGE_SIZE,
+            after: None,
+        }
+    }
+}
+
+impl Cursor {
+    /// new returns the cursor pointing to the given person in the given sort.
+    pub fn new(sort: Sort, person: &Person) -> Self {
+        Cursor {
+            sort,
+            value: sort.field.value_of(person),
+            id: person.id.clone(),
+        }
+    }
+
+    /// encode returns the cursor as an opaque token clients can send back.
+    pub fn encode(&self) -> String {
+        let position = serde_json::to_vec(self).expect("cursor is always serializable");
+        URL_SAFE_NO_PAD.encode(position)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, error::Error> {
+        URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|position| serde_json::from_slice(&position).ok())
+            .ok_or(error::Error::InvalidCursor)
+    }
 }
 
-pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination, error::Error> {
-    if params.contains_key("limit") && params.contains_key("offset") {
-        return Ok(Pagination {
-            limit: Some(
-                params
-                    .get("limit")
-                    .unwrap()
-                    .parse::<i32>()
-                    .map_err(error::Error::ParseError)?,
-            ),
-            offset: params
-                .get("offset")
-                .unwrap()
-                .parse::<i32>()
-                .map_err(error::Error::ParseError)?,
-        });
+pub fn extract_pagination(params: &HashMap<String, String>) -> Result<Pagination, error::Error> {
+    // pages start after a cursor, an offset would be silently ignored.
+    if params.contains_key("offset") {
+        return Err(error::Error::OffsetNotSupported);
     }
-    Err(error::Error::MissingParameters)
+
+    let limit = match params.get("limit") {
+        Some(limit) => limit
+            .parse::<i32>()
+            .map_err(error::Error::ParseError)?
+            .clamp(1, MAX_PAGE_SIZE),
+        None => DEFAULT_PAGE_SIZE,
+    };
+
+    let after = match params.get("cursor") {
+        Some(token) => Some(Cursor::decode(token)?),
+        None => None,
+    };
+
+    Ok(Pagination { limit, after })
 }
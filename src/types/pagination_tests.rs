@@ -1,61 +1,101 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod pagination_tests {
     use crate::errors::error;
     use crate::types::pagination;
+    use crate::types::people::PersonID;
+    use crate::types::query::{Sort, SortField, SortOrder, SortValue};
     use std::collections::HashMap;
 
     #[test]
     fn test_extract_pagination() {
         // Given
+        let cursor = pagination::Cursor {
+            sort: Sort {
+                field: SortField::LastName,
+                order: SortOrder::Desc,
+            },
+            value: SortValue::Text("Luis|Luis".to_string()),
+            id: PersonID("1".to_string()),
+        };
         let mut params: HashMap<String, String> = HashMap::new();
-        params.insert(String::from("offset"), String::from("0"));
+        params.insert(String::from("cursor"), cursor.encode());
         params.insert(String::from("limit"), String::from("10"));
 
         let expected_result: Result<pagination::Pagination, error::Error> =
             Ok(pagination::Pagination {
-                offset: 0,
-                limit: Some(10),
+                limit: 10,
+                after: Some(cursor),
             });
         // When
-        let got = pagination::extract_pagination(params);
+        let got = pagination::extract_pagination(&params);
         // Then
-        assert_eq!(got.unwrap(), expected_result.unwrap());
+        assert_eq!(got, expected_result);
     }
 
     #[test]
     fn test_extract_pagination_no_params() {
         // Given
         let params: HashMap<String, String> = HashMap::new();
-        let expected_result = error::Error::MissingParameters;
         // When
-        let got = pagination::extract_pagination(params);
+        let got = pagination::extract_pagination(&params);
         // Then
-        assert_eq!(got.is_err(), true);
-        match got {
-            Ok(v) => panic!("unexpected result {:?}", v),
-            Err(err) => assert_eq!(err, expected_result),
-        }
+        assert_eq!(got, Ok(pagination::Pagination::default()));
+        assert_eq!(got.unwrap().limit, pagination::DEFAULT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_extract_pagination_limit_is_capped() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("limit"), String::from("100000"));
+        // When
+        let got = pagination::extract_pagination(&params);
+        // Then
+        assert_eq!(got.unwrap().limit, pagination::MAX_PAGE_SIZE);
     }
 
     #[test]
     fn test_extract_pagination_invalid_params() {
         // Given
         let mut params: HashMap<String, String> = HashMap::new();
-        params.insert(String::from("offset"), String::from("a"));
-        params.insert(String::from("limit"), String::from("10"));
+        params.insert(String::from("limit"), String::from("a"));
 
         let expected_result: Result<pagination::Pagination, error::Error> = parse_int_error();
         // When
-        let got = pagination::extract_pagination(params);
+        let got = pagination::extract_pagination(&params);
         // Then
-        assert_eq!(got.is_err(), true);
+        assert!(got.is_err());
         assert_eq!(got, expected_result);
     }
 
+    #[test]
+    fn test_extract_pagination_invalid_cursor() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("cursor"), String::from("not a cursor"));
+        // When
+        let got = pagination::extract_pagination(&params);
+        // Then
+        assert_eq!(got, Err(error::Error::InvalidCursor));
+    }
+
+    #[test]
+    fn test_extract_pagination_rejects_offset() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("offset"), String::from("0"));
+        params.insert(String::from("limit"), String::from("10"));
+        // When
+        let got = pagination::extract_pagination(&params);
+        // Then
+        assert_eq!(got, Err(error::Error::OffsetNotSupported));
+    }
+
     fn parse_int_error() -> Result<pagination::Pagination, error::Error> {
         Ok(pagination::Pagination {
-            offset: "a".parse::<i32>().map_err(error::Error::ParseError)?,
-            limit: Some("10".parse::<i32>().map_err(error::Error::ParseError)?),
+            limit: "a".parse::<i32>().map_err(error::Error::ParseError)?,
+            after: None,
         })
     }
 }
@@ -1,3 +1,7 @@
+use crate::types::pagination::Cursor;
+use crate::types::pets::Pet;
+use crate::types::profile::Profile;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt,
@@ -10,12 +14,25 @@ pub struct Person {
     pub id: PersonID,
     pub first_name: String,
     pub last_name: String,
+    /// When the person was added, people are listed in this order.
+    #[serde(default = "Utc::now")]
+    pub created_on: DateTime<Utc>,
+    /// Incremented on every update, an update must carry the version it read.
+    #[serde(default)]
+    pub version: i32,
+    /// When the person was soft deleted, deleted people are hidden by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub profile: Profile,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct NewPerson {
     pub first_name: String,
     pub last_name: String,
+    #[serde(flatten)]
+    pub profile: Profile,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -26,14 +43,61 @@ pub struct SavePersonSuccess {
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct PersonID(pub String);
 
+/// PersonMatch is a person found by a search, the higher the score the
+/// better the names match.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PersonMatch {
+    #[serde(flatten)]
+    pub person: Person,
+    pub score: f32,
+}
+
+/// DuplicateCandidates is the body of a new person refused because they look
+/// like people already saved.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateCandidates {
+    pub error: String,
+    pub candidates: Vec<PersonMatch>,
+}
+
+/// PersonWithPets is a person and, when they were asked for, their pets.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PersonWithPets {
+    #[serde(flatten)]
+    pub person: Person,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pets: Option<Vec<Pet>>,
+}
+
+/// PeoplePage is a page of people and the cursor of the page after it, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeoplePage {
+    pub people: Vec<Person>,
+    pub next: Option<Cursor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PurgePeopleSuccess {
+    pub purged: u64,
+}
+
 impl Person {
     pub fn new(id: PersonID, first_name: String, last_name: String) -> Self {
         Person {
             id,
             first_name,
             last_name,
+            created_on: Utc::now(),
+            version: 1,
+            deleted_at: None,
+            profile: Profile::default(),
         }
     }
+
+    /// etag returns the version of the person as an HTTP entity tag.
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.version)
+    }
 }
 
 impl NewPerson {
@@ -41,6 +105,7 @@ impl NewPerson {
         NewPerson {
             first_name,
             last_name,
+            profile: Profile::default(),
         }
     }
 
@@ -49,6 +114,10 @@ impl NewPerson {
             id: PersonID(uuid::Uuid::new_v4().to_string()),
             first_name: self.first_name.clone(),
             last_name: self.last_name.clone(),
+            created_on: Utc::now(),
+            version: 1,
+            deleted_at: None,
+            profile: self.profile.clone(),
         }
     }
 }
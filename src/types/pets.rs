@@ -1,16 +1,19 @@
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, str::FromStr};
 
+use crate::errors::error;
 use crate::types::people::PersonID;
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct PetID(pub String);
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Pet {
     pub id: PetID,
     pub name: String,
-    pub person_id: PersonID,
+    /// The owner of the pet, none when the owner was deleted with the orphan
+    /// policy.
+    pub person_id: Option<PersonID>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -19,6 +22,39 @@ pub struct NewPet {
     pub person_id: PersonID,
 }
 
+/// PetName is the body to add or rename a pet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PetName {
+    pub name: String,
+}
+
+/// PetTransfer is the body to give a pet to another owner.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PetTransfer {
+    pub person_id: PersonID,
+}
+
+/// DeletePolicy is what happens to the pets of a person who is deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletePolicy {
+    /// The person is not deleted while they have pets
+    #[default]
+    Refuse,
+    /// The pets are deleted with their owner
+    Cascade,
+    /// The pets are kept without an owner
+    Orphan,
+}
+
+/// PetsBlockingDelete is the body of a delete refused because of the pets of
+/// the person.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PetsBlockingDelete {
+    pub error: String,
+    pub pets: Vec<Pet>,
+}
+
 impl NewPet {
     pub fn new(name: String, person_id: PersonID) -> Self {
         NewPet { name, person_id }
@@ -28,7 +64,7 @@ impl NewPet {
         Pet {
             id: PetID(uuid::Uuid::new_v4().to_string()),
             name: self.name.clone(),
-            person_id: self.person_id.clone(),
+            person_id: Some(self.person_id.clone()),
         }
     }
 }
@@ -38,3 +74,16 @@ impl fmt::Display for PetID {
         write!(f, "{}", self.0)
     }
 }
+
+impl FromStr for DeletePolicy {
+    type Err = error::Error;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "refuse" => Ok(DeletePolicy::Refuse),
+            "cascade" => Ok(DeletePolicy::Cascade),
+            "orphan" => Ok(DeletePolicy::Orphan),
+            _ => Err(error::Error::InvalidDeletePolicy),
+        }
+    }
+}
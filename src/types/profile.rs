@@ -0,0 +1,126 @@
+use crate::errors::error;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The longest email address SMTP can deliver to
+const MAX_EMAIL_LENGTH: usize = 254;
+/// The most digits an E.164 phone number has
+const MAX_PHONE_DIGITS: usize = 15;
+/// The most postal addresses a person can have
+pub const MAX_ADDRESSES: usize = 10;
+
+/// Profile is the optional contact details of a person.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// The phone number in E.164 format, like +34600000000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<Address>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    /// The ISO 3166-1 alpha-2 code of the country, like ES
+    pub country: String,
+}
+
+impl Profile {
+    /// validate checks every field of the profile, the error tells the first
+    /// field which is not valid.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if let Some(email) = &self.email {
+            if !is_email(email) {
+                return Err(invalid(format!("email {email} is not valid")));
+            }
+        }
+
+        if let Some(phone) = &self.phone {
+            if !is_e164(phone) {
+                return Err(invalid(format!(
+                    "phone {phone} is not in E.164 format, like +34600000000"
+                )));
+            }
+        }
+
+        if let Some(birth_date) = self.birth_date {
+            if birth_date > Utc::now().date_naive() {
+                return Err(invalid(format!("birth_date {birth_date} is in the future")));
+            }
+        }
+
+        if self.addresses.len() > MAX_ADDRESSES {
+            return Err(invalid(format!(
+                "a person can have {MAX_ADDRESSES} addresses at most"
+            )));
+        }
+
+        for (index, address) in self.addresses.iter().enumerate() {
+            address
+                .validate()
+                .map_err(|reason| invalid(format!("address {index} {reason}")))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Address {
+    fn validate(&self) -> Result<(), String> {
+        if self.street.trim().is_empty() || self.city.trim().is_empty() {
+            return Err("needs a street and a city".to_string());
+        }
+
+        let is_country_code =
+            self.country.len() == 2 && self.country.chars().all(|c| c.is_ascii_uppercase());
+        if !is_country_code {
+            return Err(format!(
+                "country {} is not an ISO 3166-1 alpha-2 code, like ES",
+                self.country
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(reason: String) -> error::Error {
+    error::Error::InvalidProfile(reason)
+}
+
+/// is_email only checks the shape of the address, whether it exists is only
+/// known by sending an email to it.
+fn is_email(email: &str) -> bool {
+    let (local, domain) = match email.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    email.len() <= MAX_EMAIL_LENGTH
+        && !local.is_empty()
+        && !email.chars().any(char::is_whitespace)
+        && !domain.contains('@')
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+fn is_e164(phone: &str) -> bool {
+    let digits = match phone.strip_prefix('+') {
+        Some(digits) => digits,
+        None => return false,
+    };
+
+    (2..=MAX_PHONE_DIGITS).contains(&digits.len())
+        && !digits.starts_with('0')
+        && digits.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod profile_tests {
+    use crate::errors::error;
+    use crate::types::profile::{Address, Profile, MAX_ADDRESSES};
+    use chrono::{Duration, NaiveDate, Utc};
+
+    fn address() -> Address {
+        Address {
+            street: "Calle Mayor 1".to_string(),
+            city: "Madrid".to_string(),
+            region: None,
+            postal_code: Some("28013".to_string()),
+            country: "ES".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        // Given
+        let profile = Profile {
+            email: Some("luis@anydomain.com".to_string()),
+            phone: Some("+34600000000".to_string()),
+            birth_date: NaiveDate::from_ymd_opt(1990, 5, 17),
+            addresses: vec![address()],
+        };
+        // When
+        let got = profile.validate();
+        let got_empty = Profile::default().validate();
+        // Then
+        assert_eq!(got, Ok(()));
+        assert_eq!(got_empty, Ok(()));
+    }
+
+    #[test]
+    fn test_validate_profile_email() {
+        for email in [
+            "luis",
+            "luis@",
+            "@anydomain.com",
+            "luis@anydomain",
+            "lu is@anydomain.com",
+        ] {
+            // Given
+            let profile = Profile {
+                email: Some(email.to_string()),
+                ..Default::default()
+            };
+            // When
+            let got = profile.validate();
+            // Then
+            assert!(
+                matches!(got, Err(error::Error::InvalidProfile(_))),
+                "{email} should not be valid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_profile_phone() {
+        for phone in [
+            "600000000",
+            "+0600000000",
+            "+34 600 000 000",
+            "+1234567890123456",
+        ] {
+            // Given
+            let profile = Profile {
+                phone: Some(phone.to_string()),
+                ..Default::default()
+            };
+            // When
+            let got = profile.validate();
+            // Then
+            assert!(
+                matches!(got, Err(error::Error::InvalidProfile(_))),
+                "{phone} should not be valid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_profile_birth_date_in_the_future() {
+        // Given
+        let profile = Profile {
+            birth_date: Some(Utc::now().date_naive() + Duration::days(1)),
+            ..Default::default()
+        };
+        // When
+        let got = profile.validate();
+        // Then
+        assert!(matches!(got, Err(error::Error::InvalidProfile(_))));
+    }
+
+    #[test]
+    fn test_validate_profile_addresses() {
+        // Given
+        let bad_country = Profile {
+            addresses: vec![
+                address(),
+                Address {
+                    country: "Spain".to_string(),
+                    ..address()
+                },
+            ],
+            ..Default::default()
+        };
+        let too_many = Profile {
+            addresses: vec![address(); MAX_ADDRESSES + 1],
+            ..Default::default()
+        };
+        // When
+        let got_bad_country = bad_country.validate();
+        let got_too_many = too_many.validate();
+        // Then
+        assert_eq!(
+            got_bad_country,
+            Err(error::Error::InvalidProfile(
+                "address 1 country Spain is not an ISO 3166-1 alpha-2 code, like ES".to_string()
+            ))
+        );
+        assert!(matches!(got_too_many, Err(error::Error::InvalidProfile(_))));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::errors::error;
+use crate::types::history;
+use crate::types::pagination::Cursor;
+use crate::types::people::Person;
+use crate::types::pets::DeletePolicy;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How many relationships away the graph of a person goes when not asked
+pub const DEFAULT_GRAPH_DEPTH: u32 = 1;
+/// The furthest the graph of a person can go
+pub const MAX_GRAPH_DEPTH: u32 = 5;
+
+/// PeopleQuery is how a listing of people is filtered and sorted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeopleQuery {
+    pub sort: Sort,
+    /// Every filter has to match for a person to be listed
+    pub filters: Vec<NameFilter>,
+    pub include_deleted: bool,
+    /// Read the people as they were at this moment instead of as they are now
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sort {
+    pub field: SortField,
+    pub order: SortOrder,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortField {
+    Id,
+    FirstName,
+    LastName,
+    #[default]
+    CreatedOn,
+    Version,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// SortValue is the value a person has in the field people are sorted by.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SortValue {
+    Text(String),
+    Int(i32),
+    Time(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameFilter {
+    pub field: NameField,
+    pub matching: NameMatch,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameField {
+    FirstName,
+    LastName,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameMatch {
+    Exact,
+    Prefix,
+    /// Case-insensitive
+    Contains,
+}
+
+impl PeopleQuery {
+    /// select returns, in the order of the query, up to limit of the people
+    /// matching it after the cursor.
+    pub fn select<'a>(
+        &self,
+        people: impl IntoIterator<Item = &'a Person>,
+        limit: i32,
+        after: Option<Cursor>,
+    ) -> Vec<Person> {
+        let sort = self.sort;
+        let position = |person: &Person| (sort.field.value_of(person), person.id.clone());
+        let after = after.map(|cursor| (cursor.value, cursor.id));
+        let is_after = |person: &Person| match (&after, sort.order) {
+            (None, _) => true,
+            (Some(after), SortOrder::Asc) => position(person) > *after,
+            (Some(after), SortOrder::Desc) => position(person) < *after,
+        };
+
+        let mut people: Vec<Person> = people
+            .into_iter()
+            .filter(|person| self.include_deleted || person.deleted_at.is_none())
+            .filter(|person| self.filters.iter().all(|filter| filter.matches(person)))
+            .filter(|person| is_after(person))
+            .cloned()
+            .collect();
+        people.sort_by_key(position);
+        if sort.order == SortOrder::Desc {
+            people.reverse();
+        }
+        people.truncate(limit.max(0) as usize);
+        people
+    }
+}
+
+impl SortField {
+    /// column returns the column of the people table for the field.
+    pub fn column(&self) -> &'static str {
+        match self {
+            SortField::Id => "id",
+            SortField::FirstName => "first_name",
+            SortField::LastName => "last_name",
+            SortField::CreatedOn => "created_on",
+            SortField::Version => "version",
+        }
+    }
+
+    pub fn value_of(&self, person: &Person) -> SortValue {
+        match self {
+            SortField::Id => SortValue::Text(person.id.to_string()),
+            SortField::FirstName => SortValue::Text(person.first_name.clone()),
+            SortField::LastName => SortValue::Text(person.last_name.clone()),
+            SortField::CreatedOn => SortValue::Time(person.created_on),
+            SortField::Version => SortValue::Int(person.version),
+        }
+    }
+}
+
+impl NameField {
+    pub fn column(&self) -> &'static str {
+        match self {
+            NameField::FirstName => "first_name",
+            NameField::LastName => "last_name",
+        }
+    }
+
+    pub fn value_of<'a>(&self, person: &'a Person) -> &'a str {
+        match self {
+            NameField::FirstName => &person.first_name,
+            NameField::LastName => &person.last_name,
+        }
+    }
+}
+
+impl NameFilter {
+    pub fn matches(&self, person: &Person) -> bool {
+        let name = self.field.value_of(person);
+        match self.matching {
+            NameMatch::Exact => name == self.value,
+            NameMatch::Prefix => name.starts_with(&self.value),
+            NameMatch::Contains => name.to_lowercase().contains(&self.value.to_lowercase()),
+        }
+    }
+
+    /// like_pattern returns the value as a LIKE pattern, escaping the
+    /// wildcards so they match themselves.
+    pub fn like_pattern(&self) -> String {
+        let escaped = self
+            .value
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        match self.matching {
+            NameMatch::Exact => escaped,
+            NameMatch::Prefix => format!("{escaped}%"),
+            NameMatch::Contains => format!("%{}%", escaped.to_lowercase()),
+        }
+    }
+}
+
+const NAME_FILTERS: [(&str, NameField, NameMatch); 6] = [
+    ("first_name", NameField::FirstName, NameMatch::Exact),
+    ("first_name_prefix", NameField::FirstName, NameMatch::Prefix),
+    (
+        "first_name_contains",
+        NameField::FirstName,
+        NameMatch::Contains,
+    ),
+    ("last_name", NameField::LastName, NameMatch::Exact),
+    ("last_name_prefix", NameField::LastName, NameMatch::Prefix),
+    (
+        "last_name_contains",
+        NameField::LastName,
+        NameMatch::Contains,
+    ),
+];
+
+pub fn extract_people_query(params: &HashMap<String, String>) -> Result<PeopleQuery, error::Error> {
+    let field = match params.get("sort").map(String::as_str) {
+        Some("id") => SortField::Id,
+        Some("first_name") => SortField::FirstName,
+        Some("last_name") => SortField::LastName,
+        Some("created_on") | None => SortField::CreatedOn,
+        Some("version") => SortField::Version,
+        Some(_) => return Err(error::Error::InvalidSort),
+    };
+
+    let order = match params.get("order").map(String::as_str) {
+        Some("asc") | None => SortOrder::Asc,
+        Some("desc") => SortOrder::Desc,
+        Some(_) => return Err(error::Error::InvalidSort),
+    };
+
+    let filters = NAME_FILTERS
+        .iter()
+        .filter_map(|(param, field, matching)| {
+            params.get(*param).map(|value| NameFilter {
+                field: *field,
+                matching: *matching,
+                value: value.clone(),
+            })
+        })
+        .collect();
+
+    Ok(PeopleQuery {
+        sort: Sort { field, order },
+        filters,
+        include_deleted: params
+            .get("include_deleted")
+            .is_some_and(|value| value == "true"),
+        as_of: history::extract_as_of(params)?,
+    })
+}
+
+/// extract_expand_pets tells whether the expand parameter, a comma separated
+/// list of relations, asks for the pets of every person.
+pub fn extract_expand_pets(params: &HashMap<String, String>) -> Result<bool, error::Error> {
+    let mut pets = false;
+    for relation in params
+        .get("expand")
+        .into_iter()
+        .flat_map(|expand| expand.split(','))
+    {
+        match relation.trim() {
+            "pets" => pets = true,
+            "" => {}
+            _ => return Err(error::Error::InvalidExpand),
+        }
+    }
+    Ok(pets)
+}
+
+/// extract_delete_policy returns the delete_policy parameter, if any.
+pub fn extract_delete_policy(
+    params: &HashMap<String, String>,
+) -> Result<Option<DeletePolicy>, error::Error> {
+    params
+        .get("delete_policy")
+        .map(|policy| policy.parse::<DeletePolicy>())
+        .transpose()
+}
+
+/// extract_graph_depth returns the depth parameter, or the default depth.
+pub fn extract_graph_depth(params: &HashMap<String, String>) -> Result<u32, error::Error> {
+    match params.get("depth") {
+        Some(depth) => match depth.parse::<u32>() {
+            Ok(depth) if depth <= MAX_GRAPH_DEPTH => Ok(depth),
+            _ => Err(error::Error::InvalidGraphDepth),
+        },
+        None => Ok(DEFAULT_GRAPH_DEPTH),
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod query_tests {
+    use crate::errors::error;
+    use crate::types::people::{Person, PersonID};
+    use crate::types::pets::DeletePolicy;
+    use crate::types::query::{self, NameField, NameFilter, NameMatch, PeopleQuery};
+    use crate::types::query::{Sort, SortField, SortOrder};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_extract_people_query() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("sort"), String::from("last_name"));
+        params.insert(String::from("order"), String::from("desc"));
+        params.insert(String::from("first_name_prefix"), String::from("Lu"));
+        params.insert(String::from("include_deleted"), String::from("true"));
+        params.insert(String::from("as_of"), String::from("2026-10-18T08:00:00Z"));
+
+        let expected_result = Ok(PeopleQuery {
+            sort: Sort {
+                field: SortField::LastName,
+                order: SortOrder::Desc,
+            },
+            filters: vec![NameFilter {
+                field: NameField::FirstName,
+                matching: NameMatch::Prefix,
+                value: "Lu".to_string(),
+            }],
+            include_deleted: true,
+            as_of: Some("2026-10-18T08:00:00Z".parse().unwrap()),
+        });
+        // When
+        let got = query::extract_people_query(&params);
+        // Then
+        assert_eq!(got, expected_result);
+    }
+
+    #[test]
+    fn test_extract_people_query_no_params() {
+        // Given
+        let params: HashMap<String, String> = HashMap::new();
+        // When
+        let got = query::extract_people_query(&params);
+        // Then
+        assert_eq!(got, Ok(PeopleQuery::default()));
+        assert_eq!(got.unwrap().sort.field, SortField::CreatedOn);
+    }
+
+    #[test]
+    fn test_extract_people_query_invalid_sort() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("sort"), String::from("password"));
+        let mut bad_order: HashMap<String, String> = HashMap::new();
+        bad_order.insert(String::from("order"), String::from("up"));
+        // When
+        let got = query::extract_people_query(&params);
+        let got_bad_order = query::extract_people_query(&bad_order);
+        // Then
+        assert_eq!(got, Err(error::Error::InvalidSort));
+        assert_eq!(got_bad_order, Err(error::Error::InvalidSort));
+    }
+
+    #[test]
+    fn test_extract_expand_pets() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("expand"), String::from("pets"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("expand"), String::from("pets,friends"));
+        // When
+        let got = query::extract_expand_pets(&params);
+        let got_none = query::extract_expand_pets(&HashMap::new());
+        let got_bad_params = query::extract_expand_pets(&bad_params);
+        // Then
+        assert_eq!(got, Ok(true));
+        assert_eq!(got_none, Ok(false));
+        assert_eq!(got_bad_params, Err(error::Error::InvalidExpand));
+    }
+
+    #[test]
+    fn test_extract_delete_policy() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("delete_policy"), String::from("orphan"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("delete_policy"), String::from("later"));
+        // When
+        let got = query::extract_delete_policy(&params);
+        let got_none = query::extract_delete_policy(&HashMap::new());
+        let got_bad_params = query::extract_delete_policy(&bad_params);
+        // Then
+        assert_eq!(got, Ok(Some(DeletePolicy::Orphan)));
+        assert_eq!(got_none, Ok(None));
+        assert_eq!(got_bad_params, Err(error::Error::InvalidDeletePolicy));
+    }
+
+    #[test]
+    fn test_extract_graph_depth() {
+        // Given
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("depth"), String::from("3"));
+        let mut bad_params: HashMap<String, String> = HashMap::new();
+        bad_params.insert(String::from("depth"), String::from("6"));
+        // When
+        let got = query::extract_graph_depth(&params);
+        let got_none = query::extract_graph_depth(&HashMap::new());
+        let got_bad_params = query::extract_graph_depth(&bad_params);
+        // Then
+        assert_eq!(got, Ok(3));
+        assert_eq!(got_none, Ok(query::DEFAULT_GRAPH_DEPTH));
+        assert_eq!(got_bad_params, Err(error::Error::InvalidGraphDepth));
+    }
+
+    #[test]
+    fn test_name_filter_matches() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Fernando".to_string(),
+            "Luis".to_string(),
+        );
+        let filter = |field, matching, value: &str| NameFilter {
+            field,
+            matching,
+            value: value.to_string(),
+        };
+        // When
+        let exact = filter(NameField::LastName, NameMatch::Exact, "Luis");
+        let wrong_case = filter(NameField::LastName, NameMatch::Exact, "luis");
+        let prefix = filter(NameField::FirstName, NameMatch::Prefix, "Fer");
+        let wrong_prefix = filter(NameField::FirstName, NameMatch::Prefix, "fer");
+        let contains = filter(NameField::FirstName, NameMatch::Contains, "NAN");
+        // Then
+        assert!(exact.matches(&person));
+        assert!(!wrong_case.matches(&person));
+        assert!(prefix.matches(&person));
+        assert!(!wrong_prefix.matches(&person));
+        assert!(contains.matches(&person));
+        assert_eq!(contains.like_pattern(), "%nan%");
+        assert_eq!(
+            filter(NameField::FirstName, NameMatch::Prefix, "50%_").like_pattern(),
+            "50\\%\\_%"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::errors::error;
+use crate::types::people::{Person, PersonID};
+
+/// RelationshipKind is what a person is to the related person, every kind has
+/// a reciprocal kind for the other direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationshipKind {
+    Parent,
+    Child,
+    Spouse,
+    Sibling,
+    Guardian,
+    /// The person under the care of a guardian
+    Ward,
+}
+
+/// Relationship tells that the person is the kind of the related person, like
+/// the parent of the related person.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relationship {
+    pub person_id: PersonID,
+    pub related_id: PersonID,
+    pub kind: RelationshipKind,
+}
+
+/// NewRelationship is the body to relate a person to another one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewRelationship {
+    pub related_id: PersonID,
+    pub kind: RelationshipKind,
+}
+
+/// GraphPerson is a person found walking the relationships, the depth is how
+/// many relationships away from the first person they are.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphPerson {
+    #[serde(flatten)]
+    pub person: Person,
+    pub depth: u32,
+}
+
+/// RelationshipGraph is the people reached from a person and the relationships
+/// followed to reach them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipGraph {
+    pub people: Vec<GraphPerson>,
+    pub relationships: Vec<Relationship>,
+}
+
+impl RelationshipKind {
+    pub fn reciprocal(&self) -> Self {
+        match self {
+            RelationshipKind::Parent => RelationshipKind::Child,
+            RelationshipKind::Child => RelationshipKind::Parent,
+            RelationshipKind::Spouse => RelationshipKind::Spouse,
+            RelationshipKind::Sibling => RelationshipKind::Sibling,
+            RelationshipKind::Guardian => RelationshipKind::Ward,
+            RelationshipKind::Ward => RelationshipKind::Guardian,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipKind::Parent => "parent",
+            RelationshipKind::Child => "child",
+            RelationshipKind::Spouse => "spouse",
+            RelationshipKind::Sibling => "sibling",
+            RelationshipKind::Guardian => "guardian",
+            RelationshipKind::Ward => "ward",
+        }
+    }
+}
+
+impl Relationship {
+    pub fn new(person_id: PersonID, new_relationship: NewRelationship) -> Self {
+        Relationship {
+            person_id,
+            related_id: new_relationship.related_id,
+            kind: new_relationship.kind,
+        }
+    }
+
+    /// reciprocal is the same relationship seen from the related person.
+    pub fn reciprocal(&self) -> Self {
+        Relationship {
+            person_id: self.related_id.clone(),
+            related_id: self.person_id.clone(),
+            kind: self.kind.reciprocal(),
+        }
+    }
+}
+
+impl fmt::Display for RelationshipKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for RelationshipKind {
+    type Err = error::Error;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "parent" => Ok(RelationshipKind::Parent),
+            "child" => Ok(RelationshipKind::Child),
+            "spouse" => Ok(RelationshipKind::Spouse),
+            "sibling" => Ok(RelationshipKind::Sibling),
+            "guardian" => Ok(RelationshipKind::Guardian),
+            "ward" => Ok(RelationshipKind::Ward),
+            _ => Err(error::Error::InvalidRelationship(format!(
+                "unknown kind {kind}"
+            ))),
+        }
+    }
+}
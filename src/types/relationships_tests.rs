@@ -0,0 +1,57 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod relationships_tests {
+    use crate::errors::error;
+    use crate::types::people::PersonID;
+    use crate::types::relationships::{Relationship, RelationshipKind};
+
+    #[test]
+    fn test_reciprocal_relationship() {
+        // Given
+        let relationships = [
+            (RelationshipKind::Parent, RelationshipKind::Child),
+            (RelationshipKind::Spouse, RelationshipKind::Spouse),
+            (RelationshipKind::Sibling, RelationshipKind::Sibling),
+            (RelationshipKind::Guardian, RelationshipKind::Ward),
+        ];
+        for (kind, want) in relationships {
+            let relationship = Relationship {
+                person_id: PersonID("1".to_string()),
+                related_id: PersonID("2".to_string()),
+                kind,
+            };
+            // When
+            let got = relationship.reciprocal();
+            // Then
+            assert_eq!(
+                got,
+                Relationship {
+                    person_id: PersonID("2".to_string()),
+                    related_id: PersonID("1".to_string()),
+                    kind: want,
+                }
+            );
+            assert_eq!(got.reciprocal(), relationship);
+        }
+    }
+
+    #[test]
+    fn test_parse_relationship_kind() {
+        // Given
+        let kinds = ["parent", "child", "spouse", "sibling", "guardian", "ward"];
+        // When
+        let got: Vec<String> = kinds
+            .iter()
+            .map(|kind| kind.parse::<RelationshipKind>().unwrap().to_string())
+            .collect();
+        let got_unknown = "cousin".parse::<RelationshipKind>();
+        // Then
+        assert_eq!(got, kinds);
+        assert_eq!(
+            got_unknown,
+            Err(error::Error::InvalidRelationship(
+                "unknown kind cousin".to_string()
+            ))
+        );
+    }
+}
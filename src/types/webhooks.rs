@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{fmt, str::FromStr};
+
+use crate::errors::error;
+use crate::types::events::{DomainEvent, EventKind};
+
+/// The shortest secret a webhook can be signed with
+pub const MIN_SECRET_LENGTH: usize = 16;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WebhookID(pub String);
+
+/// NewWebhook is the body to subscribe a URL to the domain events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewWebhook {
+    pub url: String,
+    /// The key the payloads sent to the URL are signed with
+    pub secret: String,
+    /// The kinds of events sent to the URL, every kind when empty
+    #[serde(default)]
+    pub events: Vec<EventKind>,
+}
+
+/// Webhook is a URL the domain events are posted to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: WebhookID,
+    pub url: String,
+    /// Empty once redacted, it is never returned to clients
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub secret: String,
+    pub events: Vec<EventKind>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// DeliveryStatus is how posting an event to a webhook ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// The receiver answered with a 2xx status
+    Delivered,
+    /// The receiver could not be reached or kept failing after the retries
+    Failed,
+}
+
+/// WebhookPayload is the body posted to a webhook for an event, it is the
+/// data of the live events too.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event_id: i64,
+    pub kind: EventKind,
+    pub aggregate_id: String,
+    pub occurred_at: DateTime<Utc>,
+    /// The person or pet as it was saved
+    pub data: serde_json::Value,
+}
+
+/// WebhookDelivery records one attempt to post an event to a webhook, the
+/// retries of a transient failure included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: WebhookID,
+    pub event_id: i64,
+    pub kind: EventKind,
+    /// The body which was posted, a redelivery posts it again
+    pub payload: serde_json::Value,
+    pub status: DeliveryStatus,
+    /// None when the receiver could not be reached
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub delivered_at: DateTime<Utc>,
+}
+
+impl NewWebhook {
+    /// validate checks the URL is absolute http or https and the secret is
+    /// long enough.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        let url = reqwest::Url::parse(&self.url)
+            .map_err(|e| error::Error::InvalidWebhook(format!("url {}: {e}", self.url)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(error::Error::InvalidWebhook(format!(
+                "url {} must be http or https",
+                self.url
+            )));
+        }
+        if self.secret.chars().count() < MIN_SECRET_LENGTH {
+            return Err(error::Error::InvalidWebhook(format!(
+                "secret must have at least {MIN_SECRET_LENGTH} characters"
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_webhook(&self) -> Webhook {
+        Webhook {
+            id: WebhookID(uuid::Uuid::new_v4().to_string()),
+            url: self.url.clone(),
+            secret: self.secret.clone(),
+            events: self.events.clone(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl Webhook {
+    /// accepts tells whether events of the given kind are sent to the webhook.
+    pub fn accepts(&self, kind: EventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+
+    /// redacted returns the webhook without its secret.
+    pub fn redacted(self) -> Self {
+        Webhook {
+            secret: String::new(),
+            ..self
+        }
+    }
+}
+
+impl From<&DomainEvent> for WebhookPayload {
+    fn from(event: &DomainEvent) -> Self {
+        WebhookPayload {
+            event_id: event.id,
+            kind: event.kind,
+            aggregate_id: event.aggregate_id.clone(),
+            occurred_at: event.occurred_at,
+            data: event.payload.clone(),
+        }
+    }
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookID {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = error::Error;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "delivered" => Ok(DeliveryStatus::Delivered),
+            "failed" => Ok(DeliveryStatus::Failed),
+            _ => Err(error::Error::InvalidWebhook(format!(
+                "unknown delivery status {status}"
+            ))),
+        }
+    }
+}
+
+/// sign returns the signature of the body with the secret, as sent in the
+/// X-Webhook-Signature header: sha256= followed by the hex HMAC-SHA256.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod webhooks_tests {
+    use crate::errors::error;
+    use crate::types::events::{EventKind, NewEvent};
+    use crate::types::people::{Person, PersonID};
+    use crate::types::webhooks::{sign, NewWebhook, WebhookPayload};
+
+    #[test]
+    fn test_sign() {
+        // Given
+        let secret = "Jefe";
+        let body = b"what do ya want for nothing?";
+        // When
+        let got = sign(secret, body);
+        // Then
+        assert_eq!(
+            got,
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_validate_new_webhook() {
+        // Given
+        let valid = NewWebhook {
+            url: "https://partner.example.com/hooks".to_string(),
+            secret: "a-long-enough-secret".to_string(),
+            events: vec![],
+        };
+        let not_http = NewWebhook {
+            url: "ftp://partner.example.com/hooks".to_string(),
+            ..valid.clone()
+        };
+        let relative = NewWebhook {
+            url: "/hooks".to_string(),
+            ..valid.clone()
+        };
+        let short_secret = NewWebhook {
+            secret: "short".to_string(),
+            ..valid.clone()
+        };
+        // When
+        let got = valid.validate();
+        let got_not_http = not_http.validate();
+        let got_relative = relative.validate();
+        let got_short_secret = short_secret.validate();
+        // Then
+        assert_eq!(got, Ok(()));
+        assert_eq!(
+            got_not_http,
+            Err(error::Error::InvalidWebhook(
+                "url ftp://partner.example.com/hooks must be http or https".to_string()
+            ))
+        );
+        assert!(matches!(got_relative, Err(error::Error::InvalidWebhook(_))));
+        assert_eq!(
+            got_short_secret,
+            Err(error::Error::InvalidWebhook(
+                "secret must have at least 16 characters".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_webhook_accepts() {
+        // Given
+        let new_webhook = NewWebhook {
+            url: "https://partner.example.com/hooks".to_string(),
+            secret: "a-long-enough-secret".to_string(),
+            events: vec![],
+        };
+        let all = new_webhook.to_webhook();
+        let pets = NewWebhook {
+            events: vec![EventKind::PetAdded, EventKind::PetDeleted],
+            ..new_webhook
+        }
+        .to_webhook();
+        // When
+        let got_all = all.accepts(EventKind::PersonCreated);
+        let got_pet = pets.accepts(EventKind::PetAdded);
+        let got_person = pets.accepts(EventKind::PersonCreated);
+        // Then
+        assert!(got_all);
+        assert!(got_pet);
+        assert!(!got_person);
+        assert_eq!(pets.redacted().secret, "");
+    }
+
+    #[test]
+    fn test_webhook_payload() {
+        // Given
+        let person = Person::new(
+            PersonID("1".to_string()),
+            "Esme".to_string(),
+            "Weatherwax".to_string(),
+        );
+        let event = NewEvent::person(EventKind::PersonUpdated, &person).numbered(3);
+        // When
+        let got = serde_json::to_value(WebhookPayload::from(&event)).unwrap();
+        // Then
+        assert_eq!(got["event_id"], 3);
+        assert_eq!(got["kind"], "PersonUpdated");
+        assert_eq!(got["aggregate_id"], "1");
+        assert_eq!(got["data"]["first_name"], "Esme");
+    }
+}
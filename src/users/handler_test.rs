@@ -27,7 +27,7 @@ mod handler_tests {
         // When
         let got = runtime.block_on(handler::register(new_account, account_service));
         // Then
-        assert_eq!(false, got.is_err());
+        assert!(got.is_ok());
 
         let got_result = match got {
             Ok(reply) => {
@@ -64,7 +64,7 @@ mod handler_tests {
         // When
         let got = runtime.block_on(handler::login(login, account_service));
         // Then
-        assert_eq!(false, got.is_err());
+        assert!(got.is_ok());
 
         let got_result = match got {
             Ok(reply) => {
@@ -79,10 +79,10 @@ mod handler_tests {
             Err(err) => panic!("unexpected error: {:?}", err),
         };
 
-        assert_eq!(true, !got_result.is_empty());
+        assert!(!got_result.is_empty());
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default)]
     struct DummyStore {
         add_account_id_value: Option<AccountID>,
         add_account_error: bool,
@@ -92,28 +92,18 @@ mod handler_tests {
 
     impl DummyStore {
         fn new_add_account(account_id: Option<AccountID>, is_error: bool) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.add_account_id_value = account_id;
-            dummy_store.add_account_error = is_error;
-
-            dummy_store
+            DummyStore {
+                add_account_id_value: account_id,
+                add_account_error: is_error,
+                ..Default::default()
+            }
         }
 
         fn new_with_login(is_error: bool, account: Account) -> Self {
-            let mut dummy_store = DummyStore::default();
-            dummy_store.login_error = Some(is_error);
-            dummy_store.get_account_value = Some(account);
-            dummy_store
-        }
-    }
-
-    impl Default for DummyStore {
-        fn default() -> DummyStore {
             DummyStore {
-                add_account_id_value: Default::default(),
-                add_account_error: Default::default(),
-                login_error: Default::default(),
-                get_account_value: Default::default(),
+                login_error: Some(is_error),
+                get_account_value: Some(account),
+                ..Default::default()
             }
         }
     }
@@ -16,9 +16,9 @@ fn test_create_account() {
     // When
     let got = runtime.block_on(account_service.add_account(new_account));
     // Then
-    assert_eq!(false, got.is_err());
+    assert!(got.is_ok());
     let id = got.unwrap();
-    assert_eq!(false, id.to_string().is_empty());
+    assert!(!id.to_string().is_empty());
 }
 
 #[test]
@@ -39,12 +39,12 @@ fn test_login() {
     // When
     let got = runtime.block_on(account_service.login(login));
     // Then
-    assert_eq!(false, got.is_err());
+    assert!(got.is_ok());
     let result = got.unwrap();
-    assert_eq!(true, !result.is_empty());
+    assert!(!result.is_empty());
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 struct DummyStore {
     login_error: Option<bool>,
     get_account_value: Option<Account>,
@@ -53,24 +53,16 @@ struct DummyStore {
 
 impl DummyStore {
     fn new_with_login(is_error: bool, account: Account) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.login_error = Some(is_error);
-        dummy_store.get_account_value = Some(account);
-        dummy_store
+        DummyStore {
+            login_error: Some(is_error),
+            get_account_value: Some(account),
+            ..Default::default()
+        }
     }
     fn new_with_add_account(is_error: bool) -> Self {
-        let mut dummy_store = DummyStore::default();
-        dummy_store.add_account_error = Some(is_error);
-        dummy_store
-    }
-}
-
-impl Default for DummyStore {
-    fn default() -> DummyStore {
         DummyStore {
-            login_error: Default::default(),
-            get_account_value: Default::default(),
-            add_account_error: Default::default(),
+            add_account_error: Some(is_error),
+            ..Default::default()
         }
     }
 }
@@ -84,7 +76,7 @@ impl storage::Storer for DummyStore {
         }
     }
 
-    async fn get_account(&self, email: String) -> Result<Account, error::Error> {
+    async fn get_account(&self, _email: String) -> Result<Account, error::Error> {
         match &self.login_error.unwrap() {
             false => Ok(self.get_account_value.clone().unwrap()),
             true => Err(error::Error::GetAccountError),